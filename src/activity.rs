@@ -0,0 +1,84 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+/// One line of the activity log
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// "YYYY-MM-DD HH:MM:SS" UTC
+    pub time: String,
+    /// What was done: "archive", "delete", "send", ...
+    pub action: String,
+    pub subject: String,
+    pub message_id: String,
+    /// Where the message went (folder or new file path), recipients, etc.
+    pub detail: String,
+}
+
+/// Append-only log of changes made to mail: one
+/// `<time>\t<action>\t<subject>\t<message id>\t<detail>` per line
+fn log_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|p| p.join("mailtui/activity.log"))
+}
+
+/// Tabs and newlines would break the line format
+fn clean(field: &str) -> String {
+    field.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Append an entry stamped with the current time. Logging is best effort:
+/// a failure to write never blocks the change itself
+pub fn record(action: &str, subject: &str, message_id: &str, detail: &str) {
+    let Some(path) = log_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let t = mail_parser::DateTime::from_timestamp(now);
+    let line = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}\t{}\t{}\t{}\t{}\n",
+        t.year,
+        t.month,
+        t.day,
+        t.hour,
+        t.minute,
+        t.second,
+        clean(action),
+        clean(subject),
+        clean(message_id),
+        clean(detail)
+    );
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+    {
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+/// The last `limit` entries, newest first
+pub fn recent(limit: usize) -> Vec<Entry> {
+    let Some(content) = log_path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            Some(Entry {
+                time: fields.next()?.to_string(),
+                action: fields.next()?.to_string(),
+                subject: fields.next()?.to_string(),
+                message_id: fields.next()?.to_string(),
+                detail: fields.next().unwrap_or("").to_string(),
+            })
+        })
+        .take(limit)
+        .collect()
+}
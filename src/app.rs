@@ -1,9 +1,22 @@
 use ratatui::{layout::Rect, widgets::ListState};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Receiver;
 use std::sync::Arc;
 use std::time::Instant;
 
-use crate::config::Config;
-use crate::mail::Envelope;
+use crate::config::{Config, ViewOptions};
+use crate::contacts::ContactEntry;
+use crate::keymap::{Action, Keymap};
+use crate::macros::Macros;
+use crate::mail::{
+    CalendarEvent, CleanupCandidate, DuplicateGroup, Envelope, FolderSize, MaildirProblem,
+    PreviewText,
+};
+use crate::receipts::{Receipt, ReceiptRules};
+use crate::tickets::TicketLinks;
+use crate::ui::PreviewLayout;
+use crate::view_prefs::ViewPrefs;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum View {
@@ -11,6 +24,16 @@ pub enum View {
     Search,
     DeepSearch,
     Compose,
+    Move,
+    Templates,
+    LinkHints,
+    Duplicates,
+    Cleanup,
+    Agenda,
+    Activity,
+    Diagnostics,
+    ThreadDebug,
+    OpenWith,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,29 +53,453 @@ pub struct App {
     pub status_message: Option<String>,
     pub search_query: String,
     pub is_search_results: bool,
+    // Reviewing the spam folder (shown like search results) and its size
+    pub junk_review: bool,
+    pub junk_count: usize,
     // Current account
     pub current_account: String,
     // Compose state
     pub compose: ComposeState,
+    // Move-to-folder prompt state
+    pub move_prompt: MovePrompt,
+    // Highlighted entry in the reply template picker
+    pub template_selection: usize,
+    // Hint label typed so far while picking a link with the keyboard
+    pub link_hint_input: String,
+    // Duplicate finder: scan in progress, results and highlighted group
+    pub duplicate_scan: Option<Receiver<Vec<DuplicateGroup>>>,
+    pub duplicates: Vec<DuplicateGroup>,
+    pub duplicate_selection: usize,
+    // Large/old mail cleanup view
+    pub cleanup: CleanupView,
+    // Calendar agenda: invite scan in progress, upcoming events and highlighted one
+    pub agenda_scan: Option<Receiver<Vec<CalendarEvent>>>,
+    pub agenda: Vec<CalendarEvent>,
+    pub agenda_selection: usize,
+    // Inbox unread count per account (in account name order), the count in
+    // progress and when the last one started
+    pub account_unread: Vec<(String, usize)>,
+    pub unread_check: Option<Receiver<Vec<(String, usize)>>>,
+    pub unread_checked_at: Option<std::time::Instant>,
+    // Current account's folder sizes, the count in progress (tagged with its
+    // account) and when the last one started
+    pub folder_sizes: Vec<FolderSize>,
+    pub size_check: Option<Receiver<(String, Vec<FolderSize>)>>,
+    pub size_checked_at: Option<std::time::Instant>,
+    // Activity log entries (newest first) and highlighted one
+    pub activity: Vec<crate::activity::Entry>,
+    pub activity_selection: usize,
+    // Threading explanation of the selected message's thread and highlighted row
+    pub thread_debug: Vec<crate::mail::ThreadDebugRow>,
+    pub thread_debug_selection: usize,
+    // Open-with menu for the previewed message's images and attachments
+    pub open_with: OpenWithMenu,
+    // External tool check shown by the diagnostics panel
+    pub diagnostics: Vec<crate::diagnostics::ToolStatus>,
     // Preview pane state
     pub preview_content: String,
-    pub preview_images: Vec<image::DynamicImage>,
     pub preview_image_states: Vec<ratatui_image::protocol::StatefulProtocol>,
+    // Preview images decoding on a worker thread: (envelope id, protocols)
+    pub image_decode: Option<Receiver<ImageBatch>>,
+    // Raw preview image data, kept to rebuild protocols after a resize
+    pub preview_image_data: Arc<Vec<Vec<u8>>>,
     pub preview_id: Option<String>,
     pub preview_scroll: u16,
+    // Preview scroll offsets by Message-ID, restored when a message is reopened
+    pub scroll_positions: HashMap<String, u16>,
     // Pane focus
     pub focused_pane: Pane,
     // Mouse tracking - pane areas
     pub list_area: Rect,
+    // List rows visible in the list pane, top to bottom (None = day divider)
+    pub list_rows: Vec<Option<usize>>,
     pub preview_area: Rect,
-    // Clickable URLs in preview: (row, col_start, col_end, url)
-    pub preview_urls: Vec<(u16, u16, u16, String)>,
+    // Third column of the wide layout (thread and attachments), when shown
+    pub info_area: Option<Rect>,
+    // Sender avatar image for the preview: (sender address, protocol)
+    pub preview_avatar: Option<(String, ratatui_image::protocol::StatefulProtocol)>,
+    // Sender whose gravatar is being fetched in the background
+    pub pending_avatar: Option<String>,
+    // Rows above the message text inside the preview (sender header)
+    pub preview_header_rows: u16,
+    // List rows shown in the preview's thread summary, top to bottom
+    pub thread_rows: Vec<usize>,
+    // Listed images/attachments in the preview: (source line, MIME part index)
+    pub preview_parts: Vec<(usize, usize)>,
+    // Message-ID of the message the previewed bounce reports on
+    pub preview_bounce_of: Option<String>,
+    // Order/total/tracking details found in the previewed message
+    pub preview_receipt: Option<Receipt>,
+    // Clickable URLs and wrapped-row mapping from the last render
+    pub preview_layout: PreviewLayout,
     // Debounced read marking: (message_id, opened_at)
     pub pending_read_mark: Option<(String, Instant)>,
-    // Inbox filter
+    // Debounced search preview: when the last `/` search keystroke was typed
+    pub pending_search_preview: Option<Instant>,
+    // Unread-only filter of the folder on screen
     pub show_unread_only: bool,
     // Send confirmation
     pub confirm_send: bool,
+    pub send_failure: Option<SendFailure>,
+    // Terminal focus (images and refreshes pause while unfocused)
+    pub terminal_focused: bool,
+    // When regaining focus last started a rescan
+    pub focus_reload_at: Option<Instant>,
+    // Showing the previous session's cached list while a scan runs in the background
+    pub stale: bool,
+    pub background_scan: Option<Receiver<anyhow::Result<Vec<Envelope>>>>,
+    pub scan_progress: Arc<ScanProgress>,
+    // The account's maildir is missing or empty of folders, with the expanded path
+    pub maildir_problem: Option<(MaildirProblem, String)>,
+    // List view key bindings and keys typed so far in a multi-key sequence
+    pub keymap: Keymap,
+    pub pending_keys: Vec<String>,
+    // Keyboard macro registers, recording and replay queue
+    pub macros: Macros,
+    // Repeated by `.`
+    pub last_change: Option<Change>,
+    // Envelope ids picked by "select all matching" for batch operations,
+    // and the batch operation waiting for y/n
+    pub bulk_selection: HashSet<String>,
+    pub confirm_bulk: Option<Change>,
+    pub bulk_job: Option<BulkJob>,
+    // Any other destructive action waiting for y/n
+    pub confirm: Option<Confirm>,
+    // Ticket ID patterns linked in the preview
+    pub tickets: TicketLinks,
+    // Receipt/shipping details extraction for the preview
+    pub receipts: ReceiptRules,
+    // Sender contact panel
+    pub show_contact_panel: bool,
+    // Sender/related narrowing on top of search and unread filters
+    pub list_filter: Option<ListFilter>,
+    // Address book lookups by sender address (None = not in address book)
+    pub contact_cache: HashMap<String, Option<ContactEntry>>,
+    // Address book query running on a worker thread: (address, entry)
+    pub contact_lookup: Option<Receiver<(String, Option<ContactEntry>)>>,
+    // External command (keys.commands) running on a worker thread
+    pub command_run: Option<Receiver<std::io::Result<std::process::Output>>>,
+    // Triage marks by thread root Message-ID, and whether the list is sorted by them
+    pub triage: HashMap<String, char>,
+    pub sort_by_triage: bool,
+    // Unread filter and triage sort remembered per account and folder
+    pub view_prefs: HashMap<String, ViewPrefs>,
+}
+
+/// Decoded preview images for one envelope id
+pub type ImageBatch = (String, Vec<ratatui_image::protocol::StatefulProtocol>);
+
+/// Decode images and build their render protocols on a worker thread
+fn spawn_image_decode(
+    id: String,
+    images: Arc<Vec<Vec<u8>>>,
+    picker: ratatui_image::picker::Picker,
+) -> Receiver<ImageBatch> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let states = images
+            .iter()
+            .filter_map(|data| image::load_from_memory(data).ok())
+            .map(|img| picker.new_resize_protocol(img))
+            .collect();
+        let _ = tx.send((id, states));
+    });
+    rx
+}
+
+/// Narrowing of the list to messages connected to one message
+#[derive(Debug, Clone)]
+pub enum ListFilter {
+    /// Messages from one sender address
+    Sender(String),
+    /// Messages in the same thread, from the same sender, or sharing a subject token
+    Related {
+        thread: Option<String>,
+        sender: Option<String>,
+        tokens: Vec<String>,
+    },
+}
+
+impl ListFilter {
+    pub fn sender_of(e: &Envelope) -> Option<Self> {
+        e.from.as_ref().map(|a| Self::Sender(a.addr.clone()))
+    }
+
+    pub fn related_to(e: &Envelope) -> Option<Self> {
+        Some(Self::Related {
+            thread: thread_root(e).map(|s| s.to_string()),
+            sender: e.from.as_ref().map(|a| a.addr.clone()),
+            tokens: subject_tokens(e.subject.as_deref().unwrap_or("")),
+        })
+    }
+
+    pub fn matches(&self, e: &Envelope) -> bool {
+        let same_sender = |sender: &str| {
+            e.from
+                .as_ref()
+                .is_some_and(|a| a.addr.eq_ignore_ascii_case(sender))
+        };
+        match self {
+            Self::Sender(sender) => same_sender(sender),
+            Self::Related {
+                thread,
+                sender,
+                tokens,
+            } => {
+                if thread.is_some() && thread.as_deref() == thread_root(e) {
+                    return true;
+                }
+                if sender.as_deref().is_some_and(same_sender) {
+                    return true;
+                }
+                let subject_tokens = subject_tokens(e.subject.as_deref().unwrap_or(""));
+                tokens.iter().any(|t| subject_tokens.contains(t))
+            }
+        }
+    }
+
+    /// Suffix for the list title
+    pub fn label(&self) -> String {
+        match self {
+            Self::Sender(sender) => crate::i18n::trf("title.from_sender", &[("sender", sender)]),
+            Self::Related { .. } => crate::i18n::tr("title.related").to_string(),
+        }
+    }
+}
+
+/// Message-ID at the root of an envelope's thread
+fn thread_root(e: &Envelope) -> Option<&str> {
+    e.references
+        .first()
+        .or(e.in_reply_to.as_ref())
+        .or(e.message_id.as_ref())
+        .map(|s| s.as_str())
+}
+
+/// Identifier-like words in a subject (order numbers, ticket IDs): 4+ characters
+/// containing a digit, so ordinary words and short numbers don't match everything
+fn subject_tokens(subject: &str) -> Vec<String> {
+    subject
+        .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '_')
+        .map(|w| w.trim_matches(|c| c == '-' || c == '_'))
+        .filter(|w| w.len() >= 4 && w.chars().any(|c| c.is_ascii_digit()))
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// The last change made to a message, repeated by `.`
+#[derive(Debug, Clone)]
+pub enum Change {
+    Action(Action),
+    /// Moved to this folder through the move prompt
+    MoveTo(String),
+}
+
+/// A destructive action asked about with y/n before it runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Confirm {
+    /// Move the cleanup view's targets to the trash
+    CleanupDelete,
+    /// Strip the attachments of the cleanup view's targets
+    CleanupStrip,
+    /// Delete the message selected in junk review without a trash copy
+    DeleteForever,
+    /// Delete the redundant copies of the highlighted duplicate group, or
+    /// of every group
+    DeleteDuplicates { all: bool },
+}
+
+/// A confirmed batch operation, applied a slice at a time from the main
+/// loop so the progress modal stays live and Esc can stop it
+pub struct BulkJob {
+    pub change: Change,
+    /// Envelope ids to apply it to, in list order
+    pub ids: Vec<String>,
+    /// Ids handled so far (applied or no longer listed)
+    pub done: usize,
+    /// Messages the change was applied to
+    pub applied: usize,
+}
+
+/// Files read so far by a background scan, out of the total; written by
+/// the scan thread, read by the help bar
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    pub done: AtomicUsize,
+    pub total: AtomicUsize,
+}
+
+/// One image or attachment offered by the open-with menu
+#[derive(Debug, Clone)]
+pub struct OpenWithPart {
+    /// Index into the message's MIME parts
+    pub part: usize,
+    pub filename: String,
+    pub content_type: String,
+}
+
+/// Open-with menu: pick an attachment (when there are several), then a
+/// configured handler, the default application or a command typed in
+#[derive(Debug, Clone, Default)]
+pub struct OpenWithMenu {
+    pub parts: Vec<OpenWithPart>,
+    /// Index into `parts` of the chosen attachment
+    pub chosen: Option<usize>,
+    /// Handlers for the chosen attachment's type
+    pub handlers: Vec<String>,
+    pub selection: usize,
+    /// Command being typed, once "Other command" is picked
+    pub command: Option<String>,
+}
+
+impl OpenWithMenu {
+    /// Rows in the list on screen: attachments, or handlers followed by the
+    /// default application and "Other command"
+    pub fn rows(&self) -> usize {
+        match self.chosen {
+            Some(_) => self.handlers.len() + 2,
+            None => self.parts.len(),
+        }
+    }
+}
+
+/// Folder picker for moving a message
+#[derive(Debug, Clone, Default)]
+pub struct MovePrompt {
+    pub query: String,
+    pub folders: Vec<String>,
+    /// Recent destinations, most recent first (numbered 1-5 in the prompt)
+    pub recent: Vec<String>,
+    pub selection: usize,
+}
+
+impl MovePrompt {
+    /// Folders to offer for the current query: recent destinations first when
+    /// the query is empty, otherwise fuzzy matches ranked prefix > substring > fuzzy
+    pub fn matches(&self) -> Vec<&str> {
+        if self.query.is_empty() {
+            let mut list: Vec<&str> = self.recent.iter().map(|s| s.as_str()).collect();
+            list.extend(
+                self.folders
+                    .iter()
+                    .filter(|f| !self.recent.contains(f))
+                    .map(|s| s.as_str()),
+            );
+            return list;
+        }
+
+        let query = self.query.to_lowercase();
+        let mut ranked: Vec<(u8, usize, &str)> = self
+            .folders
+            .iter()
+            .filter_map(|f| {
+                let lower = f.to_lowercase();
+                // Match against the last path component too ("Trash" for "[Gmail]/Trash")
+                let leaf = lower.rsplit('/').next().unwrap_or(&lower);
+                let rank = if leaf.starts_with(&query) || lower.starts_with(&query) {
+                    0
+                } else if lower.contains(&query) {
+                    1
+                } else if fuzzy_match(&lower, &query) {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, f.len(), f.as_str()))
+            })
+            .collect();
+        ranked.sort();
+        ranked.into_iter().map(|(_, _, f)| f).collect()
+    }
+
+    /// Folder under the cursor, or the typed name when nothing matches
+    pub fn selected(&self) -> Option<String> {
+        let matches = self.matches();
+        match matches.get(self.selection) {
+            Some(f) => Some(f.to_string()),
+            None if !self.query.trim().is_empty() => Some(self.query.trim().to_string()),
+            None => None,
+        }
+    }
+
+    pub fn next(&mut self) {
+        let len = self.matches().len();
+        if len > 0 {
+            self.selection = (self.selection + 1) % len;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let len = self.matches().len();
+        if len > 0 {
+            self.selection = (self.selection + len - 1) % len;
+        }
+    }
+}
+
+/// Cleanup view: big old messages with a cursor and a set of marked ids
+#[derive(Debug, Clone, Default)]
+pub struct CleanupView {
+    pub candidates: Vec<CleanupCandidate>,
+    pub selection: usize,
+    pub marked: HashSet<String>,
+}
+
+impl CleanupView {
+    pub fn next(&mut self) {
+        if self.selection + 1 < self.candidates.len() {
+            self.selection += 1;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        self.selection = self.selection.saturating_sub(1);
+    }
+
+    /// Mark or unmark the highlighted message and move on
+    pub fn toggle_mark(&mut self) {
+        if let Some(c) = self.candidates.get(self.selection) {
+            if !self.marked.remove(&c.id) {
+                self.marked.insert(c.id.clone());
+            }
+            self.next();
+        }
+    }
+
+    /// Messages an action applies to: the marked ones, else the highlighted one
+    pub fn targets(&self) -> Vec<CleanupCandidate> {
+        if self.marked.is_empty() {
+            return self
+                .candidates
+                .get(self.selection)
+                .cloned()
+                .into_iter()
+                .collect();
+        }
+        self.candidates
+            .iter()
+            .filter(|c| self.marked.contains(&c.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Drop a deleted message from the view
+    pub fn remove(&mut self, id: &str) {
+        self.candidates.retain(|c| c.id != id);
+        self.marked.remove(id);
+        self.selection = self.selection.min(self.candidates.len().saturating_sub(1));
+    }
+}
+
+/// Why the send command failed: its exit code and what it printed,
+/// shown over the draft until dismissed
+#[derive(Debug, Clone)]
+pub struct SendFailure {
+    pub command: String,
+    /// None if the command couldn't be started or was killed by a signal
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -63,6 +510,8 @@ pub struct ComposeState {
     pub attachments: Vec<String>,
     pub attachment_selection: usize,
     pub reply_to_id: Option<String>,
+    /// Language detected in the message being replied to
+    pub language: Option<crate::language::Language>,
 }
 
 impl App {
@@ -73,8 +522,18 @@ impl App {
         }
 
         let filtered_indices: Vec<usize> = (0..envelopes.len()).collect();
+        let (keymap, mut config_errors) = Keymap::from_config(&config.keys);
+        let (tickets, ticket_errors) = TicketLinks::from_config(&config.links.tickets);
+        config_errors.extend(ticket_errors);
+        let (receipts, receipt_errors) = ReceiptRules::from_config(&config.receipts);
+        config_errors.extend(receipt_errors);
+        let scroll_positions = if config.preview.persist_scroll {
+            crate::scroll::load()
+        } else {
+            HashMap::new()
+        };
 
-        Self {
+        let mut app = Self {
             config,
             view: View::List,
             original_envelopes: envelopes.clone(),
@@ -82,24 +541,101 @@ impl App {
             filtered_indices,
             list_state,
             should_quit: false,
-            status_message: None,
+            status_message: config_errors.first().cloned(),
             search_query: String::new(),
             is_search_results: false,
+            junk_review: false,
+            junk_count: 0,
             current_account: account_name,
             compose: ComposeState::default(),
+            move_prompt: MovePrompt::default(),
+            template_selection: 0,
+            link_hint_input: String::new(),
+            duplicate_scan: None,
+            duplicates: Vec::new(),
+            duplicate_selection: 0,
+            agenda_scan: None,
+            agenda: Vec::new(),
+            agenda_selection: 0,
+            account_unread: Vec::new(),
+            unread_check: None,
+            unread_checked_at: None,
+            folder_sizes: Vec::new(),
+            size_check: None,
+            size_checked_at: None,
+            activity: Vec::new(),
+            activity_selection: 0,
+            thread_debug: Vec::new(),
+            thread_debug_selection: 0,
+            open_with: OpenWithMenu::default(),
+            diagnostics: Vec::new(),
+            cleanup: CleanupView::default(),
             preview_content: String::new(),
-            preview_images: Vec::new(),
+            image_decode: None,
+            preview_image_data: Arc::new(Vec::new()),
             preview_image_states: Vec::new(),
             preview_id: None,
             preview_scroll: 0,
+            scroll_positions,
             focused_pane: Pane::List,
             list_area: Rect::default(),
+            list_rows: Vec::new(),
             preview_area: Rect::default(),
-            preview_urls: Vec::new(),
+            info_area: None,
+            preview_avatar: None,
+            pending_avatar: None,
+            preview_header_rows: 0,
+            thread_rows: Vec::new(),
+            preview_parts: Vec::new(),
+            preview_bounce_of: None,
+            preview_receipt: None,
+            preview_layout: PreviewLayout::default(),
             pending_read_mark: None,
+            pending_search_preview: None,
             show_unread_only: false,
             confirm_send: false,
-        }
+            send_failure: None,
+            terminal_focused: true,
+            focus_reload_at: None,
+            stale: false,
+            background_scan: None,
+            scan_progress: Arc::default(),
+            maildir_problem: None,
+            keymap,
+            tickets,
+            receipts,
+            pending_keys: Vec::new(),
+            macros: Macros::default(),
+            last_change: None,
+            bulk_selection: HashSet::new(),
+            confirm_bulk: None,
+            confirm: None,
+            bulk_job: None,
+            show_contact_panel: false,
+            list_filter: None,
+            contact_cache: HashMap::new(),
+            contact_lookup: None,
+            command_run: None,
+            triage: crate::triage::load(),
+            sort_by_triage: false,
+            view_prefs: crate::view_prefs::load(),
+        };
+        app.restore_view_prefs();
+        // The restored unread filter applies from the first frame
+        app.apply_filter();
+        app.check_maildir();
+        app.update_junk_count();
+        app
+    }
+
+    /// Recount the current account's spam folder
+    pub fn update_junk_count(&mut self) {
+        self.junk_count = match (self.maildir(), self.account()) {
+            (Some(mail_dir), Some(account)) => {
+                crate::mail::count_folder(&shellexpand::tilde(mail_dir), &account.spam_folder)
+            }
+            _ => 0,
+        };
     }
 
     /// Get current account config
@@ -114,9 +650,12 @@ impl App {
             .filter(|s| !s.is_empty())
     }
 
-    /// Get current account's signature
-    pub fn signature(&self) -> Option<&str> {
-        self.account().and_then(|a| a.signature.as_deref())
+    /// Get current account's signature for the message being composed
+    /// (per-domain and per-language rules)
+    pub fn compose_signature(&self) -> Option<&str> {
+        let language = self.compose.language.as_ref().map(|l| l.tag.as_str());
+        self.account()
+            .and_then(|a| a.signature_for(&self.compose.to, language))
     }
 
     /// Get current account's signature delimiter
@@ -138,6 +677,24 @@ impl App {
             .unwrap_or("msmtp -t")
     }
 
+    /// Get current account's tag backend (e.g. "notmuch")
+    pub fn tags_backend(&self) -> Option<&str> {
+        self.account().and_then(|a| a.tags_backend.as_deref())
+    }
+
+    /// Space the current account's mail takes, once its folders are counted
+    pub fn mailbox_size(&self) -> Option<u64> {
+        let account = self.account()?;
+        if self.folder_sizes.is_empty() {
+            return None;
+        }
+        Some(crate::mail::account_size(
+            &self.folder_sizes,
+            &account.trash_folder,
+            &account.spam_folder,
+        ))
+    }
+
     /// Switch to the next account in the list, returns new account name if switched
     pub fn next_account(&mut self) -> Option<String> {
         let names = self.config.account_names();
@@ -150,9 +707,61 @@ impl App {
             .unwrap_or(0);
         let next_idx = (current_idx + 1) % names.len();
         self.current_account = names[next_idx].clone();
+        self.restore_view_prefs();
+        self.check_maildir();
+        // Sizes are per account: count the new one right away
+        self.folder_sizes.clear();
+        self.size_checked_at = None;
         Some(self.current_account.clone())
     }
 
+    /// Look for the current account's maildir, so a wrong path shows a
+    /// warning instead of an empty list
+    pub fn check_maildir(&mut self) {
+        let mail_dir = shellexpand::tilde(self.maildir().unwrap_or_default()).to_string();
+        self.maildir_problem = crate::mail::check_maildir(&mail_dir).map(|p| (p, mail_dir));
+    }
+
+    /// Key of the folder on screen in the remembered view settings
+    fn view_key(&self) -> String {
+        let folder = match self.account() {
+            Some(a) if self.junk_review => a.spam_folder.as_str(),
+            Some(a) => a.inbox_folder.as_str(),
+            None => "",
+        };
+        format!("{}/{}", self.current_account, folder)
+    }
+
+    /// Remember the unread filter and triage sort for the folder on screen
+    fn remember_view_prefs(&mut self) {
+        let prefs = ViewPrefs {
+            unread_only: self.show_unread_only,
+            sort_by_triage: self.sort_by_triage,
+        };
+        self.view_prefs.insert(self.view_key(), prefs);
+        let _ = crate::view_prefs::save(&self.view_prefs);
+    }
+
+    /// Bring back the unread filter and triage sort last used in the folder
+    /// on screen (callers reapply the filter)
+    pub fn restore_view_prefs(&mut self) {
+        let prefs = self
+            .view_prefs
+            .get(&self.view_key())
+            .copied()
+            .unwrap_or_default();
+        self.show_unread_only = prefs.unread_only;
+        self.sort_by_triage = prefs.sort_by_triage;
+    }
+
+    /// Show the spam folder's or the inbox's own view settings
+    pub fn set_junk_review(&mut self, on: bool) {
+        if self.junk_review != on {
+            self.junk_review = on;
+            self.restore_view_prefs();
+        }
+    }
+
     /// Schedule a message to be marked as read after delay
     pub fn schedule_read_mark(&mut self, id: String) {
         self.pending_read_mark = Some((id, Instant::now()));
@@ -176,13 +785,119 @@ impl App {
         self.pending_read_mark = None;
     }
 
+    /// Reload the preview once typing in search pauses, not on every key
+    pub fn schedule_search_preview(&mut self) {
+        self.pending_search_preview = Some(Instant::now());
+    }
+
+    /// Whether typing paused long enough to load the search preview (clears it)
+    pub fn check_pending_search_preview(&mut self) -> bool {
+        let due = self
+            .pending_search_preview
+            .is_some_and(|typed_at| typed_at.elapsed().as_millis() >= 150);
+        if due {
+            self.pending_search_preview = None;
+        }
+        due
+    }
+
+    /// Install images decoded for the current preview (call in main loop).
+    /// Returns true when images arrived
+    pub fn take_decoded_images(&mut self) -> bool {
+        let Some(ref rx) = self.image_decode else {
+            return false;
+        };
+        let (id, states) = match rx.try_recv() {
+            Ok(batch) => batch,
+            Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.image_decode = None;
+                return false;
+            }
+        };
+        self.image_decode = None;
+        if self.preview_id.as_ref() != Some(&id) {
+            return false;
+        }
+        self.preview_image_states = states;
+        true
+    }
+
+    /// Rebuild the preview's image protocols for new cell geometry (after a
+    /// resize); the old images stay up until the new ones are decoded
+    pub fn rebuild_preview_images(&mut self, picker: &ratatui_image::picker::Picker) {
+        let Some(id) = self.preview_id.clone() else {
+            return;
+        };
+        if self.preview_image_data.is_empty() {
+            return;
+        }
+        self.image_decode = Some(spawn_image_decode(
+            id,
+            self.preview_image_data.clone(),
+            picker.clone(),
+        ));
+    }
+
+    /// Whether the main loop needs to keep ticking (debounced read mark,
+    /// avatar download, background scan, image decode)
+    pub fn has_pending_work(&self) -> bool {
+        // Paused while the terminal is in the background
+        let when_focused = self.pending_avatar.is_some()
+            || self.image_decode.is_some()
+            || self.unread_check.is_some()
+            || self.size_check.is_some();
+        self.pending_read_mark.is_some()
+            || self.pending_search_preview.is_some()
+            || self.background_scan.is_some()
+            || self.duplicate_scan.is_some()
+            || self.agenda_scan.is_some()
+            || (self.terminal_focused && when_focused)
+            || self.contact_lookup.is_some()
+            || self.command_run.is_some()
+            || self.bulk_job.is_some()
+    }
+
+    /// How far the background scan is, in percent of files read, while one runs
+    pub fn scan_percent(&self) -> Option<usize> {
+        self.background_scan.as_ref()?;
+        let done = self.scan_progress.done.load(Ordering::Relaxed);
+        let total = self.scan_progress.total.load(Ordering::Relaxed);
+        Some((done * 100).checked_div(total).unwrap_or(0))
+    }
+
     pub fn refresh(&mut self, envelopes: Vec<Envelope>) {
+        // A fresh load supersedes any background scan still in flight
+        self.background_scan = None;
+        self.stale = false;
+        let selected_id = self.selected_envelope().map(|e| e.id.clone());
         self.envelopes = envelopes.clone();
         self.original_envelopes = envelopes;
         self.is_search_results = false;
+        self.set_junk_review(false);
         self.search_query.clear();
+        self.apply_filter_keeping(selected_id);
+        self.update_junk_count();
+        self.status_message = Some(crate::i18n::tr("status.refreshed").to_string());
+    }
+
+    /// Swap in freshly scanned envelopes, keeping the selected message selected.
+    /// While search results or the junk review are on screen only the list
+    /// they return to is replaced (callers re-run the search)
+    pub fn replace_envelopes(&mut self, envelopes: Vec<Envelope>) {
+        if self.is_search_results {
+            self.original_envelopes = envelopes;
+            self.update_junk_count();
+            return;
+        }
+        let selected_id = self.selected_envelope().map(|e| e.id.clone());
+        self.envelopes = envelopes.clone();
+        self.original_envelopes = envelopes;
+        self.is_search_results = false;
+        self.set_junk_review(false);
         self.apply_filter();
-        self.status_message = Some("Refreshed".to_string());
+        self.reselect(selected_id);
+        self.update_junk_count();
     }
 
     pub fn set_status(&mut self, msg: &str) {
@@ -200,6 +915,61 @@ impl App {
             .and_then(|&idx| self.envelopes.get(idx))
     }
 
+    /// Explain how the selected message's thread was put together, starting
+    /// on the selected message. False when nothing is selected
+    pub fn open_thread_debug(&mut self) -> bool {
+        let Some(&idx) = self
+            .list_state
+            .selected()
+            .and_then(|i| self.filtered_indices.get(i))
+        else {
+            return false;
+        };
+        self.thread_debug = crate::mail::explain_thread(&self.envelopes, idx);
+        let id = &self.envelopes[idx].id;
+        self.thread_debug_selection = self
+            .thread_debug
+            .iter()
+            .position(|row| &row.id == id)
+            .unwrap_or(0);
+        self.view = View::ThreadDebug;
+        true
+    }
+
+    /// List rows of the selected message's thread, in list order
+    pub fn thread_members(&self) -> Vec<usize> {
+        let Some(root) = self.selected_envelope().and_then(thread_root) else {
+            return Vec::new();
+        };
+        self.filtered_indices
+            .iter()
+            .enumerate()
+            .filter(|(_, idx)| self.envelopes.get(**idx).and_then(thread_root) == Some(root))
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    /// Select the next (or previous) message in the selected thread.
+    /// Returns true if the selection moved
+    pub fn step_in_thread(&mut self, forward: bool) -> bool {
+        let members = self.thread_members();
+        let Some(current) = self.list_state.selected() else {
+            return false;
+        };
+        let target = if forward {
+            members.into_iter().find(|&row| row > current)
+        } else {
+            members.into_iter().rev().find(|&row| row < current)
+        };
+        match target {
+            Some(row) => {
+                self.list_state.select(Some(row));
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn next(&mut self) {
         if self.filtered_indices.is_empty() {
             return;
@@ -277,6 +1047,22 @@ impl App {
         self.list_state.selected() != old_selected
     }
 
+    /// Whether a search or filter is narrowing the list
+    pub fn has_active_filter(&self) -> bool {
+        !self.search_query.is_empty()
+            || self.is_search_results
+            || self.show_unread_only
+            || self.list_filter.is_some()
+    }
+
+    /// Envelopes currently listed, in list order
+    pub fn listed_envelopes(&self) -> Vec<&Envelope> {
+        self.filtered_indices
+            .iter()
+            .filter_map(|&i| self.envelopes.get(i))
+            .collect()
+    }
+
     pub fn start_search(&mut self) {
         self.search_query.clear();
         self.view = View::Search;
@@ -285,21 +1071,145 @@ impl App {
     /// Toggle unread-only filter and recompute filtered_indices
     pub fn toggle_unread_filter(&mut self) {
         self.show_unread_only = !self.show_unread_only;
+        self.remember_view_prefs();
+        self.apply_filter();
+    }
+
+    /// Narrow the list using a filter built from the selected message, or clear
+    /// the current one. The selected message stays selected when still visible
+    pub fn toggle_list_filter(&mut self, make: fn(&Envelope) -> Option<ListFilter>) {
+        let selected_id = self.selected_envelope().map(|e| e.id.clone());
+        if self.list_filter.is_some() {
+            self.list_filter = None;
+        } else {
+            self.list_filter = self.selected_envelope().and_then(make);
+        }
         self.apply_filter();
+        self.reselect(selected_id);
+    }
+
+    /// Triage mark of an envelope's thread
+    pub fn triage_mark(&self, e: &Envelope) -> Option<char> {
+        thread_root(e).and_then(|root| self.triage.get(root).copied())
+    }
+
+    /// Set or clear the triage mark on the selected message's thread
+    pub fn set_triage(&mut self, mark: Option<char>) {
+        let Some(root) = self
+            .selected_envelope()
+            .and_then(thread_root)
+            .map(|s| s.to_string())
+        else {
+            return;
+        };
+        match mark {
+            Some(c) => self.triage.insert(root, c),
+            None => self.triage.remove(&root),
+        };
+        let _ = crate::triage::save(&self.triage);
+        if self.sort_by_triage {
+            let selected_id = self.selected_envelope().map(|e| e.id.clone());
+            self.apply_filter();
+            self.reselect(selected_id);
+        }
     }
 
-    /// Recompute filtered_indices based on current filters (unread + search query)
+    /// Toggle sorting marked threads to the top (1 before 2 before 3, unmarked last)
+    pub fn toggle_triage_sort(&mut self) {
+        let selected_id = self.selected_envelope().map(|e| e.id.clone());
+        self.sort_by_triage = !self.sort_by_triage;
+        self.remember_view_prefs();
+        self.apply_filter();
+        self.reselect(selected_id);
+    }
+
+    /// Select the envelope with this id if visible, otherwise the first row
+    fn reselect(&mut self, selected_id: Option<String>) {
+        let pos = selected_id.and_then(|id| {
+            self.filtered_indices
+                .iter()
+                .position(|&i| self.envelopes[i].id == id)
+        });
+        if let Some(pos) = pos {
+            self.list_state.select(Some(pos));
+        } else if !self.filtered_indices.is_empty() {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    /// Summarize what we know about the selected message's sender
+    pub fn sender_summary(&self) -> Option<crate::ui::ContactSummary> {
+        let from = self.selected_envelope()?.from.as_ref()?;
+        let mut messages: Vec<&Envelope> = self
+            .original_envelopes
+            .iter()
+            .filter(|e| {
+                e.from
+                    .as_ref()
+                    .is_some_and(|a| a.addr.eq_ignore_ascii_case(&from.addr))
+            })
+            .collect();
+        // Newest first; undated messages last
+        messages.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        let first_seen = messages
+            .iter()
+            .filter(|e| e.timestamp.is_some())
+            .min_by_key(|e| e.timestamp)
+            .and_then(|e| e.date.clone());
+        let recent = messages
+            .iter()
+            .take(5)
+            .map(|e| {
+                (
+                    e.date.clone().unwrap_or_default(),
+                    e.subject
+                        .clone()
+                        .unwrap_or_else(|| crate::i18n::tr("list.no_subject").to_string()),
+                )
+            })
+            .collect();
+
+        Some(crate::ui::ContactSummary {
+            name: from.name.clone().unwrap_or_else(|| from.addr.clone()),
+            addr: from.addr.clone(),
+            entry: self.contact_cache.get(&from.addr).cloned().flatten(),
+            message_count: messages.len(),
+            first_seen,
+            recent,
+        })
+    }
+
+    /// Recompute the listed rows (unread filter, search query and operators,
+    /// sender/related filter), keeping the selected message selected when it
+    /// is still listed
     pub fn apply_filter(&mut self) {
-        let query = self.search_query.to_lowercase();
+        let selected_id = self.selected_envelope().map(|e| e.id.clone());
+        self.apply_filter_keeping(selected_id);
+    }
+
+    /// Recompute the listed rows and select the envelope with `selected_id`
+    /// if it survived the filter, for callers that swap `envelopes` first
+    fn apply_filter_keeping(&mut self, selected_id: Option<String>) {
+        let (operators, text) = split_search_operators(&self.search_query);
+        let query = text.to_lowercase();
         self.filtered_indices = self
             .envelopes
             .iter()
             .enumerate()
             .filter(|(_, e)| {
-                // Apply unread filter
-                if self.show_unread_only && e.flags.contains(&"Seen".to_string()) {
+                // Apply unread filter (auto-replies never count as unread)
+                if self.show_unread_only && (e.is_auto || e.flags.contains(&"Seen".to_string())) {
+                    return false;
+                }
+                if !operators.iter().all(|op| matches_operator(e, op)) {
                     return false;
                 }
+                // Apply sender/related filter
+                if let Some(ref filter) = self.list_filter {
+                    if !filter.matches(e) {
+                        return false;
+                    }
+                }
                 // Apply search query if any
                 if query.is_empty() {
                     return true;
@@ -311,8 +1221,24 @@ impl App {
             .map(|(i, _)| i)
             .collect();
 
-        // Preserve selection if possible, otherwise reset
-        if let Some(selected) = self.list_state.selected() {
+        // Stable sort keeps each thread's messages together and in order,
+        // since every message in a thread shares its root's mark
+        if self.sort_by_triage {
+            let mut indices = std::mem::take(&mut self.filtered_indices);
+            indices.sort_by_key(|&i| self.triage_mark(&self.envelopes[i]).unwrap_or(char::MAX));
+            self.filtered_indices = indices;
+        }
+
+        // Follow the selected message to its new row; failing that keep the
+        // row position if it still exists, otherwise reset
+        let anchor = selected_id.and_then(|id| {
+            self.filtered_indices
+                .iter()
+                .position(|&i| self.envelopes[i].id == id)
+        });
+        if let Some(pos) = anchor {
+            self.list_state.select(Some(pos));
+        } else if let Some(selected) = self.list_state.selected() {
             if selected >= self.filtered_indices.len() {
                 if !self.filtered_indices.is_empty() {
                     self.list_state.select(Some(0));
@@ -326,90 +1252,198 @@ impl App {
     }
 
     pub fn cancel_search(&mut self) {
+        let selected_id = self.selected_envelope().map(|e| e.id.clone());
         self.search_query.clear();
         // Restore original envelopes if we were showing search results
         if self.is_search_results {
             self.envelopes = self.original_envelopes.clone();
             self.is_search_results = false;
+            self.set_junk_review(false);
         }
-        self.apply_filter();
+        self.apply_filter_keeping(selected_id);
         self.view = View::List;
     }
 
     pub fn set_search_results(&mut self, results: Vec<Envelope>) {
+        let selected_id = self.selected_envelope().map(|e| e.id.clone());
         self.envelopes = results;
         self.is_search_results = true;
-        self.apply_filter();
+        self.apply_filter_keeping(selected_id);
     }
 
     pub fn preview_scroll_down(&mut self) {
         self.preview_scroll = self.preview_scroll.saturating_add(3);
+        self.remember_scroll();
     }
 
     pub fn preview_scroll_up(&mut self) {
         self.preview_scroll = self.preview_scroll.saturating_sub(3);
+        self.remember_scroll();
+    }
+
+    /// Record the preview offset for the selected message
+    fn remember_scroll(&mut self) {
+        if let Some(mid) = self.selected_envelope().and_then(|e| e.message_id.clone()) {
+            self.scroll_positions.insert(mid, self.preview_scroll);
+        }
+    }
+
+    /// Offset to reopen a message at (0 if never scrolled)
+    fn saved_scroll(&self, env: &Envelope) -> u16 {
+        env.message_id
+            .as_ref()
+            .and_then(|mid| self.scroll_positions.get(mid))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Write scroll offsets to disk when `preview.persist_scroll` is set
+    pub fn save_scroll_positions(&self) {
+        if self.config.preview.persist_scroll {
+            let _ = crate::scroll::save(&self.scroll_positions);
+        }
+    }
+
+    /// View options for the selected message: [preview] defaults, overridden
+    /// by the [preview.senders] rule matching its sender
+    fn selected_view_options(&self) -> ViewOptions {
+        let from = self
+            .selected_envelope()
+            .and_then(|env| env.from.as_ref())
+            .map(|from| from.addr.as_str())
+            .unwrap_or("");
+        self.config.preview.view_options(from)
     }
 
     /// Load preview for currently selected envelope if not already loaded
-    /// The loader function receives the file_path (preferred) or id
-    pub fn load_preview_if_needed(&mut self, loader: impl FnOnce(&str) -> String) {
+    /// The loader function receives the file_path (preferred) or id, and
+    /// whether to show the HTML part over the plain text one
+    pub fn load_preview_if_needed(&mut self, loader: impl FnOnce(&str, bool) -> PreviewText) {
+        let view = self.selected_view_options();
         if let Some(env) = self.selected_envelope() {
             let id = env.id.clone();
             if self.preview_id.as_ref() != Some(&id) {
+                let scroll = self.saved_scroll(env);
                 // Use file_path if available, otherwise fall back to id
                 let path_or_id = env.file_path.as_deref().unwrap_or(&id);
-                self.preview_content = loader(path_or_id);
-                self.preview_images.clear();
+                let mut preview = loader(path_or_id, view.prefer_html);
+                if view.collapse_quotes {
+                    preview.collapse_quotes();
+                }
+                let subject = env.subject.clone();
+                self.preview_content = preview.text;
+                self.preview_parts = preview.parts;
+                self.preview_bounce_of = preview.bounce_of;
+                self.add_preview_banner(subject.as_deref());
+                self.preview_image_states.clear();
+                self.preview_image_data = Arc::new(Vec::new());
+                self.image_decode = None;
                 self.preview_id = Some(id);
-                self.preview_scroll = 0;
-                // Extract URLs for click handling
-                self.preview_urls = crate::ui::extract_urls(&self.preview_content);
+                self.preview_scroll = scroll;
+                // Hit boxes depend on wrapping; rendering fills them in
+                self.preview_layout = PreviewLayout::default();
             }
         } else {
             self.preview_content.clear();
-            self.preview_images.clear();
+            self.preview_image_states.clear();
+            self.preview_image_data = Arc::new(Vec::new());
+            self.image_decode = None;
             self.preview_id = None;
             self.preview_scroll = 0;
-            self.preview_urls.clear();
+            self.preview_parts.clear();
+            self.preview_bounce_of = None;
+            self.preview_receipt = None;
+            self.preview_layout = PreviewLayout::default();
         }
     }
 
-    /// Load preview with images for currently selected envelope
+    /// Load preview with images for currently selected envelope.
+    /// The loader returns the text and raw image data; images are decoded on a
+    /// worker thread and picked up by `take_decoded_images`. Senders whose
+    /// rule turns `load_images` off get the text and image list only
     pub fn load_preview_with_images(
         &mut self,
-        loader: impl FnOnce(&str) -> (String, Vec<image::DynamicImage>),
+        loader: impl FnOnce(&str, bool) -> (PreviewText, Vec<Vec<u8>>),
         picker: &ratatui_image::picker::Picker,
     ) {
+        let view = self.selected_view_options();
         if let Some(env) = self.selected_envelope() {
             let id = env.id.clone();
             if self.preview_id.as_ref() != Some(&id) {
+                let scroll = self.saved_scroll(env);
                 // Use file_path if available, otherwise fall back to id
                 let path_or_id = env.file_path.as_deref().unwrap_or(&id);
-                let (text, images) = loader(path_or_id);
-                self.preview_content = text;
-                // Create image states for rendering
-                self.preview_image_states = images
-                    .iter()
-                    .map(|img| picker.new_resize_protocol(img.clone()))
-                    .collect();
-                self.preview_images = images;
+                let (mut preview, mut images) = loader(path_or_id, view.prefer_html);
+                if view.collapse_quotes {
+                    preview.collapse_quotes();
+                }
+                if !view.load_images {
+                    images.clear();
+                }
+                let subject = env.subject.clone();
+                self.preview_content = preview.text;
+                self.preview_parts = preview.parts;
+                self.preview_bounce_of = preview.bounce_of;
+                self.add_preview_banner(subject.as_deref());
+                // Text shows now; images appear once decoded. Replacing the
+                // receiver drops any batch still decoding for the old message
+                self.preview_image_states.clear();
+                self.preview_image_data = Arc::new(images);
+                self.image_decode = if self.preview_image_data.is_empty() {
+                    None
+                } else {
+                    Some(spawn_image_decode(
+                        id.clone(),
+                        self.preview_image_data.clone(),
+                        picker.clone(),
+                    ))
+                };
                 self.preview_id = Some(id);
-                self.preview_scroll = 0;
-                // Extract URLs for click handling
-                self.preview_urls = crate::ui::extract_urls(&self.preview_content);
+                self.preview_scroll = scroll;
+                // Hit boxes depend on wrapping; rendering fills them in
+                self.preview_layout = PreviewLayout::default();
             }
         } else {
             self.preview_content.clear();
-            self.preview_images.clear();
             self.preview_image_states.clear();
+            self.preview_image_data = Arc::new(Vec::new());
+            self.image_decode = None;
             self.preview_id = None;
             self.preview_scroll = 0;
-            self.preview_urls.clear();
+            self.preview_parts.clear();
+            self.preview_bounce_of = None;
+            self.preview_receipt = None;
+            self.preview_layout = PreviewLayout::default();
+        }
+    }
+
+    /// Put summary lines above the message text: the subject's ticket IDs
+    /// (clickable like the ones in the body) and any receipt details
+    fn add_preview_banner(&mut self, subject: Option<&str>) {
+        let subject = subject.unwrap_or("");
+        let mut banner = Vec::new();
+        let ids = self.tickets.ids(subject);
+        if !ids.is_empty() {
+            banner.push(crate::i18n::trf(
+                "preview.tickets",
+                &[("ids", &ids.join("  "))],
+            ));
+        }
+        self.preview_receipt = self.receipts.extract(subject, &self.preview_content);
+        if let Some(receipt) = &self.preview_receipt {
+            banner.push(receipt_summary(receipt));
+        }
+        if banner.is_empty() {
+            return;
+        }
+        self.preview_content = format!("{}\n\n{}", banner.join("\n"), self.preview_content);
+        for (line, _) in &mut self.preview_parts {
+            *line += banner.len() + 1;
         }
     }
 
     /// Force reload preview (e.g., after navigation)
-    pub fn reload_preview(&mut self, loader: impl FnOnce(&str) -> String) {
+    pub fn reload_preview(&mut self, loader: impl FnOnce(&str, bool) -> PreviewText) {
         self.preview_id = None;
         self.load_preview_if_needed(loader);
     }
@@ -417,7 +1451,7 @@ impl App {
     /// Force reload preview with images
     pub fn reload_preview_with_images(
         &mut self,
-        loader: impl FnOnce(&str) -> (String, Vec<image::DynamicImage>),
+        loader: impl FnOnce(&str, bool) -> (PreviewText, Vec<Vec<u8>>),
         picker: &ratatui_image::picker::Picker,
     ) {
         self.preview_id = None;
@@ -433,23 +1467,164 @@ impl App {
                         env.flags.push("Seen".to_string());
                     }
                 }
+                self.recount_thread_unread(idx);
+            }
+        }
+    }
+
+    /// Recount the unread replies on the root of the thread holding
+    /// `envelopes[idx]`, after its read state changed locally. The list is in
+    /// thread order: the root is the nearest depth-0 row at or above it
+    fn recount_thread_unread(&mut self, idx: usize) {
+        let Some(root) = (0..=idx)
+            .rev()
+            .find(|&i| self.envelopes.get(i).is_some_and(|e| e.thread_depth == 0))
+        else {
+            return;
+        };
+        let unread = self.envelopes[root + 1..]
+            .iter()
+            .take_while(|e| e.thread_depth > 0)
+            .filter(|e| e.is_unread())
+            .count();
+        self.envelopes[root].unread_replies = unread;
+    }
+
+    /// Set read status of the selected message in local state, with its renamed file
+    pub fn set_current_read(&mut self, read: bool, file_path: String) {
+        if let Some(selected) = self.list_state.selected() {
+            if let Some(&idx) = self.filtered_indices.get(selected) {
+                if let Some(env) = self.envelopes.get_mut(idx) {
+                    env.flags.retain(|f| f != "Seen");
+                    if read {
+                        env.flags.push("Seen".to_string());
+                    }
+                    env.file_path = Some(file_path);
+                }
+                self.recount_thread_unread(idx);
+            }
+        }
+    }
+
+    /// Record locally that the message with envelope id `id` was answered
+    /// and now lives at `file_path`
+    pub fn set_replied(&mut self, id: &str, file_path: String) {
+        for env in self
+            .envelopes
+            .iter_mut()
+            .chain(self.original_envelopes.iter_mut())
+            .filter(|e| e.id == id)
+        {
+            if !env.is_replied() {
+                env.flags.push("Replied".to_string());
             }
+            env.file_path = Some(file_path.clone());
         }
     }
 
+    /// Back to the full list from search results, junk review or the sender
+    /// filter, with nothing selected, at the newest message
+    pub fn go_to_inbox(&mut self) {
+        self.bulk_selection.clear();
+        self.list_filter = None;
+        self.cancel_search();
+        self.list_state
+            .select((!self.filtered_indices.is_empty()).then_some(0));
+        self.focused_pane = Pane::List;
+    }
+
+    /// Put every listed message (after search and filters) in the bulk
+    /// selection, replacing it. Returns how many were selected
+    pub fn select_all_matching(&mut self) -> usize {
+        self.bulk_selection = self
+            .filtered_indices
+            .iter()
+            .filter_map(|&i| self.envelopes.get(i))
+            .map(|e| e.id.clone())
+            .collect();
+        self.bulk_selection.len()
+    }
+
+    /// Bulk-selected envelope ids in list order
+    pub fn bulk_selected_ids(&self) -> Vec<String> {
+        self.envelopes
+            .iter()
+            .filter(|e| self.bulk_selection.contains(&e.id))
+            .map(|e| e.id.clone())
+            .collect()
+    }
+
+    /// Move the cursor to the listed envelope with this id, returns false
+    /// if it isn't listed
+    pub fn select_envelope_id(&mut self, id: &str) -> bool {
+        let pos = self
+            .filtered_indices
+            .iter()
+            .position(|&i| self.envelopes[i].id == id);
+        if let Some(pos) = pos {
+            self.list_state.select(Some(pos));
+        }
+        pos.is_some()
+    }
+
+    /// Select the message with this Message-ID, returns false if it isn't in the list
+    pub fn select_message_id(&mut self, message_id: &str) -> bool {
+        let pos = self
+            .filtered_indices
+            .iter()
+            .position(|&i| self.envelopes[i].message_id.as_deref() == Some(message_id));
+        if let Some(pos) = pos {
+            self.list_state.select(Some(pos));
+        }
+        pos.is_some()
+    }
+
+    /// Take a message's flags and file from a fresh read of it, after another
+    /// program renamed or re-downloaded it. Its preview reloads on next use
+    pub fn refresh_envelope(&mut self, id: &str, fresh: &Envelope) {
+        for env in self
+            .envelopes
+            .iter_mut()
+            .chain(self.original_envelopes.iter_mut())
+            .filter(|e| e.id == id)
+        {
+            env.flags = fresh.flags.clone();
+            env.file_path = fresh.file_path.clone();
+        }
+        if let Some(idx) = self.envelopes.iter().position(|e| e.id == id) {
+            self.recount_thread_unread(idx);
+        }
+        if self.preview_id.as_deref() == Some(id) {
+            self.preview_id = None;
+        }
+    }
+
+    /// Drop a message from the list (after it was moved away on disk)
+    pub fn remove_envelope(&mut self, id: &str) {
+        self.envelopes.retain(|e| e.id != id);
+        self.original_envelopes.retain(|e| e.id != id);
+        // Keep the cursor on the row that took its place
+        if let Some(selected) = self.list_state.selected() {
+            let last = self.filtered_indices.len().saturating_sub(2);
+            self.list_state.select(Some(selected.min(last)));
+        }
+        self.apply_filter();
+    }
+
     /// Toggle read/unread status in local state, returns (id, is_now_read)
     pub fn toggle_current_read(&mut self) -> Option<(String, bool)> {
         if let Some(selected) = self.list_state.selected() {
             if let Some(&idx) = self.filtered_indices.get(selected) {
                 if let Some(env) = self.envelopes.get_mut(idx) {
                     let id = env.id.clone();
-                    if env.flags.contains(&"Seen".to_string()) {
-                        env.flags.retain(|f| f != "Seen");
-                        return Some((id, false));
-                    } else {
+                    let now_read = !env.flags.contains(&"Seen".to_string());
+                    if now_read {
                         env.flags.push("Seen".to_string());
-                        return Some((id, true));
+                    } else {
+                        env.flags.retain(|f| f != "Seen");
                     }
+                    self.recount_thread_unread(idx);
+                    return Some((id, now_read));
                 }
             }
         }
@@ -476,13 +1651,36 @@ impl App {
             && y < self.list_area.y + self.list_area.height
         {
             self.focused_pane = Pane::List;
-            // Calculate which row was clicked (accounting for border and scroll offset)
+            // Calculate which row was clicked (accounting for border and day dividers)
             let visual_row = y.saturating_sub(self.list_area.y + 1) as usize; // +1 for top border
-            let actual_row = visual_row + self.list_state.offset();
-            if actual_row < self.filtered_indices.len() {
-                self.list_state.select(Some(actual_row));
+            if let Some(&Some(actual_row)) = self.list_rows.get(visual_row) {
+                if actual_row < self.filtered_indices.len() {
+                    self.list_state.select(Some(actual_row));
+                    return true;
+                }
+            }
+        }
+        // Info column: thread rows jump to that message, attachments open
+        else if let Some(info) = self
+            .info_area
+            .filter(|a| x >= a.x && x < a.x + a.width && y > a.y && y < a.y + a.height)
+        {
+            let row = (y - info.y - 1) as usize;
+            let (thread_top, attachments_top) = crate::ui::info_rows(self.thread_rows.len());
+            if let Some(&list_row) = row
+                .checked_sub(thread_top)
+                .filter(|_| row < thread_top + self.thread_rows.len())
+                .and_then(|i| self.thread_rows.get(i))
+            {
+                self.list_state.select(Some(list_row));
                 return true;
             }
+            if let Some(&(_, part)) = row
+                .checked_sub(attachments_top)
+                .and_then(|i| self.preview_parts.get(i))
+            {
+                self.open_part(part);
+            }
         }
         // Check if click is in preview pane
         else if x >= self.preview_area.x
@@ -491,25 +1689,184 @@ impl App {
             && y < self.preview_area.y + self.preview_area.height
         {
             self.focused_pane = Pane::Preview;
-            // Check if click is on a URL
+            // Thread summary rows jump to that message (+1 for top border)
+            let header_row = y.saturating_sub(self.preview_area.y + 1) as usize;
+            if self.info_area.is_none()
+                && y > self.preview_area.y
+                && header_row < self.thread_rows.len()
+            {
+                self.list_state.select(Some(self.thread_rows[header_row]));
+                return true;
+            }
+            // Check if click is on a URL or a listed attachment
             if let Some(url) = self.get_url_at(x, y) {
-                let _ = std::process::Command::new("xdg-open")
-                    .arg(&url)
-                    .stdout(std::process::Stdio::null())
-                    .stderr(std::process::Stdio::null())
-                    .spawn();
+                crate::platform::open_url(&url);
+            } else if let Some(part) = self.get_part_at(y) {
+                self.open_part(part);
             }
         }
         false
     }
 
+    /// Labelled URLs currently visible in the preview text, in reading order
+    pub fn link_hints(&self) -> Vec<crate::ui::LinkHint> {
+        let text_top = self.preview_area.y + 1 + self.preview_header_rows;
+        let text_rows = self
+            .preview_area
+            .height
+            .saturating_sub(2 + self.preview_header_rows);
+        let visible = self.preview_scroll..self.preview_scroll.saturating_add(text_rows);
+
+        let mut starts: Vec<&crate::ui::UrlSpan> = Vec::new();
+        let mut prev: Option<&crate::ui::UrlSpan> = None;
+        for span in &self.preview_layout.urls {
+            // A URL wrapped onto the next row continues at column 0
+            let continues = prev
+                .is_some_and(|(row, _, _, url)| span.0 == row + 1 && span.1 == 0 && span.3 == *url);
+            if !continues && visible.contains(&span.0) {
+                starts.push(span);
+            }
+            prev = Some(span);
+        }
+
+        let labels = crate::ui::hint_labels(starts.len());
+        starts
+            .into_iter()
+            .zip(labels)
+            .map(|((row, col, _, url), label)| crate::ui::LinkHint {
+                label,
+                x: self.preview_area.x + 1 + col,
+                y: text_top + row - self.preview_scroll,
+                url: url.clone(),
+            })
+            .collect()
+    }
+
+    /// MIME part of the image/attachment entry at screen row `y`, if any
+    fn get_part_at(&self, y: u16) -> Option<usize> {
+        let text_top = self.preview_area.y + 1 + self.preview_header_rows;
+        if y < text_top {
+            return None;
+        }
+        let row = (y - text_top + self.preview_scroll) as usize;
+        let source_row = *self.preview_layout.source_rows.get(row)?;
+        self.preview_parts
+            .iter()
+            .find(|(line, _)| *line == source_row)
+            .map(|(_, part)| *part)
+    }
+
+    /// Save one image/attachment of the previewed message to Downloads and open it
+    fn open_part(&mut self, part: usize) {
+        self.open_part_with(part, None);
+    }
+
+    /// Save one image/attachment of the previewed message to Downloads and
+    /// open it with `command`, or the desktop's default application
+    pub fn open_part_with(&mut self, part: usize, command: Option<&str>) {
+        let Some(file_path) = self.selected_envelope().and_then(|e| e.file_path.clone()) else {
+            self.set_status(crate::i18n::tr("status.no_file_path"));
+            return;
+        };
+        let download_dir = dirs::download_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+        match crate::mail::save_part(&file_path, part, &download_dir) {
+            Ok(saved) => match command {
+                Some(command) => match crate::platform::open_with(command, &saved) {
+                    Ok(()) => self.set_status(&crate::i18n::trf(
+                        "status.opened_with",
+                        &[("file", &saved), ("command", &command)],
+                    )),
+                    Err(e) => self.set_status(&crate::i18n::trf("status.error", &[("error", &e)])),
+                },
+                None => {
+                    crate::platform::open_url(&saved);
+                    self.set_status(&crate::i18n::trf("status.part_saved", &[("file", &saved)]));
+                }
+            },
+            Err(e) => self.set_status(&crate::i18n::trf("status.error", &[("error", &e)])),
+        }
+    }
+
+    /// List the selected message's images and attachments in the open-with
+    /// menu. A single one is chosen right away
+    pub fn open_with_menu(&mut self) {
+        let Some(file_path) = self.selected_envelope().and_then(|e| e.file_path.clone()) else {
+            self.set_status(crate::i18n::tr("status.no_file_path"));
+            return;
+        };
+        let content = match crate::mail::read_message_content(&file_path, false) {
+            Ok(content) => content,
+            Err(e) => {
+                self.set_status(&crate::i18n::trf("status.error", &[("error", &e)]));
+                return;
+            }
+        };
+        let images = content.images.into_iter().map(|img| OpenWithPart {
+            part: img.part,
+            filename: img
+                .filename
+                .unwrap_or_else(|| crate::i18n::tr("open_with.unnamed").to_string()),
+            content_type: img.content_type,
+        });
+        let attachments = content.attachments.into_iter().map(|att| OpenWithPart {
+            part: att.part,
+            filename: att.filename,
+            content_type: att.content_type,
+        });
+        let parts: Vec<OpenWithPart> = images.chain(attachments).collect();
+        if parts.is_empty() {
+            self.set_status(crate::i18n::tr("status.no_attachments"));
+            return;
+        }
+        self.open_with = OpenWithMenu {
+            parts,
+            ..Default::default()
+        };
+        if self.open_with.parts.len() == 1 {
+            self.choose_open_with_part(0);
+        }
+        self.view = View::OpenWith;
+    }
+
+    /// Move the open-with menu on from the attachment list to the handlers
+    /// configured for the attachment at `index`
+    pub fn choose_open_with_part(&mut self, index: usize) {
+        let Some(part) = self.open_with.parts.get(index) else {
+            return;
+        };
+        self.open_with.handlers = self
+            .config
+            .open_with_handlers(&part.content_type)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        self.open_with.chosen = Some(index);
+        self.open_with.selection = 0;
+    }
+
+    /// Open the chosen attachment with `command` (None: the default
+    /// application) and close the menu
+    pub fn run_open_with(&mut self, command: Option<&str>) {
+        self.view = View::List;
+        let Some(part) = self
+            .open_with
+            .chosen
+            .and_then(|i| self.open_with.parts.get(i))
+            .map(|p| p.part)
+        else {
+            return;
+        };
+        self.open_part_with(part, command);
+    }
+
     /// Get URL at screen position if any
     fn get_url_at(&self, x: u16, y: u16) -> Option<String> {
         // Adjust for pane position and scroll
         let rel_x = x.saturating_sub(self.preview_area.x + 1); // +1 for border
-        let rel_y = y.saturating_sub(self.preview_area.y + 1) + self.preview_scroll;
+        let rel_y = y.saturating_sub(self.preview_area.y + 1 + self.preview_header_rows)
+            + self.preview_scroll;
 
-        for (row, col_start, col_end, url) in &self.preview_urls {
+        for (row, col_start, col_end, url) in &self.preview_layout.urls {
             if rel_y == *row && rel_x >= *col_start && rel_x < *col_end {
                 return Some(url.clone());
             }
@@ -549,6 +1906,17 @@ impl App {
         }
     }
 
+    /// Swap the selected attachment with the one below (or above), keeping
+    /// it selected, so the important file can go first
+    pub fn move_selected_attachment(&mut self, down: bool) {
+        let from = self.compose.attachment_selection;
+        let to = if down { from + 1 } else { from.wrapping_sub(1) };
+        if to < self.compose.attachments.len() {
+            self.compose.attachments.swap(from, to);
+            self.compose.attachment_selection = to;
+        }
+    }
+
     pub fn next_attachment(&mut self) {
         if !self.compose.attachments.is_empty() {
             self.compose.attachment_selection =
@@ -567,6 +1935,101 @@ impl App {
     }
 }
 
+/// "Order 123-456  ·  Total $42.00  ·  Tracking 1Z... (UPS)"
+fn receipt_summary(receipt: &Receipt) -> String {
+    use crate::i18n::trf;
+    let mut parts = Vec::new();
+    if let Some(order) = &receipt.order {
+        parts.push(trf("receipt.order", &[("order", order)]));
+    }
+    if let Some(total) = &receipt.total {
+        parts.push(trf("receipt.total", &[("total", total)]));
+    }
+    if let Some(tracking) = &receipt.tracking {
+        let mut part = trf("receipt.tracking", &[("number", &tracking.number)]);
+        if let Some(carrier) = tracking.carrier {
+            part.push_str(&format!(" ({})", carrier));
+        }
+        parts.push(part);
+    }
+    parts.join("  ·  ")
+}
+
+/// A `key:value` term of a search query
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchOperator {
+    /// `is:<name>`, e.g. `is:auto`
+    Is(String),
+    /// `from:<text>`: sender address or name contains the text
+    From(String),
+    /// `older:<age>` (e.g. `30d`, `2w`, `6m`, `1y`): dated before this
+    /// timestamp
+    Older(i64),
+}
+
+/// Split operators (`is:auto`, `from:newsletter@`, `older:1y`) out of a
+/// search query. Returns them and the remaining free text; a term whose
+/// value is empty or doesn't parse stays in the text
+pub fn split_search_operators(query: &str) -> (Vec<SearchOperator>, String) {
+    let mut operators = Vec::new();
+    let mut words = Vec::new();
+    for word in query.split_whitespace() {
+        let operator = match word.split_once(':') {
+            Some((_, "")) | None => None,
+            Some(("is", name)) => Some(SearchOperator::Is(name.to_lowercase())),
+            Some(("from", text)) => Some(SearchOperator::From(text.to_lowercase())),
+            Some(("older", age)) => age_cutoff(age).map(SearchOperator::Older),
+            Some(_) => None,
+        };
+        match operator {
+            Some(operator) => operators.push(operator),
+            None => words.push(word),
+        }
+    }
+    (operators, words.join(" "))
+}
+
+/// Whether `e` satisfies a search operator; unknown `is:` names match nothing
+pub fn matches_operator(e: &Envelope, operator: &SearchOperator) -> bool {
+    match operator {
+        SearchOperator::Is(name) => match name.as_str() {
+            "auto" => e.is_auto,
+            "replied" => e.is_replied(),
+            // Mail still waiting for an answer; our own sent mail is not
+            "unreplied" => !e.is_replied() && !e.is_sent,
+            _ => false,
+        },
+        SearchOperator::From(text) => e.from.as_ref().is_some_and(|from| {
+            from.addr.to_lowercase().contains(text)
+                || from
+                    .name
+                    .as_ref()
+                    .is_some_and(|name| name.to_lowercase().contains(text))
+        }),
+        SearchOperator::Older(cutoff) => e.timestamp.is_some_and(|t| t < *cutoff),
+    }
+}
+
+/// Timestamp `age` (a number and d/w/m/y) before now; None for an age
+/// too large to count back
+fn age_cutoff(age: &str) -> Option<i64> {
+    let unit = age.chars().last()?;
+    let count: i64 = age[..age.len() - unit.len_utf8()].parse().ok()?;
+    let per_unit = match unit.to_ascii_lowercase() {
+        'd' => 1,
+        'w' => 7,
+        'm' => 30,
+        'y' => 365,
+        _ => return None,
+    };
+    let seconds = count.checked_mul(per_unit)?.checked_mul(86_400)?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    now.checked_sub(seconds)
+}
+
 fn fuzzy_match(text: &str, pattern: &str) -> bool {
     let mut pattern_chars = pattern.chars().peekable();
     for c in text.chars() {
@@ -579,3 +2042,79 @@ fn fuzzy_match(text: &str, pattern: &str) -> bool {
     }
     pattern_chars.peek().is_none()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mail::Address;
+
+    #[test]
+    fn test_split_search_operators() {
+        let (operators, text) = split_search_operators("invoice is:Auto from:News@ older:2w due");
+        assert_eq!(text, "invoice due");
+        assert_eq!(operators.len(), 3);
+        assert_eq!(operators[0], SearchOperator::Is("auto".to_string()));
+        assert_eq!(operators[1], SearchOperator::From("news@".to_string()));
+        assert!(matches!(operators[2], SearchOperator::Older(_)));
+
+        // Empty, unknown or unparsable terms stay in the text
+        let (operators, text) = split_search_operators("from: to:bob older:soon re:x");
+        assert!(operators.is_empty());
+        assert_eq!(text, "from: to:bob older:soon re:x");
+    }
+
+    #[test]
+    fn test_age_cutoff() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let cutoff = age_cutoff("2w").unwrap();
+        assert!((now - 14 * 86_400 - cutoff).abs() <= 1);
+        assert_eq!(age_cutoff("3x"), None);
+        assert_eq!(age_cutoff("y"), None);
+        // Too far back to count overflows rather than panicking
+        assert_eq!(age_cutoff("9999999999999999y"), None);
+        assert_eq!(age_cutoff("999999999999999d"), None);
+        let (operators, text) = split_search_operators("older:9999999999999999y");
+        assert!(operators.is_empty());
+        assert_eq!(text, "older:9999999999999999y");
+    }
+
+    #[test]
+    fn test_matches_operator() {
+        let envelope = Envelope {
+            from: Some(Address {
+                name: Some("Weekly News".to_string()),
+                addr: "news@example.com".to_string(),
+            }),
+            timestamp: Some(1_000),
+            flags: vec!["Replied".to_string()],
+            ..Default::default()
+        };
+        let is = |name: &str| SearchOperator::Is(name.to_string());
+        let from = |text: &str| SearchOperator::From(text.to_string());
+        assert!(matches_operator(&envelope, &from("news@")));
+        assert!(matches_operator(&envelope, &from("weekly")));
+        assert!(!matches_operator(&envelope, &from("bob")));
+        assert!(matches_operator(&envelope, &is("replied")));
+        assert!(!matches_operator(&envelope, &is("unreplied")));
+        assert!(!matches_operator(&envelope, &is("auto")));
+        assert!(!matches_operator(&envelope, &is("nonsense")));
+        assert!(matches_operator(&envelope, &SearchOperator::Older(2_000)));
+        assert!(!matches_operator(&envelope, &SearchOperator::Older(1_000)));
+
+        // Sent mail never counts as waiting for an answer
+        let sent = Envelope {
+            is_sent: true,
+            ..Default::default()
+        };
+        assert!(!matches_operator(&sent, &is("unreplied")));
+        let undated = Envelope::default();
+        assert!(matches_operator(&undated, &is("unreplied")));
+        assert!(!matches_operator(
+            &undated,
+            &SearchOperator::Older(i64::MAX)
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use ratatui::style::Color;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Background colors for generated initials blocks
+const PALETTE: &[Color] = &[
+    Color::Rgb(0x8f, 0xa5, 0xae),
+    Color::Rgb(0xb4, 0x8e, 0xad),
+    Color::Rgb(0xa3, 0xbe, 0x8c),
+    Color::Rgb(0xd4, 0xa3, 0x66),
+    Color::Rgb(0x88, 0xc0, 0xd0),
+    Color::Rgb(0xbf, 0x61, 0x6a),
+    Color::Rgb(0xd0, 0x87, 0x70),
+    Color::Rgb(0x81, 0xa1, 0xc1),
+];
+
+/// Up to two initials from a display name, or the first letter of the address
+pub fn initials(name: Option<&str>, addr: &str) -> String {
+    let from_name: String = name
+        .unwrap_or("")
+        .split_whitespace()
+        .filter_map(|w| w.chars().find(|c| c.is_alphanumeric()))
+        .take(2)
+        .collect();
+    let initials = if from_name.is_empty() {
+        addr.chars()
+            .find(|c| c.is_alphanumeric())
+            .into_iter()
+            .collect()
+    } else {
+        from_name
+    };
+    initials.to_uppercase()
+}
+
+/// Stable per-sender color so the same person always gets the same block
+pub fn color_for(addr: &str) -> Color {
+    // FNV-1a
+    let mut hash: u32 = 0x811c9dc5;
+    for b in addr.trim().to_lowercase().bytes() {
+        hash ^= b as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    PALETTE[hash as usize % PALETTE.len()]
+}
+
+/// Cache file for a sender's gravatar (empty file = no gravatar exists)
+fn gravatar_path(addr: &str) -> Option<PathBuf> {
+    let hash = Sha256::digest(addr.trim().to_lowercase().as_bytes());
+    let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+    dirs::cache_dir().map(|p| p.join("mailtui/avatars").join(hex))
+}
+
+/// Load a previously fetched gravatar, if one exists for this address
+pub fn cached_gravatar(addr: &str) -> Option<image::DynamicImage> {
+    let data = std::fs::read(gravatar_path(addr)?).ok()?;
+    if data.is_empty() {
+        return None;
+    }
+    image::load_from_memory(&data).ok()
+}
+
+/// Whether a fetch for this address has already completed (hit or miss)
+pub fn gravatar_fetched(addr: &str) -> bool {
+    gravatar_path(addr).map(|p| p.exists()).unwrap_or(true)
+}
+
+/// Fetch a gravatar into the cache on a background thread (uses curl)
+pub fn fetch_gravatar(addr: &str) {
+    let Some(path) = gravatar_path(addr) else {
+        return;
+    };
+    if path.exists() {
+        return;
+    }
+    let hash = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    std::thread::spawn(move || {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        // d=404 makes missing avatars fail instead of returning a placeholder
+        let url = format!("https://gravatar.com/avatar/{}?s=96&d=404", hash);
+        let tmp = path.with_extension("part");
+        let ok = Command::new("curl")
+            .args(["-sf", "--max-time", "10", "-o"])
+            .arg(&tmp)
+            .arg(&url)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+        if ok {
+            let _ = std::fs::rename(&tmp, &path);
+        } else {
+            // Remember the miss so we don't hit the network again
+            let _ = std::fs::remove_file(&tmp);
+            let _ = std::fs::write(&path, b"");
+        }
+    });
+}
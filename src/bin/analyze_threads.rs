@@ -6,7 +6,10 @@ fn main() {
     let envelopes = mailtui::mail::scan_all_mail(&mail_dir, user_email, |_, _| {}).unwrap();
     println!("Total envelopes: {}", envelopes.len());
 
-    let threaded = mailtui::mail::build_threaded_list(envelopes.clone());
+    let threaded = mailtui::mail::build_threaded_list(
+        envelopes.clone(),
+        &mailtui::config::ThreadingConfig::default(),
+    );
 
     // Count threads by looking at depth=0 messages
     let num_threads = threaded.iter().filter(|e| e.thread_depth == 0).count();
@@ -43,7 +43,10 @@ fn main() {
             // Now benchmark threading
             println!("\nBuilding threads...");
             let thread_start = Instant::now();
-            let threaded = mailtui::mail::build_threaded_list(envelopes);
+            let threaded = mailtui::mail::build_threaded_list(
+                envelopes,
+                &mailtui::config::ThreadingConfig::default(),
+            );
             let thread_duration = thread_start.elapsed();
 
             println!(
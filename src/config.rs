@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Debug, Deserialize)]
@@ -12,6 +12,25 @@ pub struct Config {
     pub layout: LayoutConfig,
     pub theme: ThemeConfig,
     pub compose: ComposeConfig,
+    /// UI language, e.g. "de" (defaults to $LANG)
+    pub locale: Option<String>,
+    pub accessibility: AccessibilityConfig,
+    pub avatars: AvatarConfig,
+    pub keys: KeysConfig,
+    pub contacts: ContactsConfig,
+    pub print: PrintConfig,
+    pub links: LinksConfig,
+    pub preview: PreviewConfig,
+    pub cleanup: CleanupConfig,
+    pub receipts: ReceiptsConfig,
+    pub threading: ThreadingConfig,
+    /// Canned reply snippets: name -> body ({first_name} and {name} are the sender's)
+    pub templates: std::collections::BTreeMap<String, String>,
+    /// Recipient groups: a name typed in To: expands to these addresses
+    pub groups: std::collections::BTreeMap<String, Vec<String>>,
+    /// Attachment handlers offered by the open-with menu: MIME type (or
+    /// "image/*" for a whole type) -> commands (%s is the saved file)
+    pub open_with: std::collections::BTreeMap<String, Vec<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -27,6 +46,28 @@ pub struct AccountConfig {
     pub signature_delim: String,
     /// Command to send mail (default: "msmtp -t")
     pub send_command: String,
+    /// Tag store kept in sync with read/flag changes ("notmuch")
+    pub tags_backend: Option<String>,
+    /// Inbox folder, relative to maildir (archiving removes mail from it)
+    pub inbox_folder: String,
+    /// Folder archived mail lives in, relative to maildir; archiving only
+    /// drops the inbox copy once a copy is here
+    pub archive_folder: String,
+    /// Trash folder, relative to maildir
+    pub trash_folder: String,
+    /// Spam folder, relative to maildir
+    pub spam_folder: String,
+    /// Run on messages marked "not spam" in junk review; {file} is the moved
+    /// file, e.g. "sa-learn --ham {file}"
+    pub ham_command: Option<String>,
+    /// Extra named signatures, e.g. formal = "Kind regards,\n..."
+    pub signatures: std::collections::HashMap<String, String>,
+    /// Recipient domain or reply language -> signature name
+    /// ("@client.com" = "formal", "lang:de" = "german")
+    pub signature_rules: std::collections::HashMap<String, String>,
+    /// Storage quota in GB (Gmail's free tier is 15); the bottom bar shows
+    /// how much of it the maildir takes
+    pub quota_gb: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,6 +75,8 @@ pub struct AccountConfig {
 pub struct ComposeConfig {
     /// Include signature when replying to messages
     pub signature_on_reply: bool,
+    /// Details listed after each attachment's name: "size", "type"
+    pub attachment_columns: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,12 +90,243 @@ pub struct LayoutConfig {
     pub date_width: usize,
     /// From column width in characters
     pub from_width: usize,
+    /// Divider rows ("Today", "Yesterday", ...) between days in the list
+    pub day_separators: bool,
+    /// Widest the preview text wraps to, in columns (0 = the pane's width)
+    pub max_content_width: u16,
+    /// Terminal width from which a third column shows the thread and
+    /// attachments beside the preview (0 = never)
+    pub three_column_width: u16,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityConfig {
+    /// Add distinct glyphs to cues that are otherwise shown by color alone
+    pub glyph_markers: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct KeysConfig {
+    /// Shortcut preset layered over the defaults ("gmail")
+    pub preset: Option<String>,
+    /// Per-key overrides: key sequence -> action name
+    pub bindings: std::collections::HashMap<String, String>,
+    /// Key sequence -> shell command; {file}, {message_id}, {from} and {subject}
+    /// are replaced with the selected message's (quoted) values
+    pub commands: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ContactsConfig {
+    /// Mutt-style address book query, `%s` is the address (e.g. "khard email --parsable %s")
+    pub query_command: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct PrintConfig {
+    /// Shell pipeline that receives the rendered message on stdin.
+    /// `{output}` is replaced with a file path in output_dir (for PDF export)
+    pub command: String,
+    /// What to feed the command: "text" or "html"
+    pub format: String,
+    /// Directory for `{output}` files
+    pub output_dir: String,
+}
+
+impl Default for PrintConfig {
+    fn default() -> Self {
+        Self {
+            command: "lp".to_string(),
+            format: "text".to_string(),
+            output_dir: "~/Documents".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct LinksConfig {
+    /// Deep link copied for a message; {message_id} is the bare Message-ID
+    pub template: String,
+    /// Ticket ID pattern (regex) -> URL template; {id} is the matched ID,
+    /// e.g. "PROJ-\\d+" = "https://jira.example.com/browse/{id}"
+    pub tickets: std::collections::HashMap<String, String>,
+}
+
+impl Default for LinksConfig {
+    fn default() -> Self {
+        Self {
+            template: "message://%3C{message_id}%3E".to_string(),
+            tickets: std::collections::HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct PreviewConfig {
+    /// Keep each message's scroll offset across restarts (always kept within a session)
+    pub persist_scroll: bool,
+    /// Render the HTML part even when the message has a plain-text one
+    pub prefer_html: bool,
+    /// Decode and show inline images below the text
+    pub load_images: bool,
+    /// Fold runs of quoted ("> ") lines into one marker line
+    pub collapse_quotes: bool,
+    /// Per-sender overrides of the three options above: "boss@example.com",
+    /// "@example.com" (that exact domain) or "example.com" (also subdomains)
+    pub senders: std::collections::HashMap<String, SenderViewRule>,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            persist_scroll: false,
+            prefer_html: false,
+            load_images: true,
+            collapse_quotes: false,
+            senders: std::collections::HashMap::new(),
+        }
+    }
+}
+
+/// A [preview.senders] entry; unset options fall back to [preview]
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SenderViewRule {
+    pub prefer_html: Option<bool>,
+    pub load_images: Option<bool>,
+    pub collapse_quotes: Option<bool>,
+}
+
+/// How to show one message, after applying its sender's rule
+#[derive(Debug, Clone, Copy)]
+pub struct ViewOptions {
+    pub prefer_html: bool,
+    pub load_images: bool,
+    pub collapse_quotes: bool,
+}
+
+impl PreviewConfig {
+    /// View options for mail from `addr`. An exact address rule beats domain
+    /// rules; among those the longest matching pattern wins
+    pub fn view_options(&self, addr: &str) -> ViewOptions {
+        let addr = addr.trim().to_lowercase();
+        let domain = addr.rsplit_once('@').map(|(_, d)| d).unwrap_or("");
+        let rule = self
+            .senders
+            .iter()
+            .filter(|(pattern, _)| {
+                let pattern = pattern.to_lowercase();
+                if pattern.contains('@') && !pattern.starts_with('@') {
+                    return addr == pattern;
+                }
+                match pattern.strip_prefix('@') {
+                    Some(exact) => domain == exact,
+                    None => {
+                        !domain.is_empty()
+                            && (domain == pattern || domain.ends_with(&format!(".{}", pattern)))
+                    }
+                }
+            })
+            .max_by_key(|(pattern, _)| {
+                (
+                    !pattern.starts_with('@') && pattern.contains('@'),
+                    pattern.len(),
+                )
+            })
+            .map(|(_, rule)| rule);
+
+        ViewOptions {
+            prefer_html: rule.and_then(|r| r.prefer_html).unwrap_or(self.prefer_html),
+            load_images: rule.and_then(|r| r.load_images).unwrap_or(self.load_images),
+            collapse_quotes: rule
+                .and_then(|r| r.collapse_quotes)
+                .unwrap_or(self.collapse_quotes),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct CleanupConfig {
+    /// Attachments smaller than this (in KB) are kept when stripping
+    pub strip_min_kb: u64,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self { strip_min_kb: 100 }
+    }
 }
 
-/// Semantic theme configuration using Capstan Cloud colors as defaults
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
+pub struct ThreadingConfig {
+    /// List messages one by one, newest first, without grouping into threads
+    pub flat: bool,
+    /// Deepest indent drawn for a reply
+    pub max_depth: usize,
+    /// Keep a parent's only reply at the parent's indent; only branches indent
+    pub collapse_linear: bool,
+}
+
+impl Default for ThreadingConfig {
+    fn default() -> Self {
+        Self {
+            flat: false,
+            max_depth: 3,
+            collapse_linear: true,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ReceiptsConfig {
+    /// Summarize order, total and tracking numbers above receipt/shipping mail
+    pub enabled: bool,
+    /// Extra regexes tried before the built-in ones; the first capture group
+    /// (or the whole match) is the value
+    pub order_patterns: Vec<String>,
+    pub total_patterns: Vec<String>,
+    pub tracking_patterns: Vec<String>,
+    /// Tracking page for numbers of unrecognized carriers; {number} is the number
+    pub tracking_url: String,
+}
+
+impl Default for ReceiptsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            order_patterns: Vec::new(),
+            total_patterns: Vec::new(),
+            tracking_patterns: Vec::new(),
+            tracking_url: "https://www.google.com/search?q={number}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AvatarConfig {
+    /// Show a sender avatar (initials block) above the message preview
+    pub enabled: bool,
+    /// Fetch avatars from gravatar.com (sends a hash of the sender's address)
+    pub gravatar: bool,
+}
+
+/// Semantic theme configuration using Capstan Cloud colors as defaults
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct ThemeConfig {
+    /// Built-in palette used as the base for unset colors ("deuteranopia")
+    pub preset: Option<String>,
+
     // Base colors
     pub bg: String,
     pub bg_panel: String,
@@ -93,6 +367,20 @@ impl Default for Config {
             layout: LayoutConfig::default(),
             theme: ThemeConfig::default(),
             compose: ComposeConfig::default(),
+            locale: None,
+            accessibility: AccessibilityConfig::default(),
+            avatars: AvatarConfig::default(),
+            keys: KeysConfig::default(),
+            contacts: ContactsConfig::default(),
+            print: PrintConfig::default(),
+            links: LinksConfig::default(),
+            preview: PreviewConfig::default(),
+            cleanup: CleanupConfig::default(),
+            receipts: ReceiptsConfig::default(),
+            threading: ThreadingConfig::default(),
+            templates: std::collections::BTreeMap::new(),
+            groups: std::collections::BTreeMap::new(),
+            open_with: std::collections::BTreeMap::new(),
         }
     }
 }
@@ -105,6 +393,61 @@ impl Default for AccountConfig {
             signature: None,
             signature_delim: "-- \n".to_string(),
             send_command: "msmtp -t".to_string(),
+            tags_backend: None,
+            inbox_folder: "Inbox".to_string(),
+            archive_folder: "[Gmail]/All Mail".to_string(),
+            trash_folder: "[Gmail]/Trash".to_string(),
+            spam_folder: "[Gmail]/Spam".to_string(),
+            ham_command: None,
+            signatures: std::collections::HashMap::new(),
+            signature_rules: std::collections::HashMap::new(),
+            quota_gb: None,
+        }
+    }
+}
+
+impl AccountConfig {
+    /// Signature for a message to `to`, chosen by the first recipient's domain.
+    /// "@client.com" matches that exact domain, "client.com" also its subdomains;
+    /// the longest matching rule wins. Without a domain match, a "lang:<tag>" rule
+    /// for the reply `language` applies, then the default signature
+    pub fn signature_for(&self, to: &str, language: Option<&str>) -> Option<&str> {
+        let first = to.split(',').next().unwrap_or("");
+        let addr = match (first.find('<'), first.rfind('>')) {
+            (Some(start), Some(end)) if start < end => &first[start + 1..end],
+            _ => first,
+        }
+        .trim()
+        .to_lowercase();
+        let domain = addr.rsplit_once('@').map(|(_, d)| d).unwrap_or("");
+
+        let rule = self
+            .signature_rules
+            .iter()
+            .filter(|(pattern, _)| !pattern.starts_with("lang:"))
+            .filter(|(pattern, _)| {
+                let pattern = pattern.to_lowercase();
+                match pattern.strip_prefix('@') {
+                    Some(exact) => domain == exact,
+                    None => {
+                        !domain.is_empty()
+                            && (domain == pattern || domain.ends_with(&format!(".{}", pattern)))
+                    }
+                }
+            })
+            .max_by_key(|(pattern, _)| pattern.len())
+            .or_else(|| {
+                let language = language?;
+                self.signature_rules.iter().find(|(pattern, _)| {
+                    pattern
+                        .strip_prefix("lang:")
+                        .is_some_and(|tag| tag.eq_ignore_ascii_case(language))
+                })
+            });
+
+        match rule {
+            Some((_, name)) => self.signatures.get(name).map(|s| s.as_str()),
+            None => self.signature.as_deref(),
         }
     }
 }
@@ -128,12 +471,58 @@ impl Config {
     pub fn get_account(&self, name: &str) -> Option<&AccountConfig> {
         self.accounts.get(name)
     }
+
+    /// Commands configured for a MIME type: those for the exact type
+    /// first, then those for its "type/*"
+    pub fn open_with_handlers(&self, content_type: &str) -> Vec<&str> {
+        let content_type = content_type.to_ascii_lowercase();
+        let wildcard = content_type
+            .split_once('/')
+            .map(|(main, _)| format!("{}/*", main));
+        let mut handlers: Vec<&str> = Vec::new();
+        for key in std::iter::once(&content_type).chain(wildcard.as_ref()) {
+            for (pattern, commands) in &self.open_with {
+                if !pattern.eq_ignore_ascii_case(key) {
+                    continue;
+                }
+                for command in commands {
+                    if !handlers.contains(&command.as_str()) {
+                        handlers.push(command);
+                    }
+                }
+            }
+        }
+        handlers
+    }
+
+    /// Replace group names in a comma-separated recipient list with the
+    /// group's addresses, dropping repeats ("team, bob@x.com")
+    pub fn expand_groups(&self, recipients: &str) -> String {
+        let mut expanded: Vec<&str> = Vec::new();
+        for recipient in recipients.split(',').map(str::trim) {
+            let group = self
+                .groups
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(recipient));
+            let addrs = match group {
+                Some((_, addrs)) => addrs.iter().map(String::as_str).collect(),
+                None => vec![recipient],
+            };
+            for addr in addrs {
+                if !addr.is_empty() && !expanded.iter().any(|a| a.eq_ignore_ascii_case(addr)) {
+                    expanded.push(addr);
+                }
+            }
+        }
+        expanded.join(", ")
+    }
 }
 
 impl Default for ComposeConfig {
     fn default() -> Self {
         Self {
             signature_on_reply: true,
+            attachment_columns: vec!["size".to_string(), "type".to_string()],
         }
     }
 }
@@ -145,6 +534,9 @@ impl Default for LayoutConfig {
             preview_focused_width: 67,
             date_width: 14,
             from_width: 18,
+            day_separators: false,
+            max_content_width: 0,
+            three_column_width: 240,
         }
     }
 }
@@ -153,6 +545,8 @@ impl Default for LayoutConfig {
 impl Default for ThemeConfig {
     fn default() -> Self {
         Self {
+            preset: None,
+
             // Base colors
             bg: "#1a1917".to_string(),
             bg_panel: "#262422".to_string(),
@@ -187,6 +581,52 @@ impl Default for ThemeConfig {
     }
 }
 
+impl ThemeConfig {
+    /// Look up a built-in palette by name
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            // Okabe-Ito colors: no red/green pairs, distinguishable with deuteranopia
+            "deuteranopia" => Some(Self {
+                preset: Some(name.to_string()),
+                border_active: "#e69f00".to_string(), // orange
+                primary: "#e69f00".to_string(),
+                primary_light: "#f5c76b".to_string(),
+                secondary: "#56b4e9".to_string(), // sky blue
+                secondary_light: "#9bd3f2".to_string(),
+                success: "#56b4e9".to_string(),
+                warning: "#f0e442".to_string(), // yellow
+                error: "#d55e00".to_string(),   // vermillion
+                info: "#56b4e9".to_string(),
+                unread: "#e69f00".to_string(),
+                url: "#56b4e9".to_string(),
+                attachment: "#cc79a7".to_string(), // reddish purple
+                ..Self::default()
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Parse config, filling unset [theme] colors from the selected preset
+fn parse_config(content: &str) -> Result<Config, toml::de::Error> {
+    let mut table: toml::Table = content.parse()?;
+
+    if let Some(toml::Value::Table(theme)) = table.get_mut("theme") {
+        let preset = theme
+            .get("preset")
+            .and_then(|v| v.as_str())
+            .and_then(ThemeConfig::preset)
+            .and_then(|p| toml::Value::try_from(p).ok());
+        if let Some(toml::Value::Table(base)) = preset {
+            for (key, value) in base {
+                theme.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    toml::Value::Table(table).try_into()
+}
+
 impl Config {
     pub fn load() -> Self {
         let config_path = dirs::config_dir()
@@ -195,7 +635,7 @@ impl Config {
 
         if config_path.exists() {
             match std::fs::read_to_string(&config_path) {
-                Ok(content) => match toml::from_str(&content) {
+                Ok(content) => match parse_config(&content) {
                     Ok(config) => return config,
                     Err(e) => eprintln!("Config parse error: {}", e),
                 },
@@ -0,0 +1,39 @@
+use std::process::{Command, Stdio};
+
+/// An address book entry for a sender
+#[derive(Debug, Clone)]
+pub struct ContactEntry {
+    pub name: String,
+    /// Free-form third column (e.g. nickname, organization)
+    pub info: String,
+}
+
+/// Look up an address with a mutt-style query command.
+/// `%s` in the command is replaced by the address; output is a status line
+/// followed by `address<TAB>name<TAB>info` lines
+pub fn lookup(query_command: &str, addr: &str) -> Option<ContactEntry> {
+    // Split on whitespace rather than using a shell: the address is untrusted input
+    let mut parts = query_command
+        .split_whitespace()
+        .map(|p| p.replace("%s", addr));
+    let program = parts.next()?;
+    let output = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().skip(1).find_map(|line| {
+        let mut cols = line.split('\t');
+        let entry_addr = cols.next()?;
+        if !entry_addr.trim().eq_ignore_ascii_case(addr) {
+            return None;
+        }
+        Some(ContactEntry {
+            name: cols.next().unwrap_or("").trim().to_string(),
+            info: cols.next().unwrap_or("").trim().to_string(),
+        })
+    })
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config::Config;
+
+/// Longest version line shown
+const MAX_VERSION_LEN: usize = 50;
+
+/// How an external tool stands with the current configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolState {
+    /// Installed and used
+    Active,
+    /// Installed, but nothing in the config uses it
+    Unused,
+    /// Not installed and not needed
+    Missing,
+    /// Needed but not installed: the feature it backs is degraded
+    Broken,
+}
+
+/// One external tool mailtui shells out to (or relies on)
+#[derive(Debug, Clone)]
+pub struct ToolStatus {
+    pub name: &'static str,
+    /// i18n key of the feature it backs
+    pub feature: &'static str,
+    /// First line of its version output, None when it could not be run
+    pub version: Option<String>,
+    pub state: ToolState,
+}
+
+/// Program name of a command line ("msmtp -t" -> "msmtp")
+fn program(command: &str) -> &str {
+    let first = command.split_whitespace().next().unwrap_or("");
+    Path::new(first)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(first)
+}
+
+/// Run `<name> <flag>` and keep the first line it prints. Some tools
+/// print their version to stderr
+fn version(name: &str, flag: &str) -> Option<String> {
+    let output = Command::new(name)
+        .arg(flag)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    let line = [&output.stdout, &output.stderr]
+        .iter()
+        .map(|out| String::from_utf8_lossy(out).to_string())
+        .find_map(|text| {
+            text.lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    Some(line.chars().take(MAX_VERSION_LEN).collect())
+}
+
+/// Check every external tool (in parallel, each one is a process spawn)
+pub fn check(config: &Config) -> Vec<ToolStatus> {
+    let msmtp = config
+        .accounts
+        .values()
+        .any(|a| program(&a.send_command) == "msmtp");
+    let notmuch = config
+        .accounts
+        .values()
+        .any(|a| a.tags_backend.as_deref() == Some("notmuch"));
+    let tools: [(&'static str, &str, &'static str, bool); 6] = [
+        ("w3m", "-version", "diag.html", true),
+        ("rg", "--version", "diag.deep_search", true),
+        ("msmtp", "--version", "diag.sending", msmtp),
+        ("mbsync", "--version", "diag.sync", true),
+        ("notmuch", "--version", "diag.tags", notmuch),
+        ("yazi", "--version", "diag.attachments", true),
+    ];
+
+    std::thread::scope(|s| {
+        let handles: Vec<_> = tools
+            .iter()
+            .map(|&(name, flag, feature, needed)| {
+                s.spawn(move || {
+                    let version = version(name, flag);
+                    let state = match (version.is_some(), needed) {
+                        (true, true) => ToolState::Active,
+                        (true, false) => ToolState::Unused,
+                        (false, true) => ToolState::Broken,
+                        (false, false) => ToolState::Missing,
+                    };
+                    ToolStatus {
+                        name,
+                        feature,
+                        version,
+                        state,
+                    }
+                })
+            })
+            .collect();
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    })
+}
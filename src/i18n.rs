@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Built-in English strings, also the fallback for keys a translation omits
+const EN: &[(&str, &str)] = &[
+    // Status messages
+    ("status.marked_read", "Marked read"),
+    ("status.marked_unread", "Marked unread"),
+    ("status.opened_in_browser", "Opened in browser"),
+    ("status.no_attachments", "No attachments"),
+    ("status.files_saved", "{count} file(s) saved"),
+    ("status.part_saved", "Saved {file}"),
+    ("status.opened_with", "Saved {file}, opened with {command}"),
+    ("status.error", "Error: {error}"),
+    ("status.no_file_path", "No file path for message"),
+    (
+        "status.config_edited",
+        "Config edited - restart to apply changes",
+    ),
+    ("status.switched_account", "Switched to {account}"),
+    ("status.deep_searching", "Deep searching..."),
+    ("status.deep_results", "Found {count} results (deep)"),
+    ("status.search_error", "Search error: {error}"),
+    ("status.send_cancelled", "Send cancelled"),
+    ("status.draft_discarded", "Draft discarded"),
+    ("status.sent", "Message sent!"),
+    ("status.send_failed", "Failed to send"),
+    (
+        "status.confirm_send",
+        "Press 's' again to confirm send, any other key to cancel",
+    ),
+    ("status.reloaded", "Reloaded"),
+    ("status.refreshed", "Refreshed"),
+    ("status.reload_error", "Reload error: {error}"),
+    ("status.archived", "Archived"),
+    ("status.not_in_inbox", "Not in inbox"),
+    ("status.deleted", "Moved to trash"),
+    ("status.marked_spam", "Marked as spam"),
+    ("status.filter_cleared", "Filter cleared"),
+    ("status.moved", "Moved to {folder}"),
+    ("status.command_done", "Command finished"),
+    ("status.command_failed", "Command failed"),
+    ("status.printed", "Sent to printer"),
+    ("status.saved_to", "Saved {path}"),
+    ("status.link_copied", "Copied {link}"),
+    ("status.message_not_found", "Message not found: {id}"),
+    (
+        "status.not_a_list",
+        "Not a mailing-list message (no List-Post or Mail-Followup-To)",
+    ),
+    (
+        "status.attachment_missing",
+        "Attachment no longer exists: {path} (d to remove it)",
+    ),
+    (
+        "status.message_relocated",
+        "Message was changed outside mailtui; reloaded it",
+    ),
+    (
+        "status.message_gone",
+        "Message was moved or deleted outside mailtui; removed it from the list",
+    ),
+    (
+        "status.other_instance",
+        "Another mailtui is running; this one won't update the envelope cache",
+    ),
+    ("status.sorted_by_triage", "Sorted by triage mark"),
+    ("status.sorted_by_date", "Sorted by date"),
+    ("status.no_templates", "No reply templates configured"),
+    ("status.no_links", "No links on screen"),
+    ("status.no_tracking", "No tracking number in this message"),
+    ("status.macro_register", "Register?"),
+    ("status.nothing_to_repeat", "Nothing to repeat yet"),
+    ("status.no_activity", "Nothing in the activity log yet"),
+    (
+        "status.selected",
+        "{count} selected: archive, delete, spam, mark read/unread or move applies to all; Esc clears",
+    ),
+    ("status.selection_cleared", "Selection cleared"),
+    ("status.cancelled", "Cancelled"),
+    (
+        "status.confirm_bulk_archive",
+        "Archive {count} messages? (y/n)",
+    ),
+    (
+        "status.confirm_bulk_delete",
+        "Delete {count} messages? (y/n)",
+    ),
+    (
+        "status.confirm_bulk_spam",
+        "Mark {count} messages as spam? (y/n)",
+    ),
+    (
+        "status.confirm_bulk_read",
+        "Mark {count} messages read? (y/n)",
+    ),
+    (
+        "status.confirm_bulk_unread",
+        "Mark {count} messages unread? (y/n)",
+    ),
+    (
+        "status.confirm_bulk_move",
+        "Move {count} messages to {folder}? (y/n)",
+    ),
+    ("status.bulk_done", "Done: {count} messages"),
+    (
+        "status.bulk_stopped",
+        "Stopped after {count} of {total} messages",
+    ),
+    (
+        "status.macro_recorded",
+        "Recorded {count} keys into @{register}",
+    ),
+    ("status.macro_empty", "Register @{register} is empty"),
+    (
+        "status.opening_tracking",
+        "Opening tracking page for {number}",
+    ),
+    ("status.not_a_bounce", "Not a delivery failure report"),
+    ("status.no_such_link", "No link with that label"),
+    ("status.no_junk", "Junk folder is empty"),
+    ("status.junk_review_only", "Only in junk review (J)"),
+    ("status.not_spam", "Moved to inbox"),
+    ("status.deleted_forever", "Deleted permanently"),
+    (
+        "status.confirm_delete_forever",
+        "Delete \"{subject}\" permanently? (y/n)",
+    ),
+    (
+        "status.nothing_to_strip",
+        "No attachments of {size} or more",
+    ),
+    (
+        "status.export_needs_filter",
+        "Search or filter first, then export the results",
+    ),
+    ("status.exported", "Exported {count} message(s) to {path}"),
+    ("status.finding_duplicates", "Looking for duplicates..."),
+    ("status.no_duplicates", "No duplicate messages found"),
+    ("status.finding_events", "Looking for calendar invites..."),
+    ("status.no_events", "No upcoming events in calendar invites"),
+    (
+        "status.duplicates_removed",
+        "Removed {count} copies, freed {size}",
+    ),
+    (
+        "status.confirm_delete_duplicates",
+        "Permanently delete {count} duplicate copies ({size})? (y/n)",
+    ),
+    (
+        "status.duplicates_failed",
+        "Removed {count} copies, then: {error}",
+    ),
+    (
+        "status.cleanup_deleted",
+        "Moved {count} message(s) ({size}) to trash",
+    ),
+    (
+        "status.confirm_cleanup_delete",
+        "Move {count} message(s) ({size}) to trash? (y/n)",
+    ),
+    (
+        "status.confirm_cleanup_strip",
+        "Strip attachments from {count} message(s) ({size})? (y/n)",
+    ),
+    (
+        "status.cleanup_failed",
+        "{count} done, {failed} failed: {error}",
+    ),
+    (
+        "status.attachments_stripped",
+        "Stripped attachments from {count} message(s), freed {size}",
+    ),
+    // Help bar
+    ("help.confirm", "confirm"),
+    ("help.cancel", "cancel"),
+    ("help.pane", "pane"),
+    ("help.nav", "nav"),
+    ("help.account", "account"),
+    ("help.read", "read"),
+    ("help.search", "search"),
+    ("help.deep", "deep"),
+    ("help.reply", "reply"),
+    ("help.compose", "compose"),
+    ("help.reorder", "reorder"),
+    ("help.config", "config"),
+    ("help.reload", "reload disk"),
+    ("help.quit", "quit"),
+    ("help.substring_match", "(substring match)"),
+    ("help.edit", "edit"),
+    ("help.attach", "attach"),
+    ("help.remove", "remove"),
+    ("help.select", "select"),
+    ("help.send", "send"),
+    ("help.move", "move"),
+    ("help.recent", "recent"),
+    ("help.type_hint", "Type a link's label to open it"),
+    ("help.scroll", "scroll"),
+    ("help.page", "page"),
+    ("help.delete_copies", "delete copies"),
+    ("help.delete_all_copies", "delete all"),
+    ("help.close", "close"),
+    ("help.open_message", "open message"),
+    ("help.open", "open"),
+    ("help.back", "back"),
+    ("help.not_spam", "not spam"),
+    ("help.delete_forever", "delete forever"),
+    ("help.junk_count", "{count} in junk"),
+    ("help.recording", "recording @{register}"),
+    ("help.scanning", "{spinner} scanning {percent}%"),
+    ("help.mark", "mark"),
+    ("help.delete", "delete"),
+    ("help.strip", "strip attachments"),
+    // Pane titles
+    ("title.mail", "Mail"),
+    ("title.message", "Message"),
+    ("preview.tickets", "Tickets: {ids}"),
+    ("receipt.order", "Order {order}"),
+    ("receipt.total", "Total {total}"),
+    ("receipt.tracking", "Tracking {number}"),
+    ("title.search", "Search: {query} ({count} results)"),
+    ("title.deep_search", "Deep Search: {query}"),
+    ("title.junk", "Junk review ({count})"),
+    ("title.matches", "Mail ({count} matches)"),
+    ("title.unread", " (Unread)"),
+    ("title.compose", "Compose"),
+    ("title.body", "Body"),
+    ("title.attachments", "Attachments ({count})"),
+    ("title.contact", "Contact"),
+    ("title.info", "Details"),
+    ("info.thread", "Thread ({count})"),
+    ("info.attachments", "Attachments"),
+    ("info.no_attachments", "None"),
+    ("title.from_sender", " (from {sender})"),
+    ("title.related", " (related)"),
+    ("title.stale", " (cached - scanning...)"),
+    ("warn.maildir_missing", " Maildir not found: {path} (check maildir in the account config)"),
+    ("warn.maildir_empty", " No maildir folders (cur/ and new/) under {path} (check maildir in the account config, or run mbsync)"),
+    ("title.selected", " · {count} selected"),
+    // List and compose content
+    ("list.no_subject", "(no subject)"),
+    ("list.quota", "{size} of {quota} ({percent}%)"),
+    ("list.unread_replies", " +{count} unread"),
+    ("list.bounced", "bounced"),
+    ("list.today", "Today"),
+    ("list.yesterday", "Yesterday"),
+    ("list.this_week", "This week"),
+    ("list.last_week", "Last week"),
+    ("compose.to", "To: "),
+    ("compose.subject", "Subject: "),
+    ("compose.language", "Language: "),
+    ("compose.no_attachments", "(no attachments)"),
+    ("compose.attachment_missing", "missing"),
+    // Contact panel
+    ("contact.address_book", "Address book:"),
+    ("contact.not_in_address_book", "Not in address book"),
+    ("contact.first_seen", "First seen:"),
+    (
+        "contact.message_count",
+        "{count} message(s) from this sender",
+    ),
+    ("contact.recent", "Recent"),
+    ("contact.filter_hint", "show only this sender"),
+    // Modals
+    ("modal.confirm", " Confirm "),
+    ("modal.send_question", "Send this email?"),
+    ("modal.send_failed", " Send failed "),
+    (
+        "modal.send_failed_question",
+        "The send command failed; nothing was sent",
+    ),
+    ("modal.send_failed_hint", "Esc to return to the draft"),
+    ("send.exit_code", "exit code {code}"),
+    ("send.no_exit_code", "did not run to completion"),
+    ("send.no_output", "(the command printed nothing)"),
+    ("modal.send_hint", "Press 's' to confirm, any key to cancel"),
+    ("modal.loading", " Loading "),
+    ("modal.batch", " Working - Esc stops "),
+    ("modal.move", " Move to "),
+    ("modal.templates", " Reply with template "),
+    ("modal.duplicates", " Duplicate messages "),
+    ("modal.cleanup", " Cleanup: largest and oldest "),
+    ("modal.agenda", " Agenda "),
+    ("modal.activity", " Activity "),
+    ("modal.diagnostics", " External tools "),
+    ("modal.thread_debug", " Threading "),
+    ("modal.open_with", " Open with "),
+    ("open_with.unnamed", "(unnamed)"),
+    ("open_with.default", "Default application"),
+    ("open_with.other", "Other command..."),
+    ("open_with.command", "Command (%s is the file; appended if left out):"),
+    ("open_with.none_configured", "No handlers for {type} in [open_with]"),
+    ("thread.columns", "raw shown  subject"),
+    ("thread.orphan_tag", "orphan"),
+    ("thread.duplicate_tag", "duplicate id"),
+    ("thread.none", "none"),
+    (
+        "thread.headers",
+        "Message-ID {message_id}  In-Reply-To {in_reply_to}  References: {count}",
+    ),
+    (
+        "thread.root",
+        "Starts the thread: no In-Reply-To or References",
+    ),
+    (
+        "thread.orphan",
+        "Orphan: its parent <{id}> is not in the list, so it starts a thread",
+    ),
+    ("thread.in_reply_to", "Attached under <{id}> named by In-Reply-To"),
+    (
+        "thread.reference",
+        "Attached under <{id}>, the latest listed References entry (In-Reply-To missing or not listed)",
+    ),
+    (
+        "thread.branch",
+        "Indented one more level: its parent has {count} replies",
+    ),
+    ("thread.indented", "Indented one more level under its parent"),
+    (
+        "thread.capped",
+        "Kept at the parent's indent: threading.max_depth is reached",
+    ),
+    ("thread.flat", "Not indented: threading.flat lists messages one by one"),
+    (
+        "thread.chain",
+        "Only reply to its parent, so it stays at the parent's indent",
+    ),
+    (
+        "thread.duplicate",
+        "Another listed message has the same Message-ID; replies attach to only one of them",
+    ),
+    ("diag.active", "active"),
+    ("diag.unused", "not used"),
+    ("diag.missing", "not installed"),
+    ("diag.broken", "MISSING"),
+    ("diag.html", "HTML rendering"),
+    ("diag.deep_search", "deep search (?)"),
+    ("diag.sending", "sending (msmtp send_command)"),
+    ("diag.sync", "syncing the maildir"),
+    ("diag.tags", "notmuch tags_backend"),
+    ("diag.attachments", "attachment browsing"),
+    ("diag.all_ok", "Every integration in use is installed"),
+    ("diag.degraded", "Degraded: "),
+    (
+        "cleanup.summary",
+        "{count} messages, {size} in total, {marked} marked",
+    ),
+    (
+        "duplicates.summary",
+        "{count} messages with extra copies, {size} reclaimable",
+    ),
+    (
+        "duplicates.folders",
+        "Keeps the copy in {keep}; deletes {others}",
+    ),
+    ("move.new_folder", "(new folder)"),
+    ("loading.scanning_maildir", "Scanning maildir..."),
+    ("loading.building_threads", "Building threads..."),
+    ("loading.scanning", "Scanning..."),
+    ("loading.count", "{current} / {total} messages"),
+    ("progress.archive", "Archiving..."),
+    ("progress.delete", "Deleting..."),
+    ("progress.spam", "Moving to spam..."),
+    ("progress.read", "Marking read..."),
+    ("progress.unread", "Marking unread..."),
+    ("progress.move", "Moving to {folder}..."),
+];
+
+static STRINGS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Load the string table for `locale`, falling back to $LANG when unset.
+/// Translations are read from ~/.config/mailtui/locales/<locale>.toml;
+/// a region-specific file (de_AT) falls back to the language file (de)
+pub fn init(locale: Option<&str>) {
+    let mut table: HashMap<String, String> = EN
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    let locale = locale
+        .map(|s| s.to_string())
+        .or_else(|| std::env::var("LANG").ok())
+        .unwrap_or_default();
+    // "de_DE.UTF-8" -> "de_DE"
+    let locale = locale.split('.').next().unwrap_or("").to_string();
+    let language = locale.split(['_', '-']).next().unwrap_or("").to_string();
+
+    if let Some(dir) = dirs::config_dir().map(|p| p.join("mailtui/locales")) {
+        // Language first so region-specific entries take precedence
+        for name in [&language, &locale] {
+            if name.is_empty() || name == "en" || name == "C" || name == "POSIX" {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(dir.join(format!("{}.toml", name))) {
+                match content.parse::<toml::Table>() {
+                    Ok(parsed) => flatten_into(&mut table, "", &parsed),
+                    Err(e) => eprintln!("Locale parse error ({}): {}", name, e),
+                }
+            }
+        }
+    }
+
+    let _ = STRINGS.set(table);
+}
+
+/// Flatten nested TOML tables into dotted keys ([status] marked_read -> status.marked_read)
+fn flatten_into(table: &mut HashMap<String, String>, prefix: &str, value: &toml::Table) {
+    for (key, val) in value {
+        let full_key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match val {
+            toml::Value::String(s) => {
+                table.insert(full_key, s.clone());
+            }
+            toml::Value::Table(t) => flatten_into(table, &full_key, t),
+            _ => {}
+        }
+    }
+}
+
+/// Look up a UI string by key (unknown keys are returned as-is)
+pub fn tr(key: &'static str) -> &'static str {
+    if let Some(s) = STRINGS.get().and_then(|t| t.get(key)) {
+        return s.as_str();
+    }
+    // Not initialized (or unknown key): use the built-in English table
+    EN.iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| *v)
+        .unwrap_or(key)
+}
+
+/// Look up a UI string and substitute `{name}` placeholders
+pub fn trf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut s = tr(key).to_string();
+    for (name, value) in args {
+        s = s.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    s
+}
@@ -0,0 +1,511 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+
+use crate::config::KeysConfig;
+
+/// Actions available in the list view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Run a user-configured shell command (index into `Keymap::command`)
+    RunCommand(usize),
+    Quit,
+    Back,
+    FocusList,
+    FocusPreview,
+    Next,
+    Previous,
+    ToggleRead,
+    MarkRead,
+    MarkUnread,
+    ToggleUnreadFilter,
+    OpenInBrowser,
+    SaveAttachments,
+    Reload,
+    EditConfig,
+    SwitchAccount,
+    Compose,
+    ComposeWithAttachments,
+    Reply,
+    /// Reply to the mailing list a message came through
+    ReplyToList,
+    Search,
+    DeepSearch,
+    Archive,
+    Delete,
+    Spam,
+    ToggleContactPanel,
+    FilterBySender,
+    ShowRelated,
+    Move,
+    Print,
+    CopyLink,
+    /// Set (or with None, clear) the selected thread's triage mark
+    Triage(Option<char>),
+    ToggleTriageSort,
+    ReplyWithTemplate,
+    NextInThread,
+    PreviousInThread,
+    LinkHints,
+    /// Write the listed (searched/filtered) messages out as mbox or .eml files
+    Export {
+        eml: bool,
+    },
+    FindDuplicates,
+    Cleanup,
+    StripAttachments,
+    ReviewJunk,
+    /// Junk review: move to the inbox and run the ham command
+    NotSpam,
+    /// Junk review: delete the message file outright
+    DeleteForever,
+    /// In a bounce, jump to the message that could not be delivered
+    OpenBounced,
+    Agenda,
+    /// Open the tracking page for the package in a shipping notification
+    TrackPackage,
+    /// Start recording a macro into the register typed next, or stop recording
+    RecordMacro,
+    /// Replay the macro in the register typed next (after an optional count)
+    ReplayMacro,
+    /// Repeat the last change on the selected message
+    Repeat,
+    /// Select every listed message for a batch archive/delete/spam/read/move
+    SelectMatching,
+    /// Back to the full list: leave search results, junk review, the
+    /// sender filter and the selection
+    GoToInbox,
+    /// Show the log of changes made to mail
+    ActivityLog,
+    /// Show which external tools are installed and which features they back
+    Diagnostics,
+    /// Show how the selected message's thread was built
+    ThreadDebug,
+    /// Open an image or attachment with a handler picked from a menu
+    OpenWith,
+}
+
+impl Action {
+    /// Changes to the selected message that `.` can repeat
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Self::ToggleRead
+                | Self::MarkRead
+                | Self::MarkUnread
+                | Self::Archive
+                | Self::Delete
+                | Self::Spam
+                | Self::Triage(_)
+                | Self::StripAttachments
+                | Self::NotSpam
+                | Self::DeleteForever
+        )
+    }
+
+    /// Parse an action name as used in the [keys.bindings] config table
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "quit" => Self::Quit,
+            "back" => Self::Back,
+            "focus_list" => Self::FocusList,
+            "focus_preview" => Self::FocusPreview,
+            "next" => Self::Next,
+            "previous" => Self::Previous,
+            "toggle_read" => Self::ToggleRead,
+            "mark_read" => Self::MarkRead,
+            "mark_unread" => Self::MarkUnread,
+            "toggle_unread_filter" => Self::ToggleUnreadFilter,
+            "open_in_browser" => Self::OpenInBrowser,
+            "save_attachments" => Self::SaveAttachments,
+            "reload" => Self::Reload,
+            "edit_config" => Self::EditConfig,
+            "switch_account" => Self::SwitchAccount,
+            "compose" => Self::Compose,
+            "compose_with_attachments" => Self::ComposeWithAttachments,
+            "reply" => Self::Reply,
+            "reply_to_list" => Self::ReplyToList,
+            "search" => Self::Search,
+            "deep_search" => Self::DeepSearch,
+            "archive" => Self::Archive,
+            "delete" => Self::Delete,
+            "spam" => Self::Spam,
+            "toggle_contact_panel" => Self::ToggleContactPanel,
+            "filter_by_sender" => Self::FilterBySender,
+            "show_related" => Self::ShowRelated,
+            "move" => Self::Move,
+            "print" => Self::Print,
+            "copy_link" => Self::CopyLink,
+            "clear_triage" => Self::Triage(None),
+            "toggle_triage_sort" => Self::ToggleTriageSort,
+            "reply_with_template" => Self::ReplyWithTemplate,
+            "next_in_thread" => Self::NextInThread,
+            "previous_in_thread" => Self::PreviousInThread,
+            "link_hints" => Self::LinkHints,
+            "export_mbox" => Self::Export { eml: false },
+            "export_eml" => Self::Export { eml: true },
+            "find_duplicates" => Self::FindDuplicates,
+            "cleanup" => Self::Cleanup,
+            "strip_attachments" => Self::StripAttachments,
+            "review_junk" => Self::ReviewJunk,
+            "not_spam" => Self::NotSpam,
+            "delete_forever" => Self::DeleteForever,
+            "open_bounced" => Self::OpenBounced,
+            "agenda" => Self::Agenda,
+            "track_package" => Self::TrackPackage,
+            "record_macro" => Self::RecordMacro,
+            "replay_macro" => Self::ReplayMacro,
+            "repeat" => Self::Repeat,
+            "select_matching" => Self::SelectMatching,
+            "go_to_inbox" => Self::GoToInbox,
+            "activity_log" => Self::ActivityLog,
+            "diagnostics" => Self::Diagnostics,
+            "thread_debug" => Self::ThreadDebug,
+            "open_with" => Self::OpenWith,
+            _ => {
+                // triage_<char>, e.g. triage_1 or triage_w
+                let mut mark = name.strip_prefix("triage_")?.chars();
+                let c = mark.next()?;
+                if mark.next().is_some() {
+                    return None;
+                }
+                Self::Triage(Some(c))
+            }
+        })
+    }
+}
+
+/// Built-in bindings (the keys documented in the README)
+const DEFAULT_BINDINGS: &[(&str, Action)] = &[
+    ("q", Action::Quit),
+    ("Esc", Action::Back),
+    ("h", Action::FocusList),
+    ("Left", Action::FocusList),
+    ("l", Action::FocusPreview),
+    ("Right", Action::FocusPreview),
+    ("Enter", Action::FocusPreview),
+    ("j", Action::Next),
+    ("Down", Action::Next),
+    ("k", Action::Previous),
+    ("Up", Action::Previous),
+    ("u", Action::ToggleRead),
+    ("U", Action::ToggleUnreadFilter),
+    ("o", Action::OpenInBrowser),
+    ("a", Action::SaveAttachments),
+    ("R", Action::Reload),
+    ("S", Action::EditConfig),
+    ("Tab", Action::SwitchAccount),
+    ("c", Action::Compose),
+    ("C", Action::ComposeWithAttachments),
+    ("r", Action::Reply),
+    ("L", Action::ReplyToList),
+    ("/", Action::Search),
+    ("?", Action::DeepSearch),
+    ("i", Action::ToggleContactPanel),
+    ("F", Action::FilterBySender),
+    ("=", Action::ShowRelated),
+    ("m", Action::Move),
+    ("P", Action::Print),
+    ("Y", Action::CopyLink),
+    ("1", Action::Triage(Some('1'))),
+    ("2", Action::Triage(Some('2'))),
+    ("3", Action::Triage(Some('3'))),
+    ("0", Action::Triage(None)),
+    ("T", Action::ToggleTriageSort),
+    ("t", Action::ReplyWithTemplate),
+    ("]", Action::NextInThread),
+    ("[", Action::PreviousInThread),
+    ("f", Action::LinkHints),
+    ("E", Action::Export { eml: false }),
+    ("D", Action::FindDuplicates),
+    ("X", Action::Cleanup),
+    ("A", Action::StripAttachments),
+    ("J", Action::ReviewJunk),
+    ("N", Action::NotSpam),
+    ("x", Action::DeleteForever),
+    ("O", Action::OpenBounced),
+    ("G", Action::Agenda),
+    ("p", Action::TrackPackage),
+    ("Q", Action::RecordMacro),
+    ("@", Action::ReplayMacro),
+    (".", Action::Repeat),
+    ("*", Action::SelectMatching),
+    ("H", Action::ActivityLog),
+    ("V", Action::Diagnostics),
+    ("W", Action::ThreadDebug),
+    ("w", Action::OpenWith),
+];
+
+/// Gmail web shortcuts layered over the defaults
+const GMAIL_BINDINGS: &[(&str, Action)] = &[
+    ("e", Action::Archive),
+    ("y", Action::Archive),
+    ("#", Action::Delete),
+    ("!", Action::Spam),
+    ("I", Action::MarkRead),
+    ("U", Action::MarkUnread),
+    ("u", Action::Back),
+    ("v", Action::Move),
+    ("gi", Action::GoToInbox),
+    ("*a", Action::SelectMatching),
+];
+
+/// Result of feeding a key into the keymap
+pub enum KeyResult {
+    Action(Action),
+    /// Key is the start of a multi-key sequence; wait for more
+    Pending,
+    Unbound,
+}
+
+/// Maps key sequences (e.g. ["g", "i"], ["Tab"]) to list actions
+pub struct Keymap {
+    bindings: HashMap<Vec<String>, Action>,
+    commands: Vec<String>,
+}
+
+/// Split a config key string into a sequence: "Tab" is one key, "gi" is two,
+/// "C-xC-s" is Ctrl-x then Ctrl-s. A named key must end the string ("gTab")
+fn parse_sequence(keys: &str) -> Vec<String> {
+    let mut seq = Vec::new();
+    let mut rest = keys;
+    while !rest.is_empty() {
+        let (mut ctrl, mut alt) = (false, false);
+        // "C-" and "M-" only count as modifiers when a key follows
+        while rest.len() > 2 && (rest.starts_with("C-") || rest.starts_with("M-")) {
+            ctrl |= rest.starts_with('C');
+            alt |= rest.starts_with('M');
+            rest = &rest[2..];
+        }
+        // Same order as `key_name`, whichever way round the config has it
+        let mut key = String::new();
+        if ctrl {
+            key.push_str("C-");
+        }
+        if alt {
+            key.push_str("M-");
+        }
+        let len = if NAMED_KEYS.contains(&rest) {
+            rest.len()
+        } else {
+            rest.chars().next().map_or(0, char::len_utf8)
+        };
+        key.push_str(&rest[..len]);
+        rest = &rest[len..];
+        seq.push(key);
+    }
+    seq
+}
+
+impl Keymap {
+    /// Build from defaults, the configured preset, then per-key overrides.
+    /// Returns the keymap and any config errors (unknown presets/actions)
+    pub fn from_config(config: &KeysConfig) -> (Self, Vec<String>) {
+        let mut bindings: HashMap<Vec<String>, Action> = DEFAULT_BINDINGS
+            .iter()
+            .map(|(k, a)| (parse_sequence(k), *a))
+            .collect();
+        let mut errors = Vec::new();
+
+        match config.preset.as_deref() {
+            None | Some("default") => {}
+            Some("gmail") => {
+                for (k, a) in GMAIL_BINDINGS {
+                    let seq = parse_sequence(k);
+                    // A default key that starts a Gmail sequence (`*` of
+                    // `* a`) would fire before the rest is typed
+                    bindings.retain(|keys, _| keys.len() >= seq.len() || !seq.starts_with(keys));
+                    bindings.insert(seq, *a);
+                }
+            }
+            Some(other) => errors.push(format!("Unknown key preset '{}'", other)),
+        }
+
+        for (keys, name) in &config.bindings {
+            match Action::from_name(name) {
+                Some(action) => {
+                    bindings.insert(parse_sequence(keys), action);
+                }
+                None => errors.push(format!("Unknown action '{}' for key '{}'", name, keys)),
+            }
+        }
+
+        // Commands are bound last so they win over built-in keys
+        let mut commands = Vec::new();
+        for (keys, command) in &config.commands {
+            bindings.insert(parse_sequence(keys), Action::RunCommand(commands.len()));
+            commands.push(command.clone());
+        }
+
+        (Self { bindings, commands }, errors)
+    }
+
+    /// Command line for an `Action::RunCommand` index
+    pub fn command(&self, index: usize) -> Option<&str> {
+        self.commands.get(index).map(|s| s.as_str())
+    }
+
+    /// Resolve `key` following any keys already pending in a sequence
+    pub fn resolve(&self, pending: &[String], key: &str) -> KeyResult {
+        let mut seq = pending.to_vec();
+        seq.push(key.to_string());
+        if let Some(action) = self.bindings.get(&seq) {
+            return KeyResult::Action(*action);
+        }
+        if self.bindings.keys().any(|k| k.starts_with(&seq)) {
+            return KeyResult::Pending;
+        }
+        KeyResult::Unbound
+    }
+}
+
+/// Key names accepted in config besides single characters
+const NAMED_KEYS: &[&str] = &[
+    "Enter",
+    "Esc",
+    "Tab",
+    "Backspace",
+    "Left",
+    "Right",
+    "Up",
+    "Down",
+    "PageUp",
+    "PageDown",
+    "Home",
+    "End",
+];
+
+/// Name of a key event as written in config ("j", "U", "Tab", "Enter", ...),
+/// with "C-" for Ctrl and "M-" for Alt, so Ctrl-x is "C-x" and not "x"
+pub fn key_name(key: &KeyEvent) -> Option<String> {
+    let mut prefix = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        prefix.push_str("C-");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        prefix.push_str("M-");
+    }
+    let name = match key.code {
+        KeyCode::Char(c) => return Some(format!("{}{}", prefix, c)),
+        KeyCode::Enter => "Enter",
+        KeyCode::Esc => "Esc",
+        KeyCode::Tab => "Tab",
+        KeyCode::Backspace => "Backspace",
+        KeyCode::Left => "Left",
+        KeyCode::Right => "Right",
+        KeyCode::Up => "Up",
+        KeyCode::Down => "Down",
+        KeyCode::PageUp => "PageUp",
+        KeyCode::PageDown => "PageDown",
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        _ => return None,
+    };
+    Some(format!("{}{}", prefix, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keymap(preset: Option<&str>, bindings: &[(&str, &str)]) -> Keymap {
+        let config = KeysConfig {
+            preset: preset.map(str::to_string),
+            bindings: bindings
+                .iter()
+                .map(|(k, a)| (k.to_string(), a.to_string()))
+                .collect(),
+            ..KeysConfig::default()
+        };
+        let (keymap, errors) = Keymap::from_config(&config);
+        assert!(errors.is_empty(), "{:?}", errors);
+        keymap
+    }
+
+    /// Feed `keys` one at a time, like the event loop does
+    fn press(keymap: &Keymap, keys: &[&str]) -> Option<Action> {
+        let mut pending = Vec::new();
+        for key in keys {
+            match keymap.resolve(&pending, key) {
+                KeyResult::Action(action) => return Some(action),
+                KeyResult::Pending => pending.push(key.to_string()),
+                KeyResult::Unbound => return None,
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        assert_eq!(parse_sequence("Tab"), ["Tab"]);
+        assert_eq!(parse_sequence("gi"), ["g", "i"]);
+        assert_eq!(parse_sequence("gTab"), ["g", "Tab"]);
+        assert_eq!(parse_sequence("C-xC-s"), ["C-x", "C-s"]);
+        assert_eq!(parse_sequence("M-C-Enter"), ["C-M-Enter"]);
+        assert_eq!(parse_sequence("C-"), ["C", "-"]);
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let keymap = keymap(None, &[]);
+        assert_eq!(press(&keymap, &["j"]), Some(Action::Next));
+        assert_eq!(press(&keymap, &["Down"]), Some(Action::Next));
+        assert_eq!(press(&keymap, &["u"]), Some(Action::ToggleRead));
+        assert_eq!(press(&keymap, &["*"]), Some(Action::SelectMatching));
+        assert_eq!(press(&keymap, &["g"]), None);
+    }
+
+    #[test]
+    fn test_gmail_preset() {
+        let keymap = keymap(Some("gmail"), &[]);
+        assert!(matches!(keymap.resolve(&[], "g"), KeyResult::Pending));
+        assert_eq!(press(&keymap, &["g", "i"]), Some(Action::GoToInbox));
+        assert_eq!(press(&keymap, &["g", "x"]), None);
+        // `*` alone would select before `a` is typed, so it only starts `* a`
+        assert!(matches!(keymap.resolve(&[], "*"), KeyResult::Pending));
+        assert_eq!(press(&keymap, &["*", "a"]), Some(Action::SelectMatching));
+        assert_eq!(press(&keymap, &["u"]), Some(Action::Back));
+        assert_eq!(press(&keymap, &["e"]), Some(Action::Archive));
+        assert_eq!(press(&keymap, &["j"]), Some(Action::Next));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let keymap = keymap(
+            Some("gmail"),
+            &[("e", "reload"), ("gr", "reply"), ("C-d", "delete")],
+        );
+        assert_eq!(press(&keymap, &["e"]), Some(Action::Reload));
+        assert_eq!(press(&keymap, &["g", "r"]), Some(Action::Reply));
+        assert_eq!(press(&keymap, &["g", "i"]), Some(Action::GoToInbox));
+        assert_eq!(press(&keymap, &["C-d"]), Some(Action::Delete));
+
+        let config = KeysConfig {
+            preset: Some("emacs".to_string()),
+            bindings: [("z".to_string(), "nap".to_string())].into(),
+            ..KeysConfig::default()
+        };
+        let (_, errors) = Keymap::from_config(&config);
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_key_name_modifiers() {
+        let key = |code, modifiers| key_name(&KeyEvent::new(code, modifiers));
+        assert_eq!(key(KeyCode::Char('x'), KeyModifiers::NONE).unwrap(), "x");
+        assert_eq!(key(KeyCode::Char('U'), KeyModifiers::SHIFT).unwrap(), "U");
+        assert_eq!(
+            key(KeyCode::Char('x'), KeyModifiers::CONTROL).unwrap(),
+            "C-x"
+        );
+        assert_eq!(
+            key(KeyCode::Enter, KeyModifiers::CONTROL | KeyModifiers::ALT).unwrap(),
+            "C-M-Enter"
+        );
+        assert_eq!(key(KeyCode::F(1), KeyModifiers::NONE), None);
+
+        // Ctrl-j is not j
+        let keymap = keymap(None, &[]);
+        let ctrl_j = key(KeyCode::Char('j'), KeyModifiers::CONTROL).unwrap();
+        assert_eq!(press(&keymap, &[&ctrl_j]), None);
+    }
+}
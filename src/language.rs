@@ -0,0 +1,65 @@
+/// Language detected in a message body
+#[derive(Debug, Clone, PartialEq)]
+pub struct Language {
+    /// ISO 639-3 code from the detector, e.g. "deu"
+    pub code: String,
+    /// BCP 47 tag for the Content-Language header, e.g. "de"
+    pub tag: String,
+    /// English name for display, e.g. "German"
+    pub name: String,
+}
+
+/// ISO 639-3 -> 639-1 for common languages (BCP 47 prefers the short code)
+const SHORT_CODES: &[(&str, &str)] = &[
+    ("ara", "ar"),
+    ("ces", "cs"),
+    ("cmn", "zh"),
+    ("dan", "da"),
+    ("deu", "de"),
+    ("ell", "el"),
+    ("eng", "en"),
+    ("fin", "fi"),
+    ("fra", "fr"),
+    ("heb", "he"),
+    ("hin", "hi"),
+    ("hun", "hu"),
+    ("ita", "it"),
+    ("jpn", "ja"),
+    ("kor", "ko"),
+    ("nld", "nl"),
+    ("nob", "nb"),
+    ("pol", "pl"),
+    ("por", "pt"),
+    ("ron", "ro"),
+    ("rus", "ru"),
+    ("spa", "es"),
+    ("swe", "sv"),
+    ("tur", "tr"),
+    ("ukr", "uk"),
+];
+
+/// Detect the language of `text`, ignoring quoted lines so a reply chain
+/// in another language doesn't outvote the latest message
+pub fn detect(text: &str) -> Option<Language> {
+    let own_text: String = text
+        .lines()
+        .filter(|l| !l.trim_start().starts_with('>'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let info = whatlang::detect(&own_text)?;
+    if !info.is_reliable() {
+        return None;
+    }
+
+    let code = info.lang().code().to_string();
+    let tag = SHORT_CODES
+        .iter()
+        .find(|(long, _)| *long == code)
+        .map(|(_, short)| short.to_string())
+        .unwrap_or_else(|| code.clone());
+    Some(Language {
+        code,
+        tag,
+        name: info.lang().eng_name().to_string(),
+    })
+}
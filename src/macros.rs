@@ -0,0 +1,153 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use std::collections::{HashMap, VecDeque};
+
+/// Keys a single top-level replay may feed, so a macro that replays itself
+/// (directly or through another register) stops instead of looping forever
+const REPLAY_BUDGET: usize = 10_000;
+
+/// What the key after the record/replay key is read as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MacroPrompt {
+    Record,
+    /// Digits typed so far form a repeat count
+    Replay {
+        count: usize,
+    },
+}
+
+/// Outcome of a key typed at the register prompt
+pub enum PromptResult {
+    /// A count digit; still waiting for the register
+    Pending,
+    Recording,
+    Replaying {
+        register: char,
+        count: usize,
+    },
+    Empty(char),
+    Cancelled,
+}
+
+/// Keyboard macros: key sequences recorded into single-character registers
+/// and fed back through the main loop on replay
+#[derive(Default)]
+pub struct Macros {
+    registers: HashMap<char, Vec<KeyEvent>>,
+    /// Register and keys of the recording in progress
+    recording: Option<(char, Vec<KeyEvent>)>,
+    pub prompt: Option<MacroPrompt>,
+    /// Last register replayed, for `@@`
+    last: Option<char>,
+    replay: VecDeque<KeyEvent>,
+    budget: usize,
+}
+
+impl Macros {
+    /// Register being recorded into, if any
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    /// Add a typed key to the recording in progress
+    pub fn record_key(&mut self, key: KeyEvent) {
+        if let Some((_, keys)) = &mut self.recording {
+            keys.push(key);
+        }
+    }
+
+    /// Ask for a register to record into, or finish the recording in
+    /// progress. `seq_len` keys (the binding that stopped it) are dropped
+    /// from the end. Returns the register and key count that were saved
+    pub fn toggle_recording(&mut self, seq_len: usize) -> Option<(char, usize)> {
+        match self.recording.take() {
+            Some((register, mut keys)) => {
+                keys.truncate(keys.len().saturating_sub(seq_len));
+                let len = keys.len();
+                self.registers.insert(register, keys);
+                Some((register, len))
+            }
+            None => {
+                self.prompt = Some(MacroPrompt::Record);
+                None
+            }
+        }
+    }
+
+    /// Ask for a (count and) register to replay
+    pub fn prompt_replay(&mut self) {
+        self.prompt = Some(MacroPrompt::Replay { count: 0 });
+    }
+
+    /// Feed a key to the register prompt. `@` as the register means the
+    /// last one replayed; Esc cancels
+    pub fn prompt_key(&mut self, key: &KeyEvent) -> PromptResult {
+        let Some(prompt) = self.prompt.take() else {
+            return PromptResult::Cancelled;
+        };
+        let KeyCode::Char(c) = key.code else {
+            return PromptResult::Cancelled;
+        };
+        match prompt {
+            MacroPrompt::Record => {
+                self.recording = Some((c, Vec::new()));
+                PromptResult::Recording
+            }
+            MacroPrompt::Replay { count } => {
+                if let Some(digit) = c.to_digit(10) {
+                    self.prompt = Some(MacroPrompt::Replay {
+                        count: (count * 10 + digit as usize).min(REPLAY_BUDGET),
+                    });
+                    return PromptResult::Pending;
+                }
+                let register = match (c, self.last) {
+                    ('@', Some(last)) => last,
+                    ('@', None) => return PromptResult::Cancelled,
+                    _ => c,
+                };
+                if self
+                    .registers
+                    .get(&register)
+                    .is_none_or(|keys| keys.is_empty())
+                {
+                    return PromptResult::Empty(register);
+                }
+                self.last = Some(register);
+                PromptResult::Replaying {
+                    register,
+                    count: count.max(1),
+                }
+            }
+        }
+    }
+
+    /// Queue a register's keys `count` times. A replay started by replayed
+    /// keys (`nested`) runs before the rest of the outer one and shares its budget
+    pub fn replay(&mut self, register: char, count: usize, nested: bool) {
+        let Some(keys) = self.registers.get(&register) else {
+            return;
+        };
+        if !nested {
+            self.replay.clear();
+            self.budget = REPLAY_BUDGET;
+        }
+        for _ in 0..count {
+            for key in keys.iter().rev() {
+                self.replay.push_front(*key);
+            }
+            if self.replay.len() > self.budget {
+                break;
+            }
+        }
+    }
+
+    /// Next queued replay key, if a replay is running
+    pub fn next_replay_key(&mut self) -> Option<KeyEvent> {
+        if self.budget == 0 {
+            self.replay.clear();
+            return None;
+        }
+        let key = self.replay.pop_front()?;
+        self.budget -= 1;
+        Some(key)
+    }
+}
@@ -1,18 +1,100 @@
 use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
-use std::time::SystemTime;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
 
 use anyhow::Result;
 
 use super::types::{CachedEnvelope, Envelope};
 
-const CACHE_VERSION: u32 = 3; // Bumped for fast path
+/// Bump whenever `Envelope`'s serialized fields change. Caches from
+/// `FIRST_MIGRATABLE_VERSION` on are migrated, not thrown away
+const CACHE_VERSION: u32 = 7; // Bumped for the migratable layout
 
+/// Oldest cache that can be migrated. Version 6 stored a map of file path
+/// to `CachedEnvelopeV6`
+const FIRST_MIGRATABLE_VERSION: u32 = 6;
+
+/// First version written as a `CacheHeader` followed by the envelopes
+const FIRST_HEADER_VERSION: u32 = 7;
+
+/// Start of the cache file, followed by the envelopes in the same order as
+/// `files`. This layout must never change: it is what lets an outdated
+/// cache be read far enough to migrate it
 #[derive(serde::Serialize, serde::Deserialize)]
-struct CacheFile {
+struct CacheHeader {
     version: u32,
-    envelopes: HashMap<String, CachedEnvelope>, // keyed by file path
+    /// Cached file paths and their mtimes
+    files: Vec<(String, u64)>,
+}
+
+/// `Address` as version 6 serialized it: name, addr
+type AddressV6 = (Option<String>, String);
+
+/// `Envelope` as version 6 serialized it: id, flags, subject, from, to,
+/// date, timestamp, has_attachment, has_inline_images, message_id,
+/// in_reply_to, references, is_sent, is_auto, file_path, bounce_of.
+/// Frozen here so a version 6 cache stays readable whatever `Envelope` becomes
+type EnvelopeV6 = (
+    String,
+    Vec<String>,
+    Option<String>,
+    Option<AddressV6>,
+    Option<AddressV6>,
+    Option<String>,
+    Option<i64>,
+    bool,
+    bool,
+    Option<String>,
+    Option<String>,
+    Vec<String>,
+    bool,
+    bool,
+    Option<String>,
+    Option<String>,
+);
+
+/// `CachedEnvelope` as version 6 serialized it: envelope, mtime
+type CachedEnvelopeV6 = (EnvelopeV6, u64);
+
+/// Set when another mailtui instance holds the lock: it owns the envelope
+/// cache, so this one reads it but never writes it
+static CACHE_READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Take the per-user instance lock, held until the process exits. Returns
+/// false if another mailtui holds it; the envelope cache then becomes
+/// read-only for this process. Where locking isn't supported, carry on as
+/// the only instance
+pub fn lock_instance() -> bool {
+    static LOCK: OnceLock<File> = OnceLock::new();
+    let Some(path) = dirs::cache_dir().map(|p| p.join("mailtui/instance.lock")) else {
+        return true;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let Ok(file) = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+    else {
+        return true;
+    };
+    match file.try_lock() {
+        Ok(()) => {
+            let _ = LOCK.set(file);
+            true
+        }
+        Err(fs::TryLockError::WouldBlock) => {
+            CACHE_READ_ONLY.store(true, Ordering::Relaxed);
+            false
+        }
+        Err(fs::TryLockError::Error(_)) => true,
+    }
 }
 
 /// Quick check if cache is likely still valid by comparing file counts
@@ -28,75 +110,249 @@ fn cache_path() -> Option<std::path::PathBuf> {
     dirs::cache_dir().map(|p| p.join("mailtui/envelopes.bin"))
 }
 
-/// Load envelope cache from disk (binary format for speed)
-pub fn load_cache() -> HashMap<String, CachedEnvelope> {
-    let path = match cache_path() {
-        Some(p) => p,
-        None => return HashMap::new(),
+/// Load envelope cache from disk (binary format for speed), keyed by file
+/// path. A cache written by an older version is migrated with `reparse`,
+/// which reads one file's headers
+pub fn load_cache(
+    reparse: impl Fn(&Path) -> Option<Envelope> + Sync,
+) -> HashMap<String, CachedEnvelope> {
+    match cache_path() {
+        Some(path) => load_cache_at(&path, reparse),
+        None => HashMap::new(),
+    }
+}
+
+fn load_cache_at(
+    path: &Path,
+    reparse: impl Fn(&Path) -> Option<Envelope> + Sync,
+) -> HashMap<String, CachedEnvelope> {
+    let Ok(file) = File::open(path) else {
+        return HashMap::new();
     };
 
-    if !path.exists() {
+    // The version comes first in every layout; check it before reading on,
+    // since older layouts would decode as garbage
+    let mut reader = BufReader::new(file);
+    let version: u32 = match bincode::deserialize_from(&mut reader) {
+        Ok(version) => version,
+        Err(_) => return HashMap::new(),
+    };
+    if !(FIRST_MIGRATABLE_VERSION..=CACHE_VERSION).contains(&version) {
         return HashMap::new();
     }
-
-    let file = match File::open(&path) {
-        Ok(f) => f,
+    if version < FIRST_HEADER_VERSION {
+        // Only the paths are kept: the envelopes are re-read like any
+        // other outdated cache's
+        let cached: HashMap<String, CachedEnvelopeV6> = match bincode::deserialize_from(&mut reader)
+        {
+            Ok(cached) => cached,
+            Err(_) => return HashMap::new(),
+        };
+        let files = cached
+            .into_iter()
+            .map(|(path, (_, mtime))| (path, mtime))
+            .collect();
+        return migrate(path, files, reparse);
+    }
+    // The rest of the `CacheHeader`
+    let files: Vec<(String, u64)> = match bincode::deserialize_from(&mut reader) {
+        Ok(files) => files,
         Err(_) => return HashMap::new(),
     };
+    if version != CACHE_VERSION {
+        return migrate(path, files, reparse);
+    }
 
-    let reader = BufReader::new(file);
-    let cache: CacheFile = match bincode::deserialize_from(reader) {
-        Ok(c) => c,
+    let envelopes: Vec<Envelope> = match bincode::deserialize_from(&mut reader) {
+        Ok(envelopes) => envelopes,
         Err(_) => return HashMap::new(),
     };
-
-    // Check version
-    if cache.version != CACHE_VERSION {
+    if envelopes.len() != files.len() {
         return HashMap::new();
     }
+    files
+        .into_iter()
+        .zip(envelopes)
+        .map(|((path, mtime), envelope)| (path, CachedEnvelope { envelope, mtime }))
+        .collect()
+}
 
-    cache.envelopes
+/// Bring an outdated cache up to date by re-reading just the headers of the
+/// files it lists, in parallel, instead of starting over with a full scan.
+/// Files gone since are dropped; the upgraded cache is written back to `path`
+fn migrate(
+    path: &Path,
+    files: Vec<(String, u64)>,
+    reparse: impl Fn(&Path) -> Option<Envelope> + Sync,
+) -> HashMap<String, CachedEnvelope> {
+    use rayon::prelude::*;
+
+    let migrated: HashMap<String, CachedEnvelope> = files
+        .into_par_iter()
+        .filter_map(|(path, _)| {
+            let mtime = get_file_mtime(&path)?;
+            let envelope = reparse(Path::new(&path))?;
+            Some((path, CachedEnvelope { envelope, mtime }))
+        })
+        .collect();
+    let entries = migrated
+        .iter()
+        .map(|(path, cached)| (path.clone(), cached.mtime, &cached.envelope))
+        .collect();
+    let _ = write_cache(path, entries);
+    migrated
 }
 
 /// Save envelope cache to disk (binary format for speed)
 pub fn save_cache(envelopes: &[Envelope]) -> Result<()> {
-    let path = match cache_path() {
-        Some(p) => p,
-        None => return Ok(()),
-    };
+    let entries = envelopes
+        .iter()
+        .filter_map(|env| {
+            let file_path = env.file_path.clone()?;
+            let mtime = get_file_mtime(&file_path).unwrap_or(0);
+            Some((file_path, mtime, env))
+        })
+        .collect();
+    match cache_path() {
+        Some(path) => write_cache(&path, entries),
+        None => Ok(()),
+    }
+}
+
+/// Write the cache file: a `CacheHeader` listing (path, mtime) of each
+/// entry, then the envelopes in the same order
+fn write_cache(path: &Path, entries: Vec<(String, u64, &Envelope)>) -> Result<()> {
+    if CACHE_READ_ONLY.load(Ordering::Relaxed) {
+        return Ok(());
+    }
 
     // Ensure parent directory exists
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
 
-    // Build cache map
-    let mut cache_map = HashMap::new();
-    for env in envelopes {
-        if let Some(ref file_path) = env.file_path {
-            let mtime = get_file_mtime(file_path).unwrap_or(0);
-            cache_map.insert(
-                file_path.clone(),
-                CachedEnvelope {
-                    envelope: env.clone(),
-                    mtime,
-                },
-            );
-        }
+    let mut files = Vec::with_capacity(entries.len());
+    let mut envelopes = Vec::with_capacity(entries.len());
+    for (file_path, mtime, envelope) in entries {
+        files.push((file_path, mtime));
+        envelopes.push(envelope);
     }
-
-    let cache = CacheFile {
+    let header = CacheHeader {
         version: CACHE_VERSION,
-        envelopes: cache_map,
+        files,
     };
 
-    let file = File::create(&path)?;
-    let writer = BufWriter::new(file);
-    bincode::serialize_into(writer, &cache)?;
+    // Write then rename, so quitting mid-save never leaves a truncated cache.
+    // The temp name is per process in case the lock isn't supported here
+    let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    let file = File::create(&tmp_path)?;
+    let mut writer = BufWriter::new(file);
+    bincode::serialize_into(&mut writer, &header)?;
+    bincode::serialize_into(&mut writer, &envelopes)?;
+    writer.flush()?;
+    fs::rename(&tmp_path, path)?;
 
     Ok(())
 }
 
+/// Rendered HTML cache entry for a message file, and the file's mtime.
+/// Keyed by the maildir unique name rather than the full path, so flag
+/// changes (which rename the file) keep hitting the cache
+fn html_cache_entry(file_path: &str) -> Option<(std::path::PathBuf, u64)> {
+    use sha2::{Digest, Sha256};
+
+    let mtime = get_file_mtime(file_path)?;
+    let filename = std::path::Path::new(file_path).file_name()?.to_str()?;
+    let unique = filename.split([':', ';']).next().unwrap_or(filename);
+    let hash = Sha256::digest(unique.as_bytes());
+    let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+    let dir = dirs::cache_dir()?.join("mailtui/html");
+    Some((dir.join(hex), mtime))
+}
+
+/// Rendered HTML entries not read for this long are pruned
+const HTML_CACHE_MAX_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 90);
+
+/// Past this total size the least recently read rendered HTML entries go
+const HTML_CACHE_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Previously rendered text of a message's HTML body, if the file is unchanged
+pub fn load_rendered_html(file_path: &str) -> Option<String> {
+    let (path, mtime) = html_cache_entry(file_path)?;
+    let content = fs::read_to_string(&path).ok()?;
+    // First line is the mtime the text was rendered from
+    let (cached_mtime, text) = content.split_once('\n')?;
+    if cached_mtime.parse::<u64>().ok()? != mtime {
+        return None;
+    }
+    // The entry's own mtime records when it was last read, for pruning
+    if let Ok(file) = File::options().write(true).open(&path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    Some(text.to_string())
+}
+
+/// Drop rendered HTML entries not read in `HTML_CACHE_MAX_AGE`, then the
+/// least recently read ones until the cache fits `HTML_CACHE_MAX_BYTES`.
+/// Returns how many entries were removed
+pub fn prune_rendered_html() -> usize {
+    let Some(dir) = dirs::cache_dir().map(|p| p.join("mailtui/html")) else {
+        return 0;
+    };
+    prune_dir(&dir, HTML_CACHE_MAX_AGE, HTML_CACHE_MAX_BYTES)
+}
+
+fn prune_dir(dir: &Path, max_age: Duration, max_bytes: u64) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let now = SystemTime::now();
+    let mut removed = 0;
+    let mut kept = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if !meta.is_file() {
+            continue;
+        }
+        let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        let age = now.duration_since(modified).unwrap_or_default();
+        if age > max_age {
+            if fs::remove_file(entry.path()).is_ok() {
+                removed += 1;
+            }
+        } else {
+            kept.push((modified, meta.len(), entry.path()));
+        }
+    }
+    let mut total: u64 = kept.iter().map(|(_, len, _)| len).sum();
+    // Oldest first
+    kept.sort();
+    for (_, len, path) in kept {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(path).is_ok() {
+            total -= len;
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Remember the rendered text of a message's HTML body
+pub fn save_rendered_html(file_path: &str, text: &str) -> Result<()> {
+    let Some((path, mtime)) = html_cache_entry(file_path) else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, format!("{}\n{}", mtime, text))?;
+    Ok(())
+}
+
 /// Get file modification time in seconds since epoch
 pub fn get_file_mtime(path: &str) -> Option<u64> {
     let metadata = fs::metadata(path).ok()?;
@@ -164,3 +420,73 @@ pub fn get_files_to_parse(
 
     (to_parse, from_cache)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(subject: &str, path: &Path) -> Envelope {
+        Envelope {
+            id: "1".to_string(),
+            subject: Some(subject.to_string()),
+            message_id: Some("<a@example.com>".to_string()),
+            file_path: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_migrate_version_6_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let mail = dir.path().join("mail");
+        fs::write(&mail, "Subject: fresh\n\nBody\n").unwrap();
+        let gone = dir.path().join("gone");
+
+        // Version 6: the version, then a map of path to cached envelope
+        let mut old = HashMap::new();
+        for path in [&mail, &gone] {
+            let cached = CachedEnvelope {
+                envelope: envelope("stale", path),
+                mtime: 1,
+            };
+            old.insert(path.to_string_lossy().to_string(), cached);
+        }
+        let cache = dir.path().join("envelopes.bin");
+        let mut writer = BufWriter::new(File::create(&cache).unwrap());
+        bincode::serialize_into(&mut writer, &6u32).unwrap();
+        bincode::serialize_into(&mut writer, &old).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+
+        let loaded = load_cache_at(&cache, |path| Some(envelope("fresh", path)));
+        let key = mail.to_string_lossy().to_string();
+        assert_eq!(loaded.len(), 1, "the missing file is dropped");
+        assert_eq!(loaded[&key].envelope.subject.as_deref(), Some("fresh"));
+        assert_eq!(Some(loaded[&key].mtime), get_file_mtime(&key));
+
+        // Written back in the current layout, so no second migration
+        let reloaded = load_cache_at(&cache, |_| panic!("migrated twice"));
+        assert_eq!(reloaded[&key].envelope.subject.as_deref(), Some("fresh"));
+    }
+
+    #[test]
+    fn test_prune_dir_by_age_then_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let day = Duration::from_secs(60 * 60 * 24);
+        for (name, age_days) in [("stale", 100), ("old", 3), ("recent", 1), ("new", 0)] {
+            let path = dir.path().join(name);
+            fs::write(&path, [0u8; 10]).unwrap();
+            let file = File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - day * age_days).unwrap();
+        }
+        // "stale" is past the age limit, then "old" goes to fit 20 bytes
+        assert_eq!(prune_dir(dir.path(), day * 90, 20), 2);
+        let mut left: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(left, ["new", "recent"]);
+    }
+}
@@ -0,0 +1,335 @@
+use mail_parser::{MimeHeaders, PartType};
+use rayon::prelude::*;
+
+/// One event from a calendar invite, with the message it came in
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub summary: String,
+    /// "YYYY-MM-DD HH:MM" as written in the invite ("UTC" appended for Z times),
+    /// or just the date for all-day events
+    pub start: String,
+    /// Sort key: the start's wall-clock time read as UTC, like list dates
+    pub start_key: i64,
+    pub location: Option<String>,
+    pub message_id: Option<String>,
+}
+
+/// Upcoming events in the calendar invites among `messages` (file path and
+/// Message-ID, newest first), soonest first. Events that started before
+/// today and cancelled events are left out; of several invites for the
+/// same event, the newest wins
+pub fn upcoming_events(messages: &[(String, Option<String>)], now: i64) -> Vec<CalendarEvent> {
+    let today = now - now.rem_euclid(86_400);
+    let found: Vec<Vec<CalendarEvent>> = messages
+        .par_iter()
+        .map(|(file_path, message_id)| {
+            let mut events = message_events(file_path);
+            for event in &mut events {
+                event.message_id = message_id.clone();
+            }
+            events
+        })
+        .collect();
+
+    let mut events: Vec<CalendarEvent> = Vec::new();
+    for event in found.into_iter().flatten() {
+        if event.start_key < today {
+            continue;
+        }
+        let seen = events
+            .iter()
+            .any(|e| e.summary == event.summary && e.start == event.start);
+        if !seen {
+            events.push(event);
+        }
+    }
+    events.sort_by_key(|e| e.start_key);
+    events
+}
+
+/// Events in the text/calendar (or .ics attachment) parts of one message
+fn message_events(file_path: &str) -> Vec<CalendarEvent> {
+    let Ok(raw) = std::fs::read(file_path) else {
+        return Vec::new();
+    };
+    // Cheap pre-check: most mail has no calendar part, so skip parsing it
+    let has_calendar = raw
+        .windows(13)
+        .any(|w| w.eq_ignore_ascii_case(b"text/calendar"))
+        || raw
+            .windows(15)
+            .any(|w| w.eq_ignore_ascii_case(b"application/ics"));
+    if !has_calendar {
+        return Vec::new();
+    }
+    let Some(message) = mail_parser::MessageParser::default().parse(&raw) else {
+        return Vec::new();
+    };
+
+    let mut events = Vec::new();
+    for part in &message.parts {
+        let is_calendar = part.content_type().is_some_and(|ct| {
+            let subtype = ct.subtype().unwrap_or("");
+            (ct.ctype().eq_ignore_ascii_case("text") && subtype.eq_ignore_ascii_case("calendar"))
+                || (ct.ctype().eq_ignore_ascii_case("application")
+                    && subtype.eq_ignore_ascii_case("ics"))
+        });
+        if !is_calendar {
+            continue;
+        }
+        let text = match &part.body {
+            PartType::Text(text) => text.to_string(),
+            PartType::Binary(data) | PartType::InlineBinary(data) => {
+                String::from_utf8_lossy(data).to_string()
+            }
+            _ => continue,
+        };
+        // Invites often carry the same calendar inline and as an attachment
+        for event in parse_ics(&text) {
+            if !events
+                .iter()
+                .any(|e: &CalendarEvent| e.summary == event.summary && e.start == event.start)
+            {
+                events.push(event);
+            }
+        }
+    }
+    events
+}
+
+/// The VEVENTs of an iCalendar text (RFC 5545): summary, start and location.
+/// Cancellations (METHOD:CANCEL or STATUS:CANCELLED) yield nothing
+pub fn parse_ics(text: &str) -> Vec<CalendarEvent> {
+    // Unfold: a line starting with a space or tab continues the previous one
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut cancelled_calendar = false;
+    let mut current: Option<PendingEvent> = None;
+    for line in &lines {
+        let Some((name, params, value)) = split_property(line) else {
+            continue;
+        };
+        match (name.as_str(), &mut current) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(PendingEvent::default());
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let Some(event) = current.take() else {
+                    continue;
+                };
+                match event.start {
+                    Some((start, start_key)) if !event.cancelled && !cancelled_calendar => events
+                        .push(CalendarEvent {
+                            summary: event.summary.unwrap_or_default(),
+                            start,
+                            start_key,
+                            location: event.location,
+                            message_id: None,
+                        }),
+                    _ => {}
+                }
+            }
+            ("METHOD", None) => cancelled_calendar = value.eq_ignore_ascii_case("CANCEL"),
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape(value)),
+            ("DTSTART", Some(event)) => event.start = parse_ics_date(value, params),
+            ("LOCATION", Some(event)) if !value.is_empty() => {
+                event.location = Some(unescape(value))
+            }
+            ("STATUS", Some(event)) => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    events
+}
+
+/// A VEVENT whose END hasn't been reached yet
+#[derive(Default)]
+struct PendingEvent {
+    summary: Option<String>,
+    start: Option<(String, i64)>,
+    location: Option<String>,
+    cancelled: bool,
+}
+
+/// Split "NAME;PARAM=x:VALUE" into the upper-cased name, the params and the value.
+/// Colons inside quoted params (e.g. TZID="...") don't end the name part
+fn split_property(line: &str) -> Option<(String, &str, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name.trim().to_ascii_uppercase(), params, value.trim()))
+}
+
+/// "20240502T150000Z", "20240502T150000" or (all-day) "20240502" ->
+/// display string and sort key
+fn parse_ics_date(value: &str, params: &str) -> Option<(String, i64)> {
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    let (date, time) = value.split_once('T').unwrap_or((value, ""));
+    if date.len() != 8 || !digits(date) {
+        return None;
+    }
+    let utc = time.ends_with('Z');
+    let time = time.trim_end_matches('Z');
+    let all_day = time.is_empty()
+        || params
+            .split(';')
+            .any(|p| p.trim().eq_ignore_ascii_case("VALUE=DATE"));
+    if !all_day && (time.len() < 4 || !digits(time)) {
+        return None;
+    }
+
+    let datetime = mail_parser::DateTime {
+        year: date[..4].parse().ok()?,
+        month: date[4..6].parse().ok()?,
+        day: date[6..8].parse().ok()?,
+        hour: if all_day { 0 } else { time[..2].parse().ok()? },
+        minute: if all_day { 0 } else { time[2..4].parse().ok()? },
+        second: 0,
+        tz_before_gmt: false,
+        tz_hour: 0,
+        tz_minute: 0,
+    };
+    let display = if all_day {
+        format!(
+            "{:04}-{:02}-{:02}",
+            datetime.year, datetime.month, datetime.day
+        )
+    } else {
+        format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}{}",
+            datetime.year,
+            datetime.month,
+            datetime.day,
+            datetime.hour,
+            datetime.minute,
+            if utc { " UTC" } else { "" }
+        )
+    };
+    Some((display, datetime.to_timestamp()))
+}
+
+/// Undo iCalendar TEXT escaping (\n, \, \; \\)
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar(method: &str, event: &str) -> String {
+        format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nMETHOD:{method}\r\nBEGIN:VEVENT\r\n{event}END:VEVENT\r\nEND:VCALENDAR\r\n"
+        )
+    }
+
+    #[test]
+    fn test_split_property() {
+        assert_eq!(
+            split_property("dtstart;TZID=\"Europe/Paris: CET\":20240502T150000"),
+            Some((
+                "DTSTART".to_string(),
+                "TZID=\"Europe/Paris: CET\"",
+                "20240502T150000"
+            ))
+        );
+        assert_eq!(
+            split_property("SUMMARY:Lunch: pizza "),
+            Some(("SUMMARY".to_string(), "", "Lunch: pizza"))
+        );
+        assert_eq!(split_property("no value here"), None);
+    }
+
+    #[test]
+    fn test_parse_ics_date() {
+        assert_eq!(
+            parse_ics_date("20240502T150000Z", ""),
+            Some(("2024-05-02 15:00 UTC".to_string(), 1_714_662_000))
+        );
+        // Floating and TZID times keep their wall clock, without a zone
+        assert_eq!(
+            parse_ics_date("20240502T150000", ""),
+            Some(("2024-05-02 15:00".to_string(), 1_714_662_000))
+        );
+        assert_eq!(
+            parse_ics_date("20240502T150000", "TZID=America/New_York"),
+            Some(("2024-05-02 15:00".to_string(), 1_714_662_000))
+        );
+        assert_eq!(
+            parse_ics_date("20240502", "VALUE=DATE"),
+            Some(("2024-05-02".to_string(), 1_714_608_000))
+        );
+        assert_eq!(parse_ics_date("2024-05-02", ""), None);
+        assert_eq!(parse_ics_date("20240502T15", ""), None);
+    }
+
+    #[test]
+    fn test_parse_ics_events() {
+        let utc = calendar(
+            "REQUEST",
+            "SUMMARY:Design review\\, round 2\r\nDTSTART:20240502T150000Z\r\nLOCATION:Room 4\r\n",
+        );
+        let events = parse_ics(&utc);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "Design review, round 2");
+        assert_eq!(events[0].start, "2024-05-02 15:00 UTC");
+        assert_eq!(events[0].location.as_deref(), Some("Room 4"));
+
+        let all_day = calendar(
+            "PUBLISH",
+            "SUMMARY:Offsite\r\nDTSTART;VALUE=DATE:20240502\r\nLOCATION:\r\n",
+        );
+        let events = parse_ics(&all_day);
+        assert_eq!(events[0].start, "2024-05-02");
+        assert_eq!(events[0].start_key, 1_714_608_000);
+        assert_eq!(events[0].location, None);
+
+        // Folded lines continue the previous one, even inside the TZID
+        let floating = calendar(
+            "REQUEST",
+            "SUMMARY:Stand\r\n up\r\nDTSTART;TZID=\"Europe/\r\n\tParis\":20240601T093000\r\n",
+        );
+        let events = parse_ics(&floating);
+        assert_eq!(events[0].summary, "Standup");
+        assert_eq!(events[0].start, "2024-06-01 09:30");
+        assert_eq!(events[0].start_key, 1_717_234_200);
+    }
+
+    #[test]
+    fn test_parse_ics_cancelled() {
+        let event = "SUMMARY:Retro\r\nDTSTART:20240502T150000Z\r\n";
+        assert!(parse_ics(&calendar("CANCEL", event)).is_empty());
+        let status = format!("{event}STATUS:CANCELLED\r\n");
+        assert!(parse_ics(&calendar("REQUEST", &status)).is_empty());
+        let confirmed = format!("{event}STATUS:CONFIRMED\r\n");
+        assert_eq!(parse_ics(&calendar("REQUEST", &confirmed)).len(), 1);
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+
+use super::types::Envelope;
+
+/// Most messages listed in the cleanup view
+const MAX_CANDIDATES: usize = 200;
+
+/// A large and/or old message offered for cleanup
+#[derive(Debug, Clone)]
+pub struct CleanupCandidate {
+    pub id: String,
+    pub message_id: Option<String>,
+    pub file_path: String,
+    pub subject: String,
+    pub from: String,
+    pub date: String,
+    pub size: u64,
+    pub has_attachment: bool,
+}
+
+/// The messages costing the most space the longest: sorted by size times age
+pub fn cleanup_candidates(envelopes: &[Envelope]) -> Vec<CleanupCandidate> {
+    let today = unix_now() / 86_400;
+    let mut scored: Vec<(u64, CleanupCandidate)> = envelopes
+        .iter()
+        .filter_map(|e| {
+            let file_path = e.file_path.clone()?;
+            let size = std::fs::metadata(&file_path).ok()?.len();
+            let date = e.date.clone().unwrap_or_default();
+            let age_days = e
+                .timestamp
+                .and_then(|t| u64::try_from(t / 86_400).ok())
+                .map(|day| today.saturating_sub(day))
+                .unwrap_or(0);
+            let candidate = CleanupCandidate {
+                id: e.id.clone(),
+                message_id: e.message_id.clone(),
+                file_path,
+                subject: e.subject.clone().unwrap_or_default(),
+                from: e.from_display(),
+                date,
+                size,
+                has_attachment: e.has_attachment,
+            };
+            // Same-day mail still counts, just barely
+            Some((size.saturating_mul(age_days.max(1)), candidate))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(MAX_CANDIDATES)
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// Rewrite a message file with each attachment of at least `min_size` bytes
+/// replaced by a short text stub naming it. Headers, body text, inline images
+/// and the maildir filename (so flags) are kept.
+/// Returns the bytes saved (0 when there was nothing to strip)
+pub fn strip_attachments(file_path: &str, min_size: u64) -> Result<u64> {
+    use mail_parser::MimeHeaders;
+
+    let raw = std::fs::read(file_path)?;
+    let message = mail_parser::MessageParser::default()
+        .parse(&raw)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse message"))?;
+
+    let today = mail_parser::DateTime::from_timestamp(unix_now() as i64);
+    let today = format!("{:04}-{:02}-{:02}", today.year, today.month, today.day);
+
+    // Byte ranges to replace (a part's headers and body) and their stubs
+    let mut cuts: Vec<(usize, usize, String)> = Vec::new();
+    for part in message.parts.iter().skip(1) {
+        let Some(name) = part.attachment_name() else {
+            continue;
+        };
+        let size = match &part.body {
+            mail_parser::PartType::Binary(data) | mail_parser::PartType::InlineBinary(data) => {
+                data.len()
+            }
+            mail_parser::PartType::Text(text) | mail_parser::PartType::Html(text) => text.len(),
+            _ => continue,
+        } as u64;
+        if size < min_size {
+            continue;
+        }
+        let inline = part
+            .content_disposition()
+            .is_some_and(|d| d.ctype().eq_ignore_ascii_case("inline"));
+        let is_image = part
+            .content_type()
+            .is_some_and(|ct| ct.ctype().eq_ignore_ascii_case("image"));
+        if inline && is_image {
+            continue;
+        }
+        let content_type = part
+            .content_type()
+            .map(|ct| match ct.subtype() {
+                Some(sub) => format!("{}/{}", ct.ctype(), sub),
+                None => ct.ctype().to_string(),
+            })
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+        // The stub is itself a small attachment, so the preview lists it
+        let name = name.replace(['\r', '\n'], " ");
+        let stub = format!(
+            "Content-Type: text/plain; charset=utf-8\r\n\
+             Content-Disposition: attachment; filename=\"{} (removed).txt\"\r\n\r\n\
+             [Attachment removed on {}: {} ({}, {})]",
+            name.replace('"', "'"),
+            today,
+            name,
+            content_type,
+            super::client::format_size(size)
+        );
+        cuts.push((part.offset_header, part.offset_end, stub));
+    }
+    if cuts.is_empty() {
+        return Ok(0);
+    }
+
+    cuts.sort_unstable_by_key(|(start, _, _)| *start);
+    let mut stripped = Vec::with_capacity(raw.len());
+    let mut pos = 0;
+    for (start, end, stub) in cuts {
+        if start < pos {
+            continue;
+        }
+        stripped.extend_from_slice(&raw[pos..start]);
+        stripped.extend_from_slice(stub.as_bytes());
+        pos = end;
+    }
+    stripped.extend_from_slice(&raw[pos..]);
+
+    // Write into the maildir's tmp/ (which scans skip) and rename over the
+    // original, so a crash can't truncate mail or leave a stray file in cur/
+    let path = Path::new(file_path);
+    let folder = path
+        .parent()
+        .and_then(Path::parent)
+        .context("Message is not in a maildir folder")?;
+    let tmp_dir = folder.join("tmp");
+    std::fs::create_dir_all(&tmp_dir)?;
+    let tmp = tmp_dir.join(format!(
+        "{}.strip",
+        path.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    if let Err(e) = std::fs::write(&tmp, &stripped).and_then(|_| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    Ok(raw.len().saturating_sub(stripped.len()) as u64)
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_attachments_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let cur = dir.path().join("INBOX/cur");
+        std::fs::create_dir_all(&cur).unwrap();
+        let path = cur.join("1.M1P1.host:2,S");
+        let message = format!(
+            "Subject: report\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: multipart/mixed; boundary=\"b\"\r\n\r\n\
+             --b\r\n\
+             Content-Type: text/plain\r\n\r\n\
+             See attached\r\n\
+             --b\r\n\
+             Content-Type: application/pdf\r\n\
+             Content-Disposition: attachment; filename=\"report.pdf\"\r\n\r\n\
+             {}\r\n\
+             --b--\r\n",
+            "x".repeat(4096)
+        );
+        std::fs::write(&path, &message).unwrap();
+
+        let saved = strip_attachments(path.to_str().unwrap(), 1024).unwrap();
+        assert!(saved > 3000);
+        let stripped = std::fs::read_to_string(&path).unwrap();
+        assert!(stripped.contains("See attached"));
+        assert!(stripped.contains("report.pdf (removed).txt"));
+        // Only the rewritten message is left; nothing behind in cur/ or tmp/
+        assert_eq!(std::fs::read_dir(&cur).unwrap().count(), 1);
+        let tmp = std::fs::read_dir(dir.path().join("INBOX/tmp")).unwrap();
+        assert_eq!(tmp.count(), 0);
+    }
+}
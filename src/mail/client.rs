@@ -1,10 +1,13 @@
 use anyhow::Result;
 use rayon::prelude::*;
+use std::collections::HashSet;
 use std::path::Path;
 use std::process::Command;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use super::cache::{get_files_to_parse, load_cache, save_cache};
+use super::cache::{
+    get_files_to_parse, load_cache, load_rendered_html, save_cache, save_rendered_html,
+};
 use super::types::{Address, Envelope};
 
 fn render_html(html: &str) -> Result<String> {
@@ -25,9 +28,141 @@ fn render_html(html: &str) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Human-readable byte count ("812 B", "4.2 KB", "1.3 MB", "2.7 GB")
+pub fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else if bytes < 1024 * 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
+    }
+}
+
+/// MIME type for an attachment, from its file extension
+pub fn guess_content_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("pdf") => "application/pdf",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("txt") => "text/plain",
+        Some("html") => "text/html",
+        Some("zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Separator between a maildir unique name and its flags.
+/// `:` is not allowed in Windows filenames, so Windows uses `;` instead
+#[cfg(not(windows))]
+pub const INFO_SEPARATOR: &str = ":2,";
+#[cfg(windows)]
+pub const INFO_SEPARATOR: &str = ";2,";
+
+/// Find the start of the info section in a maildir filename.
+/// Accepts both separators so mail copied between platforms keeps its flags
+pub(super) fn find_info_section(filename: &str) -> Option<usize> {
+    filename.rfind(":2,").or_else(|| filename.rfind(";2,"))
+}
+
+/// Whether an error is a file that isn't there (any more)
+fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound)
+}
+
+/// Error for a message file another program moved away or deleted
+fn gone_error(file_path: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{} no longer exists (moved or deleted outside mailtui); press R to reload",
+        file_path
+    )
+}
+
+/// Current path of a message file another program (mbsync, a second
+/// mailtui) renamed under us: same unique name with any flags, in the same
+/// folder's cur/ or new/
+pub fn find_renamed(file_path: &str) -> Option<String> {
+    let path = Path::new(file_path);
+    let filename = path.file_name()?.to_str()?;
+    let unique = &filename[..find_info_section(filename).unwrap_or(filename.len())];
+    let folder = path.parent()?.parent()?;
+    ["cur", "new"].iter().find_map(|sub| {
+        std::fs::read_dir(folder.join(sub))
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .find(|entry| {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                name.strip_prefix(unique)
+                    .is_some_and(|rest| rest.is_empty() || find_info_section(rest) == Some(0))
+            })
+            .map(|entry| entry.path().to_string_lossy().to_string())
+    })
+}
+
+/// Current path of a message whose file has gone: renamed in place, or
+/// re-downloaded under a new name. Looks the Message-ID up in `known` (the
+/// envelopes already loaded) first, then reads the headers of All Mail
+/// files not among them
+pub fn relocate_message(
+    file_path: &str,
+    mail_dir: &str,
+    message_id: Option<&str>,
+    known: &[Envelope],
+) -> Option<String> {
+    if let Some(path) = find_renamed(file_path) {
+        return Some(path);
+    }
+    let message_id = message_id?;
+    let known_path = known
+        .iter()
+        .filter(|e| e.message_id.as_deref() == Some(message_id))
+        .filter_map(|e| e.file_path.as_deref())
+        .find(|path| Path::new(path).exists());
+    if let Some(path) = known_path {
+        return Some(path.to_string());
+    }
+    let known_paths: HashSet<&str> = known
+        .iter()
+        .filter_map(|e| e.file_path.as_deref())
+        .collect();
+    folder_files(mail_dir, "[Gmail]/All Mail")
+        .into_iter()
+        .filter(|path| !known_paths.contains(&*path.to_string_lossy()))
+        .find(|path| read_message_id(path).as_deref() == Some(message_id))
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// Run `op` on a message file; if the file was renamed under us, retry once
+/// on its current path
+fn with_current_path<T>(file_path: &str, op: impl Fn(&str) -> Result<T>) -> Result<T> {
+    match op(file_path) {
+        Err(e) if is_not_found(&e) => {
+            let current = find_renamed(file_path).ok_or_else(|| gone_error(file_path))?;
+            op(&current)
+        }
+        result => result,
+    }
+}
+
+/// Modify maildir flags in a filename, following a rename by another program
+fn modify_maildir_flags(path: &str, add: Option<char>, remove: Option<char>) -> Result<String> {
+    with_current_path(path, |path| rename_with_flags(path, add, remove))
+}
+
 /// Modify maildir flags in a filename
 /// Maildir format: {unique}:2,{flags} where flags are sorted letters (DFPRST)
-fn modify_maildir_flags(path: &str, add: Option<char>, remove: Option<char>) -> Result<String> {
+fn rename_with_flags(path: &str, add: Option<char>, remove: Option<char>) -> Result<String> {
     let path = std::path::Path::new(path);
     let filename = path
         .file_name()
@@ -35,13 +170,13 @@ fn modify_maildir_flags(path: &str, add: Option<char>, remove: Option<char>) ->
         .ok_or_else(|| anyhow::anyhow!("Invalid path"))?;
 
     // Parse flags from filename (after ":2,")
-    let (base, flags) = if let Some(pos) = filename.rfind(":2,") {
+    let (base, flags) = if let Some(pos) = find_info_section(filename) {
         let base = &filename[..pos + 3]; // includes ":2,"
         let flags = &filename[pos + 3..];
         (base.to_string(), flags.to_string())
     } else {
         // No flags section, add one
-        (format!("{}:2,", filename), String::new())
+        (format!("{}{}", filename, INFO_SEPARATOR), String::new())
     };
 
     // Modify flags
@@ -76,6 +211,11 @@ pub fn mark_as_unread(file_path: &str) -> Result<String> {
     modify_maildir_flags(file_path, None, Some('S'))
 }
 
+/// Mark a message as answered (add Replied flag) - operates on file path
+pub fn mark_as_replied(file_path: &str) -> Result<String> {
+    modify_maildir_flags(file_path, Some('R'), None)
+}
+
 /// Toggle read/unread status - operates on file path, returns new path
 pub fn toggle_read(file_path: &str, currently_read: bool) -> Result<String> {
     if currently_read {
@@ -85,6 +225,160 @@ pub fn toggle_read(file_path: &str, currently_read: bool) -> Result<String> {
     }
 }
 
+/// Move a message into another maildir folder (relative to mail_dir), returns new path
+pub fn move_to_folder(file_path: &str, mail_dir: &str, folder: &str) -> Result<String> {
+    with_current_path(file_path, |path| {
+        let new_path = folder_dest_path(path, mail_dir, folder)?;
+        std::fs::rename(path, &new_path)?;
+        Ok(new_path.to_string_lossy().to_string())
+    })
+}
+
+/// Copy a message into another maildir folder (relative to mail_dir), returns new path
+pub fn copy_to_folder(file_path: &str, mail_dir: &str, folder: &str) -> Result<String> {
+    with_current_path(file_path, |path| {
+        let new_path = folder_dest_path(path, mail_dir, folder)?;
+        std::fs::copy(path, &new_path)?;
+        Ok(new_path.to_string_lossy().to_string())
+    })
+}
+
+/// Destination path for a message in `folder`/cur, creating the folder if needed.
+/// Drops mbsync's `,U=<uid>` from the name so the sync tool assigns a fresh UID
+fn folder_dest_path(file_path: &str, mail_dir: &str, folder: &str) -> Result<std::path::PathBuf> {
+    let path = Path::new(file_path);
+    let filename = path
+        .file_name()
+        .and_then(|f| f.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid path"))?;
+
+    let (unique, info) = match find_info_section(filename) {
+        Some(pos) => (&filename[..pos], &filename[pos..]),
+        None => (filename, ""),
+    };
+    let unique = match unique.find(",U=") {
+        Some(pos) => {
+            let rest = &unique[pos + 3..];
+            let end = rest.find(',').map(|i| pos + 3 + i).unwrap_or(unique.len());
+            format!("{}{}", &unique[..pos], &unique[end..])
+        }
+        None => unique.to_string(),
+    };
+
+    let dest_dir = Path::new(mail_dir).join(folder).join("cur");
+    std::fs::create_dir_all(&dest_dir)?;
+    Ok(dest_dir.join(format!("{}{}", unique, info)))
+}
+
+/// Archive a message Gmail-style: remove its copies from the inbox folder,
+/// keeping the one in `keep_folder` (All Mail, or wherever the message was
+/// just moved). `known_copy` is a file already known to hold it, if any;
+/// otherwise `keep_folder` is searched by Message-ID. When that folder has
+/// no copy, the first inbox copy is moved there instead of deleted, so
+/// archiving never loses mail outside Gmail. Returns how many copies left
+/// the inbox
+pub fn archive_message(
+    message_id: &str,
+    mail_dir: &str,
+    inbox_folder: &str,
+    keep_folder: &str,
+    known_copy: Option<&str>,
+) -> Result<usize> {
+    let inbox = Path::new(mail_dir).join(inbox_folder);
+    let inbox_copies: Vec<std::path::PathBuf> = ["cur", "new"]
+        .iter()
+        .filter_map(|subdir| std::fs::read_dir(inbox.join(subdir)).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|path| read_message_id(path).as_deref() == Some(message_id))
+        .collect();
+    if inbox_copies.is_empty() {
+        return Ok(0);
+    }
+
+    let keep_dir = Path::new(mail_dir).join(keep_folder);
+    let mut kept = known_copy
+        .map(Path::new)
+        .is_some_and(|p| p.starts_with(&keep_dir) && p.is_file())
+        || folder_files(mail_dir, keep_folder)
+            .iter()
+            .any(|path| read_message_id(path).as_deref() == Some(message_id));
+
+    for path in &inbox_copies {
+        let path_str = path.to_string_lossy();
+        if kept {
+            std::fs::remove_file(path)?;
+        } else {
+            move_to_folder(&path_str, mail_dir, keep_folder)?;
+            kept = true;
+        }
+    }
+    Ok(inbox_copies.len())
+}
+
+/// Read just the Message-ID header of a mail file
+pub(super) fn read_message_id(path: &Path) -> Option<String> {
+    read_header(path, "message-id").map(|value| extract_message_id(&value))
+}
+
+/// Read one header of a mail file (the first if repeated), unfolded,
+/// without parsing the rest of the message
+pub fn read_header(path: &Path, header: &str) -> Option<String> {
+    use std::io::{BufRead, BufReader};
+
+    let file = std::fs::File::open(path).ok()?;
+    let mut lines = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .peekable();
+    while let Some(line) = lines.next() {
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case(header) {
+                let mut value = value.trim().to_string();
+                // Folded header: continues on lines starting with whitespace
+                while let Some(next) = lines.next_if(|l| l.starts_with([' ', '\t'])) {
+                    if !value.is_empty() {
+                        value.push(' ');
+                    }
+                    value.push_str(next.trim());
+                }
+                return Some(value);
+            }
+        }
+    }
+    None
+}
+
+/// Where a reply to a mailing-list message should go: the author's
+/// Mail-Followup-To if set, otherwise the list's List-Post address.
+/// None for messages not from a list, or lists that don't take posts
+pub fn list_reply_address(file_path: &str) -> Option<String> {
+    let path = Path::new(file_path);
+    if let Some(followup) = read_header(path, "mail-followup-to") {
+        return Some(followup);
+    }
+    // List-Post: <mailto:list@example.org>, or NO for announce-only lists
+    let post = read_header(path, "list-post")?;
+    let start = post.find("mailto:")? + "mailto:".len();
+    let addr = &post[start..];
+    let end = addr.find(['>', '?', ',']).unwrap_or(addr.len());
+    let addr = addr[..end].trim();
+    (!addr.is_empty()).then(|| addr.to_string())
+}
+
+/// Envelopes from the last scan of this maildir, straight from the cache
+/// without touching the mail files (may be stale)
+pub fn cached_envelopes(mail_dir: &str, user_email: &str) -> Vec<Envelope> {
+    let all_mail_path = format!("{}/[Gmail]/All Mail", mail_dir);
+    load_cache(|path| parse_mail_file(path, user_email).ok())
+        .into_iter()
+        .filter(|(path, _)| path.starts_with(&all_mail_path))
+        .map(|(_, cached)| cached.envelope)
+        .collect()
+}
+
 /// Scan all mail in maildir and parse threading headers
 /// Returns envelopes with message_id, in_reply_to, references populated
 /// Uses caching and Rayon for parallel file parsing
@@ -112,7 +406,7 @@ where
     let total = file_paths.len();
 
     // Load cache and determine what needs parsing
-    let cache = load_cache();
+    let cache = load_cache(|path| parse_mail_file(path, user_email).ok());
     let (to_parse, mut cached_envelopes) = get_files_to_parse(&file_paths, &cache);
 
     let cache_hits = cached_envelopes.len();
@@ -151,6 +445,11 @@ where
     Ok(cached_envelopes)
 }
 
+/// Parse the headers of a standalone message file (e.g. an .eml from another system)
+pub fn read_envelope(file_path: &str) -> Result<Envelope> {
+    parse_mail_file(Path::new(file_path), "")
+}
+
 /// Parse a single maildir file and extract envelope with threading headers
 fn parse_mail_file(path: &Path, user_email: &str) -> Result<Envelope> {
     use std::io::{BufRead, BufReader};
@@ -166,6 +465,7 @@ fn parse_mail_file(path: &Path, user_email: &str) -> Result<Envelope> {
     let mut subject: Option<String> = None;
     let mut date: Option<String> = None;
     let mut content_type: Option<String> = None;
+    let mut is_auto = false;
 
     let mut current_header: Option<String> = None;
     let mut current_value = String::new();
@@ -188,6 +488,7 @@ fn parse_mail_file(path: &Path, user_email: &str) -> Result<Envelope> {
                     &mut subject,
                     &mut date,
                     &mut content_type,
+                    &mut is_auto,
                 );
             }
             break;
@@ -212,6 +513,7 @@ fn parse_mail_file(path: &Path, user_email: &str) -> Result<Envelope> {
                     &mut subject,
                     &mut date,
                     &mut content_type,
+                    &mut is_auto,
                 );
             }
 
@@ -223,6 +525,10 @@ fn parse_mail_file(path: &Path, user_email: &str) -> Result<Envelope> {
         }
     }
 
+    // The string is for display; sorting and grouping use the timestamp
+    let timestamp = date.as_deref().and_then(parse_timestamp);
+    let date = date.as_deref().map(parse_date);
+
     // Parse flags from filename
     let flags = parse_flags_from_filename(path);
 
@@ -244,6 +550,13 @@ fn parse_mail_file(path: &Path, user_email: &str) -> Result<Envelope> {
         .map(|ct| ct.contains("multipart/related"))
         .unwrap_or(false);
 
+    // Bounces are rare enough to parse in full for the message they report on
+    let bounce_of = content_type
+        .as_ref()
+        .map(|ct| ct.to_ascii_lowercase())
+        .filter(|ct| ct.contains("multipart/report") && ct.contains("delivery-status"))
+        .and_then(|_| read_bounce_of(path));
+
     // Parse From address
     let from_addr = from.as_ref().map(|f| parse_email_address(f));
 
@@ -264,21 +577,33 @@ fn parse_mail_file(path: &Path, user_email: &str) -> Result<Envelope> {
         from: from_addr,
         to: to_addr,
         date,
+        timestamp,
         has_attachment,
         has_inline_images,
         message_id,
         in_reply_to,
         references,
         is_sent,
+        is_auto,
         file_path: Some(path.to_string_lossy().to_string()),
+        bounce_of,
         // Display fields will be computed by threading algorithm
         thread_depth: 0,
         display_depth: 0,
         is_last_in_thread: false,
         tree_prefix: String::new(),
+        unread_replies: 0,
+        bounced: false,
     })
 }
 
+/// Message-ID of the message a delivery failure report says bounced
+fn read_bounce_of(path: &Path) -> Option<String> {
+    let raw = std::fs::read(path).ok()?;
+    let message = mail_parser::MessageParser::default().parse(&raw)?;
+    super::dsn::delivery_report(&message)?.original_message_id
+}
+
 fn save_header(
     header: &str,
     value: &str,
@@ -290,6 +615,7 @@ fn save_header(
     subject: &mut Option<String>,
     date: &mut Option<String>,
     content_type: &mut Option<String>,
+    is_auto: &mut bool,
 ) {
     match header {
         "message-id" => *message_id = Some(extract_message_id(value)),
@@ -304,8 +630,12 @@ fn save_header(
         "from" => *from = Some(value.to_string()),
         "to" => *to = Some(value.to_string()),
         "subject" => *subject = Some(decode_header_value(value)),
-        "date" => *date = Some(parse_date(value)),
+        "date" => *date = Some(value.to_string()),
         "content-type" => *content_type = Some(value.to_lowercase()),
+        // RFC 3834; "no" marks a message a human sent
+        "auto-submitted" if !value.trim().eq_ignore_ascii_case("no") => *is_auto = true,
+        "x-autoreply" | "x-autorespond" => *is_auto = true,
+        "precedence" if value.trim().eq_ignore_ascii_case("auto_reply") => *is_auto = true,
         _ => {}
     }
 }
@@ -497,6 +827,30 @@ fn parse_date(s: &str) -> String {
     format!("0000-00-00 {}", s.chars().take(20).collect::<String>())
 }
 
+/// Seconds since the epoch for a Date header. Headers too mangled for an
+/// RFC 822 parse fall back to the day and time `parse_date` picks out, as UTC
+fn parse_timestamp(s: &str) -> Option<i64> {
+    if let Some(datetime) = mail_parser::DateTime::parse_rfc822(s) {
+        return Some(datetime.to_timestamp());
+    }
+    let date = parse_date(s);
+    let (day, time) = date.split_once(' ')?;
+    let mut day = day.split('-');
+    let mut time = time.split(':');
+    let datetime = mail_parser::DateTime {
+        year: day.next()?.parse().ok().filter(|&y| y > 0)?,
+        month: day.next()?.parse().ok().filter(|&m| m > 0)?,
+        day: day.next()?.parse().ok()?,
+        hour: time.next()?.parse().ok()?,
+        minute: time.next()?.parse().ok()?,
+        second: 0,
+        tz_before_gmt: false,
+        tz_hour: 0,
+        tz_minute: 0,
+    };
+    Some(datetime.to_timestamp())
+}
+
 /// Parse flags from maildir filename suffix (e.g., ":2,RS" -> ["Replied", "Seen"])
 fn parse_flags_from_filename(path: &Path) -> Vec<String> {
     let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
@@ -504,7 +858,7 @@ fn parse_flags_from_filename(path: &Path) -> Vec<String> {
     let mut flags = Vec::new();
 
     // Find the flags suffix after ":2,"
-    if let Some(pos) = filename.find(":2,") {
+    if let Some(pos) = find_info_section(filename) {
         let flag_chars = &filename[pos + 3..];
         for c in flag_chars.chars() {
             match c {
@@ -525,6 +879,8 @@ fn parse_flags_from_filename(path: &Path) -> Vec<String> {
 /// Inline image data
 #[derive(Clone)]
 pub struct InlineImage {
+    /// Index into the message's MIME parts
+    pub part: usize,
     pub data: Vec<u8>,
     pub content_type: String,
     pub filename: Option<String>,
@@ -533,6 +889,8 @@ pub struct InlineImage {
 /// Attachment info (non-image)
 #[derive(Clone)]
 pub struct Attachment {
+    /// Index into the message's MIME parts
+    pub part: usize,
     pub filename: String,
     pub content_type: String,
     pub size: usize,
@@ -543,59 +901,108 @@ pub struct MessageContent {
     pub text: String,
     pub images: Vec<InlineImage>,
     pub attachments: Vec<Attachment>,
+    /// For a bounce: Message-ID of the message that could not be delivered
+    pub bounce_of: Option<String>,
 }
 
-/// Read message content directly from file path
-pub fn read_message_by_path(file_path: &str) -> Result<String> {
-    let content = read_message_content(file_path)?;
+/// Message text as shown in the preview, followed by the image/attachment listing
+pub struct PreviewText {
+    pub text: String,
+    /// Listed images/attachments: (line number in `text`, MIME part index)
+    pub parts: Vec<(usize, usize)>,
+    /// For a bounce: Message-ID of the message that could not be delivered
+    pub bounce_of: Option<String>,
+}
+
+impl PreviewText {
+    /// Fold each run of quoted ("> ") lines into a single marker line,
+    /// keeping `parts` pointing at the same listing lines
+    pub fn collapse_quotes(&mut self) {
+        let is_quoted = |line: &str| line.trim_start().starts_with('>');
+        let lines: Vec<&str> = self.text.split('\n').collect();
+        let mut out: Vec<String> = Vec::with_capacity(lines.len());
+        // New line number of every old line
+        let mut moved = Vec::with_capacity(lines.len());
+        let mut i = 0;
+        while i < lines.len() {
+            let run = lines[i..].iter().take_while(|l| is_quoted(l)).count();
+            if run > 1 {
+                moved.extend(std::iter::repeat_n(out.len(), run));
+                out.push(format!("[{} quoted lines]", run));
+                i += run;
+            } else {
+                moved.push(out.len());
+                out.push(lines[i].to_string());
+                i += 1;
+            }
+        }
+        self.text = out.join("\n");
+        for (line, _) in &mut self.parts {
+            *line = moved.get(*line).copied().unwrap_or(*line);
+        }
+    }
+}
 
+/// Append image and attachment info, if present, to the message text
+pub fn preview_text(content: MessageContent) -> PreviewText {
     let has_images = !content.images.is_empty();
     let has_attachments = !content.attachments.is_empty();
 
-    // Append image and attachment info if present
+    let mut text = content.text;
+    let mut parts = Vec::new();
+    let bounce_of = content.bounce_of;
     if !has_images && !has_attachments {
-        Ok(content.text)
-    } else {
-        let mut text = content.text;
-        text.push_str("\n\n───────────────────────────────────────\n");
+        return PreviewText {
+            text,
+            parts,
+            bounce_of,
+        };
+    }
+    text.push_str("\n\n───────────────────────────────────────\n");
+
+    if has_images {
+        text.push_str(&format!("Images ({})\n", content.images.len()));
+        for img in &content.images {
+            let name = img.filename.as_deref().unwrap_or("(unnamed)");
+            parts.push((text.matches('\n').count(), img.part));
+            text.push_str(&format!("  - {} ({})\n", name, img.content_type));
+        }
+    }
 
+    if has_attachments {
         if has_images {
-            text.push_str(&format!("Images ({})\n", content.images.len()));
-            for img in &content.images {
-                let name = img.filename.as_deref().unwrap_or("(unnamed)");
-                text.push_str(&format!("  - {} ({})\n", name, img.content_type));
-            }
+            text.push('\n');
         }
-
-        if has_attachments {
-            if has_images {
-                text.push('\n');
-            }
-            text.push_str(&format!("Attachments ({})\n", content.attachments.len()));
-            for att in &content.attachments {
-                let size = if att.size < 1024 {
-                    format!("{} B", att.size)
-                } else if att.size < 1024 * 1024 {
-                    format!("{:.1} KB", att.size as f64 / 1024.0)
-                } else {
-                    format!("{:.1} MB", att.size as f64 / (1024.0 * 1024.0))
-                };
-                text.push_str(&format!(
-                    "  - {} ({}, {})\n",
-                    att.filename, att.content_type, size
-                ));
-            }
+        text.push_str(&format!("Attachments ({})\n", content.attachments.len()));
+        for att in &content.attachments {
+            let size = format_size(att.size as u64);
+            parts.push((text.matches('\n').count(), att.part));
+            text.push_str(&format!(
+                "  - {} ({}, {})\n",
+                att.filename, att.content_type, size
+            ));
         }
+    }
 
-        Ok(text)
+    PreviewText {
+        text,
+        parts,
+        bounce_of,
     }
 }
 
-/// Read message content with images
-pub fn read_message_content(file_path: &str) -> Result<MessageContent> {
+/// Read message content directly from file path
+#[allow(dead_code)] // used by the check_attachments tool; the TUI uses preview_text
+pub fn read_message_by_path(file_path: &str) -> Result<String> {
+    Ok(preview_text(read_message_content(file_path, false)?).text)
+}
+
+/// Read message content with images. With `prefer_html`, an HTML part is
+/// rendered even when the message also has a plain-text one
+pub fn read_message_content(file_path: &str, prefer_html: bool) -> Result<MessageContent> {
     use mail_parser::MimeHeaders;
 
-    let raw = std::fs::read(file_path)?;
+    let raw = with_current_path(file_path, |path| Ok(std::fs::read(path)?))?;
 
     let message = mail_parser::MessageParser::default()
         .parse(&raw)
@@ -605,7 +1012,7 @@ pub fn read_message_content(file_path: &str) -> Result<MessageContent> {
     let mut images = Vec::new();
     let mut attachments = Vec::new();
 
-    for part in message.parts.iter() {
+    for (index, part) in message.parts.iter().enumerate() {
         let content_type = part
             .content_type()
             .map(|ct| format!("{}/{}", ct.ctype(), ct.subtype().unwrap_or("octet-stream")))
@@ -617,6 +1024,7 @@ pub fn read_message_content(file_path: &str) -> Result<MessageContent> {
                 &part.body
             {
                 images.push(InlineImage {
+                    part: index,
                     data: data.to_vec(),
                     content_type: content_type.clone(),
                     filename: part.attachment_name().map(|s| s.to_string()),
@@ -634,6 +1042,7 @@ pub fn read_message_content(file_path: &str) -> Result<MessageContent> {
                 mail_parser::PartType::Multipart(_) => 0,
             };
             attachments.push(Attachment {
+                part: index,
                 filename: filename.to_string(),
                 content_type: content_type.clone(),
                 size,
@@ -641,60 +1050,143 @@ pub fn read_message_content(file_path: &str) -> Result<MessageContent> {
         }
     }
 
-    // Try to get text body first, then HTML
-    if let Some(text_body) = message.body_text(0) {
-        return Ok(MessageContent {
-            text: text_body.to_string(),
-            images,
-            attachments,
-        });
-    }
-
-    if let Some(html_body) = message.body_html(0) {
-        return Ok(MessageContent {
-            text: render_html(&html_body)?,
-            images,
-            attachments,
-        });
-    }
+    // mail-parser converts between text and HTML when a part is missing,
+    // so check for a real HTML part before preferring it
+    let has_html = message.html_body.iter().any(|&i| {
+        matches!(
+            message.parts.get(i).map(|p| &p.body),
+            Some(mail_parser::PartType::Html(_))
+        )
+    });
 
-    // Fallback: try to extract any text parts
-    let mut text_parts = Vec::new();
-    for part in message.parts.iter() {
-        if let mail_parser::PartType::Text(text) = &part.body {
-            text_parts.push(text.as_ref());
+    // Try to get text body first, then HTML
+    let text_body = message.body_text(0).filter(|_| !(prefer_html && has_html));
+    let text = if let Some(text_body) = text_body {
+        text_body.to_string()
+    } else if let Some(html_body) = message.body_html(0) {
+        // w3m is slow on big newsletters; reuse the text from a previous visit
+        match load_rendered_html(file_path) {
+            Some(text) => text,
+            None => {
+                let text = render_html(&html_body)?;
+                let _ = save_rendered_html(file_path, &text);
+                text
+            }
+        }
+    } else {
+        // Fallback: try to extract any text parts
+        let mut text_parts = Vec::new();
+        for part in message.parts.iter() {
+            if let mail_parser::PartType::Text(text) = &part.body {
+                text_parts.push(text.as_ref());
+            }
         }
-    }
-
-    if !text_parts.is_empty() {
-        return Ok(MessageContent {
-            text: text_parts.join("\n\n"),
-            images,
-            attachments,
-        });
-    }
 
-    // Last resort: show attachment info
-    let mut info = String::from("(No readable text content)\n\nAttachments:\n");
-    for part in message.parts.iter() {
-        if let Some(filename) = part.attachment_name() {
-            info.push_str(&format!("  - {}\n", filename));
+        if !text_parts.is_empty() {
+            text_parts.join("\n\n")
+        } else {
+            // Last resort: show attachment info
+            let mut info = String::from("(No readable text content)\n\nAttachments:\n");
+            for part in message.parts.iter() {
+                if let Some(filename) = part.attachment_name() {
+                    info.push_str(&format!("  - {}\n", filename));
+                }
+            }
+            info
         }
-    }
+    };
+
+    // Bounces lead with who failed and why, then the report's own text
+    let report = super::dsn::delivery_report(&message);
+    let text = match &report {
+        Some(report) => format!("{}\n{}", report.summary(), text),
+        None => text,
+    };
 
     Ok(MessageContent {
-        text: info,
+        text,
         images,
         attachments,
+        bounce_of: report.and_then(|r| r.original_message_id),
     })
 }
 
+/// Render a message for printing: a header block followed by the body.
+/// With `html`, produces an HTML document (the original HTML body when present)
+pub fn render_for_print(file_path: &str, html: bool) -> Result<String> {
+    let raw = with_current_path(file_path, |path| Ok(std::fs::read(path)?))?;
+    let message = mail_parser::MessageParser::default()
+        .parse(&raw)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse message"))?;
+
+    let format_addrs = |addrs: Option<&mail_parser::Address>| -> String {
+        addrs
+            .map(|a| {
+                a.iter()
+                    .map(|addr| match (addr.name(), addr.address()) {
+                        (Some(name), Some(email)) => format!("{} <{}>", name, email),
+                        (None, Some(email)) => email.to_string(),
+                        (Some(name), None) => name.to_string(),
+                        (None, None) => String::new(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default()
+    };
+    let headers = [
+        ("From", format_addrs(message.from())),
+        ("To", format_addrs(message.to())),
+        ("Cc", format_addrs(message.cc())),
+        (
+            "Date",
+            message.date().map(|d| d.to_rfc822()).unwrap_or_default(),
+        ),
+        ("Subject", message.subject().unwrap_or("").to_string()),
+    ];
+
+    if html {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+        let mut doc = String::from("<html><head><meta charset=\"utf-8\"></head><body>\n<table>\n");
+        for (name, value) in headers.iter().filter(|(_, v)| !v.is_empty()) {
+            doc.push_str(&format!(
+                "<tr><th align=\"left\">{}:</th><td>{}</td></tr>\n",
+                name,
+                escape(value)
+            ));
+        }
+        doc.push_str("</table>\n<hr>\n");
+        match message.body_html(0) {
+            Some(body) => doc.push_str(&body),
+            None => doc.push_str(&format!(
+                "<pre>{}</pre>",
+                escape(&message.body_text(0).unwrap_or_default())
+            )),
+        }
+        doc.push_str("\n</body></html>\n");
+        return Ok(doc);
+    }
+
+    let mut doc = String::new();
+    for (name, value) in headers.iter().filter(|(_, v)| !v.is_empty()) {
+        doc.push_str(&format!("{}: {}\n", name, value));
+    }
+    doc.push('\n');
+    doc.push_str(&read_message_content(file_path, false)?.text);
+    doc.push('\n');
+    Ok(doc)
+}
+
 /// Save all attachments from an email to a directory
 /// Returns list of saved file paths
 pub fn save_attachments(file_path: &str, output_dir: &std::path::Path) -> Result<Vec<String>> {
     use mail_parser::MimeHeaders;
 
-    let raw = std::fs::read(file_path)?;
+    let raw = with_current_path(file_path, |path| Ok(std::fs::read(path)?))?;
     let message = mail_parser::MessageParser::default()
         .parse(&raw)
         .ok_or_else(|| anyhow::anyhow!("Failed to parse message"))?;
@@ -727,6 +1219,84 @@ pub fn save_attachments(file_path: &str, output_dir: &std::path::Path) -> Result
     Ok(saved)
 }
 
+/// Save one MIME part (an attachment or inline image) to a directory.
+/// Returns the saved file path
+pub fn save_part(file_path: &str, part: usize, output_dir: &std::path::Path) -> Result<String> {
+    use mail_parser::MimeHeaders;
+
+    let raw = with_current_path(file_path, |path| Ok(std::fs::read(path)?))?;
+    let message = mail_parser::MessageParser::default()
+        .parse(&raw)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse message"))?;
+    let part = message
+        .parts
+        .get(part)
+        .ok_or_else(|| anyhow::anyhow!("Part {} not found", part))?;
+
+    let data: &[u8] = match &part.body {
+        mail_parser::PartType::Binary(data) | mail_parser::PartType::InlineBinary(data) => data,
+        mail_parser::PartType::Text(text) => text.as_bytes(),
+        mail_parser::PartType::Html(html) => html.as_bytes(),
+        _ => anyhow::bail!("Part has no content to save"),
+    };
+    // Unnamed inline images get a name from their subtype, e.g. image.png
+    // Only the final path component, so a crafted name can't escape output_dir
+    let filename = match part
+        .attachment_name()
+        .and_then(|name| std::path::Path::new(name).file_name())
+    {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => {
+            let ext = part
+                .content_type()
+                .and_then(|ct| ct.subtype())
+                .unwrap_or("bin");
+            format!("image.{}", ext)
+        }
+    };
+
+    std::fs::create_dir_all(output_dir)?;
+    let out_path = output_dir.join(filename);
+    std::fs::write(&out_path, data)?;
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+/// Message files directly in a folder (relative to mail_dir)
+fn folder_files(mail_dir: &str, folder: &str) -> Vec<std::path::PathBuf> {
+    let mut files = Vec::new();
+    for subdir in &["cur", "new"] {
+        let Ok(entries) = std::fs::read_dir(Path::new(mail_dir).join(folder).join(subdir)) else {
+            continue;
+        };
+        files.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_file()));
+    }
+    files
+}
+
+/// How many messages a folder (relative to mail_dir) holds
+pub fn count_folder(mail_dir: &str, folder: &str) -> usize {
+    folder_files(mail_dir, folder).len()
+}
+
+/// Unread messages in a folder (relative to mail_dir), going by the flags
+/// in the file names alone
+pub fn count_unread(mail_dir: &str, folder: &str) -> usize {
+    folder_files(mail_dir, folder)
+        .iter()
+        .filter(|path| !parse_flags_from_filename(path).iter().any(|f| f == "Seen"))
+        .count()
+}
+
+/// Envelopes of one folder (relative to mail_dir), newest first
+pub fn scan_folder(mail_dir: &str, folder: &str, user_email: &str) -> Vec<Envelope> {
+    let mut envelopes: Vec<Envelope> = folder_files(mail_dir, folder)
+        .iter()
+        .filter_map(|path| parse_mail_file(path, user_email).ok())
+        .collect();
+    envelopes.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+    envelopes
+}
+
 /// Deep substring search using ripgrep to find matching files,
 /// then parses the matching files directly
 pub fn search_deep(query: &str, mail_dir: &str, user_email: &str) -> Result<Vec<Envelope>> {
@@ -766,12 +1336,8 @@ pub fn search_deep(query: &str, mail_dir: &str, user_email: &str) -> Result<Vec<
         }
     }
 
-    // Sort by date descending
-    envelopes.sort_by(|a, b| {
-        let date_a = a.date.as_deref().unwrap_or("");
-        let date_b = b.date.as_deref().unwrap_or("");
-        date_b.cmp(date_a)
-    });
+    // Sort by date descending; undated messages last
+    envelopes.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
 
     Ok(envelopes)
 }
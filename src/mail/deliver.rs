@@ -0,0 +1,87 @@
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::mbox::{hostname, unique_name};
+
+/// Deliver one RFC 822 message into `mail_dir/folder` the maildir way:
+/// written and synced under tmp/, then renamed into new/ so a scan never
+/// sees half a message. A leading mbox "From " line (as procmail and
+/// some MTAs pass it) is dropped. Returns the delivered file's path
+pub fn deliver_message(mail_dir: &str, folder: &str, message: &[u8]) -> Result<PathBuf> {
+    let message = match message.strip_prefix(b"From ") {
+        Some(rest) => rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(&[][..], |end| &rest[end + 1..]),
+        None => message,
+    };
+    if message.iter().all(u8::is_ascii_whitespace) {
+        anyhow::bail!("Empty message");
+    }
+
+    let dest = Path::new(mail_dir).join(folder);
+    for sub in ["cur", "new", "tmp"] {
+        std::fs::create_dir_all(dest.join(sub))
+            .with_context(|| format!("Cannot create {}", dest.join(sub).display()))?;
+    }
+
+    // A name taken in tmp/ (same pid and microsecond) moves the sequence on
+    let host = hostname();
+    let mut seq = 0;
+    let (mut file, tmp, unique) = loop {
+        let unique = unique_name(&host, seq);
+        let tmp = dest.join("tmp").join(&unique);
+        match std::fs::File::create_new(&tmp) {
+            Ok(file) => break (file, tmp, unique),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => seq += 1,
+            Err(e) => return Err(e).with_context(|| format!("Cannot create {}", tmp.display())),
+        }
+    };
+    if let Err(e) = file.write_all(message).and_then(|_| file.sync_all()) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("Cannot write {}", tmp.display()));
+    }
+
+    let delivered = dest.join("new").join(unique);
+    if let Err(e) = std::fs::rename(&tmp, &delivered) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("Cannot move into {}", delivered.display()));
+    }
+    Ok(delivered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deliver_into_new() {
+        let dir = tempfile::tempdir().unwrap();
+        let mail_dir = dir.path().to_str().unwrap();
+        let path = deliver_message(
+            mail_dir,
+            "INBOX",
+            b"From a@example.com Mon Jan  1 00:00:00 2024\nSubject: hi\n\nBody\n",
+        )
+        .unwrap();
+        assert_eq!(path.parent().unwrap(), dir.path().join("INBOX/new"));
+        assert_eq!(std::fs::read(&path).unwrap(), b"Subject: hi\n\nBody\n");
+        let tmp = std::fs::read_dir(dir.path().join("INBOX/tmp")).unwrap();
+        assert_eq!(tmp.count(), 0);
+
+        // Each delivery gets its own file
+        let other = deliver_message(mail_dir, "INBOX", b"Subject: hi\n\nBody\n").unwrap();
+        assert_ne!(path, other);
+        assert_eq!(std::fs::read(&other).unwrap(), b"Subject: hi\n\nBody\n");
+    }
+
+    #[test]
+    fn test_deliver_rejects_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let mail_dir = dir.path().to_str().unwrap();
+        assert!(deliver_message(mail_dir, "INBOX", b"").is_err());
+        assert!(deliver_message(mail_dir, "INBOX", b" \n\n").is_err());
+        assert!(deliver_message(mail_dir, "INBOX", b"From a@example.com Mon\n").is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use mail_parser::{Message, MimeHeaders, PartType};
+
+/// A recipient the report says could not (yet) be reached
+#[derive(Debug, Clone)]
+pub struct FailedRecipient {
+    pub recipient: String,
+    /// "failed" or "delayed"
+    pub action: String,
+    /// Enhanced status code, e.g. "5.1.1"
+    pub status: Option<String>,
+    /// The remote server's reply, e.g. "smtp; 550 5.1.1 User unknown"
+    pub diagnostic: Option<String>,
+}
+
+/// A parsed multipart/report delivery status notification (RFC 3464)
+#[derive(Debug, Clone)]
+pub struct DeliveryReport {
+    pub failures: Vec<FailedRecipient>,
+    /// Message-ID of the message that bounced, from the returned copy
+    pub original_message_id: Option<String>,
+    pub original_subject: Option<String>,
+}
+
+impl DeliveryReport {
+    /// Plain-text summary shown above the report's own text in the preview
+    pub fn summary(&self) -> String {
+        let failed = self.failures.iter().any(|f| f.action == "failed");
+        let mut text = String::from(if failed {
+            "Delivery failed\n"
+        } else {
+            "Delivery delayed\n"
+        });
+        for failure in &self.failures {
+            text.push_str(&format!("  {}", failure.recipient));
+            if let Some(status) = &failure.status {
+                text.push_str(&format!(" - {} ({})", status, status_class(status)));
+            }
+            text.push('\n');
+            if let Some(diagnostic) = &failure.diagnostic {
+                text.push_str(&format!("    {}\n", diagnostic));
+            }
+        }
+        match (&self.original_subject, &self.original_message_id) {
+            (Some(subject), _) => text.push_str(&format!("Original message: {}\n", subject)),
+            (None, Some(id)) => text.push_str(&format!("Original message: <{}>\n", id)),
+            (None, None) => {}
+        }
+        text.push_str("───────────────────────────────────────\n");
+        text
+    }
+}
+
+/// Read the delivery-status part of a bounce, if `message` is one.
+/// Recipients that were delivered, relayed or expanded are left out
+pub fn delivery_report(message: &Message) -> Option<DeliveryReport> {
+    let is_report = message.content_type().is_some_and(|ct| {
+        ct.ctype().eq_ignore_ascii_case("multipart")
+            && ct
+                .subtype()
+                .is_some_and(|sub| sub.eq_ignore_ascii_case("report"))
+    });
+    if !is_report {
+        return None;
+    }
+
+    let mut failures = Vec::new();
+    let mut original_message_id = None;
+    let mut original_subject = None;
+    for part in message.parts.iter().skip(1) {
+        let Some(ct) = part.content_type() else {
+            continue;
+        };
+        let subtype = ct.subtype().unwrap_or("").to_ascii_lowercase();
+        match (ct.ctype().to_ascii_lowercase().as_str(), subtype.as_str()) {
+            ("message", "delivery-status" | "global-delivery-status") => {
+                let body = match &part.body {
+                    PartType::Binary(data) | PartType::InlineBinary(data) => {
+                        String::from_utf8_lossy(data).to_string()
+                    }
+                    PartType::Text(text) => text.to_string(),
+                    _ => continue,
+                };
+                failures.extend(parse_status_fields(&body));
+            }
+            // The bounced message, whole or headers only
+            ("message", _) | ("text", "rfc822-headers") => {
+                let (id, subject) = match &part.body {
+                    PartType::Message(original) => (
+                        original.message_id().map(str::to_string),
+                        original.subject().map(str::to_string),
+                    ),
+                    PartType::Text(headers) => {
+                        let headers =
+                            mail_parser::MessageParser::default().parse_headers(headers.as_bytes());
+                        (
+                            headers
+                                .as_ref()
+                                .and_then(|h| h.message_id())
+                                .map(str::to_string),
+                            headers
+                                .as_ref()
+                                .and_then(|h| h.subject())
+                                .map(str::to_string),
+                        )
+                    }
+                    _ => continue,
+                };
+                original_message_id = original_message_id.or(id);
+                original_subject = original_subject.or(subject);
+            }
+            _ => {}
+        }
+    }
+
+    if failures.is_empty() {
+        return None;
+    }
+    Some(DeliveryReport {
+        failures,
+        original_message_id,
+        original_subject,
+    })
+}
+
+/// Per-recipient blocks of a message/delivery-status body. The first block
+/// holds per-message fields and has no recipient, so it is skipped naturally
+fn parse_status_fields(body: &str) -> Vec<FailedRecipient> {
+    let mut failures = Vec::new();
+    for block in body.replace("\r\n", "\n").split("\n\n") {
+        let mut recipient = None;
+        let mut action = None;
+        let mut status = None;
+        let mut diagnostic = None;
+        // Unfold continuation lines before splitting fields
+        let mut fields: Vec<String> = Vec::new();
+        for line in block.lines() {
+            match fields.last_mut() {
+                Some(field) if line.starts_with([' ', '\t']) => {
+                    field.push(' ');
+                    field.push_str(line.trim());
+                }
+                _ => fields.push(line.to_string()),
+            }
+        }
+        for field in &fields {
+            let Some((name, value)) = field.split_once(':') else {
+                continue;
+            };
+            // Address fields carry a type prefix: "rfc822; user@example.com"
+            let value = value.trim();
+            let typed = value.split_once(';').map_or(value, |(_, v)| v).trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "final-recipient" => recipient = Some(typed.to_string()),
+                "original-recipient" if recipient.is_none() => recipient = Some(typed.to_string()),
+                "action" => action = Some(value.to_ascii_lowercase()),
+                "status" => status = Some(value.to_string()),
+                "diagnostic-code" => diagnostic = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        let (Some(recipient), Some(action)) = (recipient, action) else {
+            continue;
+        };
+        if action == "failed" || action == "delayed" {
+            failures.push(FailedRecipient {
+                recipient,
+                action,
+                status,
+                diagnostic,
+            });
+        }
+    }
+    failures
+}
+
+/// What the class digit of an enhanced status code means
+fn status_class(status: &str) -> &'static str {
+    match status.chars().next() {
+        Some('4') => "temporary, will be retried",
+        Some('5') => "permanent",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(status: &str, original: &str) -> Vec<u8> {
+        format!(
+            "From: MAILER-DAEMON@example.net\r\n\
+             Subject: Undelivered Mail Returned to Sender\r\n\
+             MIME-Version: 1.0\r\n\
+             Content-Type: multipart/report; report-type=delivery-status; boundary=\"b\"\r\n\
+             \r\n\
+             --b\r\n\
+             Content-Type: text/plain\r\n\
+             \r\n\
+             Your message could not be delivered.\r\n\
+             --b\r\n\
+             Content-Type: message/delivery-status\r\n\
+             \r\n\
+             Reporting-MTA: dns; mx.example.net\r\n\
+             \r\n\
+             {status}\r\n\
+             --b\r\n\
+             {original}\r\n\
+             --b--\r\n"
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_delivery_report() {
+        let status = "Final-Recipient: rfc822; nobody@example.com\r\n\
+                      Original-Recipient: rfc822; alias@example.com\r\n\
+                      Action: failed\r\n\
+                      Status: 5.1.1\r\n\
+                      Diagnostic-Code: smtp; 550 5.1.1 User\r\n \
+                      unknown\r\n\
+                      \r\n\
+                      Final-Recipient: rfc822; ok@example.com\r\n\
+                      Action: delivered\r\n\
+                      Status: 2.0.0";
+        let original = "Content-Type: message/rfc822\r\n\
+                        \r\n\
+                        Message-ID: <sent-1@example.org>\r\n\
+                        Subject: Quarterly numbers\r\n\
+                        \r\n\
+                        Hi";
+        let raw = report(status, original);
+        let message = mail_parser::MessageParser::default().parse(&raw).unwrap();
+        let report = delivery_report(&message).unwrap();
+
+        assert_eq!(report.failures.len(), 1);
+        let failure = &report.failures[0];
+        assert_eq!(failure.recipient, "nobody@example.com");
+        assert_eq!(failure.action, "failed");
+        assert_eq!(failure.status.as_deref(), Some("5.1.1"));
+        assert_eq!(
+            failure.diagnostic.as_deref(),
+            Some("smtp; 550 5.1.1 User unknown")
+        );
+        assert_eq!(
+            report.original_message_id.as_deref(),
+            Some("sent-1@example.org")
+        );
+        assert_eq!(
+            report.original_subject.as_deref(),
+            Some("Quarterly numbers")
+        );
+        assert!(report.summary().starts_with("Delivery failed\n"));
+        assert!(report.summary().contains("5.1.1 (permanent)"));
+    }
+
+    #[test]
+    fn test_delivery_report_without_original_id() {
+        let status = "Original-Recipient: rfc822; late@example.com\r\n\
+                      Action: Delayed\r\n\
+                      Status: 4.4.1";
+        let original = "Content-Type: text/rfc822-headers\r\n\
+                        \r\n\
+                        From: me@example.org\r\n\
+                        Subject: Slow one\r\n";
+        let raw = report(status, original);
+        let message = mail_parser::MessageParser::default().parse(&raw).unwrap();
+        let report = delivery_report(&message).unwrap();
+
+        assert_eq!(report.failures[0].recipient, "late@example.com");
+        assert_eq!(report.failures[0].action, "delayed");
+        assert_eq!(report.failures[0].diagnostic, None);
+        assert_eq!(report.original_message_id, None);
+        assert_eq!(report.original_subject.as_deref(), Some("Slow one"));
+        assert!(report.summary().starts_with("Delivery delayed\n"));
+    }
+
+    #[test]
+    fn test_not_a_delivery_report() {
+        let raw = b"Subject: Hello\r\nContent-Type: text/plain\r\n\r\nHi\r\n";
+        let message = mail_parser::MessageParser::default().parse(raw).unwrap();
+        assert!(delivery_report(&message).is_none());
+
+        // Only delivered recipients: nothing to report
+        let raw = report(
+            "Final-Recipient: rfc822; ok@example.com\r\nAction: delivered",
+            "Content-Type: text/rfc822-headers\r\n\r\nSubject: x\r\n",
+        );
+        let message = mail_parser::MessageParser::default().parse(&raw).unwrap();
+        assert!(delivery_report(&message).is_none());
+    }
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::client::{read_envelope, read_message_id};
+use super::folders::list_folders;
+
+/// Copies of one message (same Message-ID and size) found in the maildir
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    pub subject: String,
+    pub size: u64,
+    /// Full paths; the first is the copy that is kept
+    pub copies: Vec<String>,
+    /// Folder of each copy, relative to the maildir
+    pub folders: Vec<String>,
+}
+
+impl DuplicateGroup {
+    /// Bytes freed by deleting every copy but the first
+    pub fn reclaimable(&self) -> u64 {
+        self.size * (self.copies.len() as u64 - 1)
+    }
+}
+
+/// Find exact duplicates across all folders of `mail_dir`, largest savings first.
+/// Only files sharing a size are opened, so this is mostly directory listing
+pub fn find_duplicates(mail_dir: &str) -> Vec<DuplicateGroup> {
+    let mut by_size: HashMap<u64, Vec<(PathBuf, String)>> = HashMap::new();
+    for folder in list_folders(mail_dir) {
+        for subdir in ["cur", "new"] {
+            let dir = Path::new(mail_dir).join(&folder).join(subdir);
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+                if meta.is_file() {
+                    by_size
+                        .entry(meta.len())
+                        .or_default()
+                        .push((entry.path(), folder.clone()));
+                }
+            }
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (size, files) in by_size {
+        if files.len() < 2 {
+            continue;
+        }
+        let mut by_id: HashMap<String, Vec<(PathBuf, String)>> = HashMap::new();
+        for (path, folder) in files {
+            // Without a Message-ID there is nothing reliable to match on
+            if let Some(id) = read_message_id(&path) {
+                by_id.entry(id).or_default().push((path, folder));
+            }
+        }
+        for mut copies in by_id.into_values().filter(|c| c.len() > 1) {
+            // Keep the All Mail copy (the one mailtui lists), else the first by path
+            copies.sort_by_key(|(path, folder)| (folder != "[Gmail]/All Mail", path.clone()));
+            let paths: Vec<String> = copies
+                .iter()
+                .map(|(path, _)| path.to_string_lossy().to_string())
+                .collect();
+            let subject = read_envelope(&paths[0])
+                .ok()
+                .and_then(|e| e.subject)
+                .unwrap_or_default();
+            groups.push(DuplicateGroup {
+                subject,
+                size,
+                folders: copies.into_iter().map(|(_, folder)| folder).collect(),
+                copies: paths,
+            });
+        }
+    }
+
+    groups.sort_by_key(|g| std::cmp::Reverse(g.reclaimable()));
+    groups
+}
+
+/// Delete every copy in `group` but the kept one, calling `deleted` with
+/// each path removed. On an error the copies not yet deleted stay in `group`
+pub fn delete_duplicates(group: &mut DuplicateGroup, mut deleted: impl FnMut(&str)) -> Result<()> {
+    while group.copies.len() > 1 {
+        std::fs::remove_file(&group.copies[1])?;
+        let path = group.copies.remove(1);
+        group.folders.remove(1);
+        deleted(&path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(mail_dir: &Path, folder: &str, name: &str, content: &str) -> PathBuf {
+        let cur = mail_dir.join(folder).join("cur");
+        std::fs::create_dir_all(&cur).unwrap();
+        let path = cur.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_find_and_delete_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let message = "Message-ID: <a@example.com>\nSubject: copy\n\nBody\n";
+        let kept = write(dir.path(), "[Gmail]/All Mail", "1:2,S", message);
+        let inbox = write(dir.path(), "INBOX", "2:2,S", message);
+        // Same size, different Message-ID: not a copy
+        write(dir.path(), "INBOX", "3:2,S", &message.replace("<a@", "<b@"));
+        // Same Message-ID, edited body: not an exact copy
+        write(
+            dir.path(),
+            "Sent",
+            "4:2,S",
+            &message.replace("Body", "Body, edited"),
+        );
+
+        let mut groups = find_duplicates(dir.path().to_str().unwrap());
+        assert_eq!(groups.len(), 1);
+        let group = &mut groups[0];
+        assert_eq!(group.subject, "copy");
+        assert_eq!(group.copies[0], kept.to_string_lossy());
+        assert_eq!(group.folders, ["[Gmail]/All Mail", "INBOX"]);
+        assert_eq!(group.reclaimable(), message.len() as u64);
+
+        let mut deleted = Vec::new();
+        delete_duplicates(group, |path| deleted.push(path.to_string())).unwrap();
+        assert_eq!(deleted, [inbox.to_string_lossy()]);
+        assert!(kept.exists() && !inbox.exists());
+        assert_eq!(group.copies.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_duplicates_keeps_the_rest_on_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let kept = write(dir.path(), "A", "1", "x");
+        let gone = dir.path().join("A/cur/2");
+        let other = write(dir.path(), "B", "3", "x");
+        let mut group = DuplicateGroup {
+            subject: String::new(),
+            size: 1,
+            copies: [&kept, &gone, &other]
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            folders: vec!["A".into(), "A".into(), "B".into()],
+        };
+        assert!(delete_duplicates(&mut group, |_| {}).is_err());
+        assert_eq!(group.copies.len(), 3);
+        assert!(other.exists());
+    }
+}
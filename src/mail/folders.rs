@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+/// How many recent move destinations to remember
+const RECENT_LIMIT: usize = 5;
+
+/// Why an account's maildir path cannot hold any mail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaildirProblem {
+    /// The path does not exist or is not a directory
+    Missing,
+    /// Neither the path nor any directory under it has cur/ and new/
+    NoFolders,
+}
+
+/// Check that `mail_dir` is a maildir root: itself or some directory below
+/// it must be a maildir folder with both cur/ and new/
+pub fn check_maildir(mail_dir: &str) -> Option<MaildirProblem> {
+    let root = Path::new(mail_dir);
+    if !root.is_dir() {
+        return Some(MaildirProblem::Missing);
+    }
+    let is_folder = |dir: &Path| dir.join("cur").is_dir() && dir.join("new").is_dir();
+    if is_folder(root)
+        || list_folders(mail_dir)
+            .iter()
+            .any(|folder| is_folder(&root.join(folder)))
+    {
+        None
+    } else {
+        Some(MaildirProblem::NoFolders)
+    }
+}
+
+/// All maildir folders under `mail_dir` (directories containing `cur/`),
+/// as paths relative to `mail_dir`, e.g. "Inbox", "[Gmail]/Sent Mail"
+pub fn list_folders(mail_dir: &str) -> Vec<String> {
+    let root = Path::new(mail_dir);
+    let mut folders = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = entry.file_name();
+            if matches!(name.to_str(), Some("cur" | "new" | "tmp")) {
+                continue;
+            }
+            if path.join("cur").is_dir() {
+                let rel = path.strip_prefix(root).unwrap_or(&path);
+                folders.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+            stack.push(path);
+        }
+    }
+
+    folders.sort();
+    folders
+}
+
+fn recent_folders_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|p| p.join("mailtui/recent_folders"))
+}
+
+/// Recently used move destinations, most recent first
+pub fn load_recent_folders() -> Vec<String> {
+    recent_folders_path()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .map(|s| {
+            s.lines()
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Record a move destination, returns the updated recent list
+pub fn remember_folder(folder: &str) -> Vec<String> {
+    let mut recent = load_recent_folders();
+    recent.retain(|f| f != folder);
+    recent.insert(0, folder.to_string());
+    recent.truncate(RECENT_LIMIT);
+
+    if let Some(path) = recent_folders_path() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let _ = std::fs::write(path, recent.join("\n"));
+    }
+    recent
+}
@@ -0,0 +1,685 @@
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::client::{find_info_section, INFO_SEPARATOR};
+use super::types::Envelope;
+
+/// Largest Content-Length trusted on import; a bigger one is taken as
+/// corrupt rather than read into memory
+const MAX_CONTENT_LENGTH: u64 = 100 * 1024 * 1024;
+
+/// Lines of an mbox file, split on raw bytes (old archives are rarely valid
+/// UTF-8 throughout), with room to push back lines read ahead
+struct MboxLines<R> {
+    reader: R,
+    pending: VecDeque<Vec<u8>>,
+}
+
+impl<R: BufRead> MboxLines<R> {
+    /// Next line without its '\n', or None at the end of the file
+    fn next_line(&mut self) -> Result<Option<Vec<u8>>> {
+        if let Some(line) = self.pending.pop_front() {
+            return Ok(Some(line));
+        }
+        let mut line = Vec::new();
+        if self.reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        Ok(Some(line))
+    }
+
+    /// Read a body of `len` bytes as given by Content-Length (mboxcl2: the
+    /// body is not escaped, so only its length tells where it ends). The
+    /// length is only trusted when the file ends or the next message starts
+    /// right after it; otherwise everything read is put back as lines
+    fn body(&mut self, len: u64) -> Result<Option<Vec<u8>>> {
+        if len > MAX_CONTENT_LENGTH || !self.pending.is_empty() {
+            return Ok(None);
+        }
+        let mut body = Vec::new();
+        (&mut self.reader).take(len).read_to_end(&mut body)?;
+
+        let mut ahead = Vec::new();
+        let boundary = loop {
+            let Some(line) = self.next_line()? else {
+                break true;
+            };
+            let content = line.strip_suffix(b"\r").unwrap_or(&line);
+            let from = content.starts_with(b"From ");
+            let blank = content.is_empty();
+            ahead.push(line);
+            // Allow the one blank line that separates messages
+            if from || !blank || ahead.len() > 1 {
+                break from;
+            }
+        };
+        if body.len() as u64 == len && boundary {
+            self.pending.extend(ahead);
+            return Ok(Some(body));
+        }
+
+        // A body cut mid-line continues on the first line read ahead
+        for line in ahead {
+            body.extend_from_slice(&line);
+            body.push(b'\n');
+        }
+        if body.last() == Some(&b'\n') {
+            body.pop();
+        }
+        self.pending
+            .extend(body.split(|&b| b == b'\n').map(<[u8]>::to_vec));
+        Ok(None)
+    }
+}
+
+/// Split an mbox file into maildir messages under `mail_dir/folder`.
+/// Reads mboxrd and mboxo (`>From ` body lines lose one '>') as well as
+/// mboxcl2, whose bodies are unescaped and sized by a Content-Length header
+/// (such a body is never unescaped, even when the length turns out wrong).
+/// Returns how many messages were imported
+pub fn import_mbox(mbox_path: &str, mail_dir: &str, folder: &str) -> Result<usize> {
+    let file =
+        std::fs::File::open(mbox_path).with_context(|| format!("Cannot open {}", mbox_path))?;
+    let dest = Path::new(mail_dir).join(folder);
+    for sub in ["cur", "new", "tmp"] {
+        std::fs::create_dir_all(dest.join(sub))?;
+    }
+
+    let host = hostname();
+    let mut count = 0;
+    let mut message: Vec<u8> = Vec::new();
+    let mut in_message = false;
+    let mut in_headers = false;
+    let mut unescape = true;
+    let mut prev_blank = true;
+
+    let mut lines = MboxLines {
+        reader: BufReader::new(file),
+        pending: VecDeque::new(),
+    };
+    while let Some(line) = lines.next_line()? {
+        let content = line.strip_suffix(b"\r").unwrap_or(&line);
+
+        // A "From " line after a blank line starts the next message
+        if prev_blank && content.starts_with(b"From ") {
+            if in_message {
+                write_message(&dest, &host, count, &message)?;
+                count += 1;
+            }
+            message.clear();
+            in_message = true;
+            in_headers = true;
+            prev_blank = false;
+            continue;
+        }
+        prev_blank = content.is_empty();
+
+        if !in_message {
+            continue;
+        }
+        if in_headers {
+            message.extend_from_slice(&line);
+            message.push(b'\n');
+            if content.is_empty() {
+                in_headers = false;
+                let len = content_length(&message);
+                unescape = len.is_none();
+                if let Some(len) = len
+                    && let Some(body) = lines.body(len)?
+                {
+                    message.extend_from_slice(&body);
+                    // The body ends where the next message starts
+                    prev_blank = true;
+                }
+            }
+            continue;
+        }
+        // mboxrd escapes body lines matching ^>*From with one more '>'
+        let unescaped = if unescape && is_escaped_from(content) {
+            &line[1..]
+        } else {
+            &line[..]
+        };
+        message.extend_from_slice(unescaped);
+        message.push(b'\n');
+    }
+    if in_message {
+        write_message(&dest, &host, count, &message)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Append `envelopes` to one mbox file (mboxrd escaping).
+/// Content-Length and Lines headers are left out: they would not match the
+/// escaped body, and readers that trust them would cut it in the wrong place.
+/// Status and X-Status are written from the maildir flags instead of copied.
+/// Line endings are kept as they are in the message file.
+/// Returns how many messages were written
+pub fn export_mbox(envelopes: &[&Envelope], out: &Path) -> Result<usize> {
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(out)?);
+    let mut count = 0;
+    for env in envelopes {
+        let Some(file_path) = env.file_path.as_deref() else {
+            continue;
+        };
+        let raw = std::fs::read(file_path)?;
+        // A leading "From " line left by the delivering MTA is replaced by ours
+        let raw = match raw.strip_prefix(b"From ") {
+            Some(rest) => rest
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(&[][..], |end| &rest[end + 1..]),
+            None => &raw[..],
+        };
+        // The final newline ends the last line rather than adding an empty one
+        let raw = raw.strip_suffix(b"\n").unwrap_or(raw);
+        writeln!(writer, "{}", from_line(raw))?;
+        let mut in_headers = true;
+        for line in raw.split(|&b| b == b'\n') {
+            let content = line.strip_suffix(b"\r").unwrap_or(line);
+            if in_headers {
+                if content.is_empty() {
+                    in_headers = false;
+                    // Same line ending as the blank line ending the headers
+                    let eol = &line[content.len()..];
+                    for header in status_headers(file_path) {
+                        writer.write_all(header.as_bytes())?;
+                        writer.write_all(eol)?;
+                        writer.write_all(b"\n")?;
+                    }
+                } else if is_length_header(content) || is_status_header(content) {
+                    continue;
+                }
+            }
+            if content.starts_with(b"From ") || is_escaped_from(content) {
+                writer.write_all(b">")?;
+            }
+            writer.write_all(line)?;
+            writer.write_all(b"\n")?;
+        }
+        // Blank line before the next separator
+        writer.write_all(b"\n")?;
+        count += 1;
+    }
+    writer.flush()?;
+    Ok(count)
+}
+
+/// Copy `envelopes` into `dir` as numbered .eml files named after their subjects.
+/// Returns how many messages were written
+pub fn export_eml(envelopes: &[&Envelope], dir: &Path) -> Result<usize> {
+    std::fs::create_dir_all(dir)?;
+    let mut count = 0;
+    for env in envelopes {
+        let Some(file_path) = env.file_path.as_deref() else {
+            continue;
+        };
+        let subject: String = env
+            .subject
+            .as_deref()
+            .unwrap_or("")
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == ' ' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .take(60)
+            .collect();
+        let name = format!("{:04} {}.eml", count + 1, subject.trim());
+        std::fs::copy(file_path, dir.join(name))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// mbox separator: `From <sender> <asctime date>`
+fn from_line(raw: &[u8]) -> String {
+    let message = mail_parser::MessageParser::default().parse_headers(raw);
+    let sender = message
+        .as_ref()
+        .and_then(|m| m.return_address().or_else(|| m.from()?.first()?.address()))
+        .filter(|addr| !addr.contains(char::is_whitespace))
+        .unwrap_or("MAILER-DAEMON")
+        .to_string();
+    let timestamp = message
+        .as_ref()
+        .and_then(|m| m.date())
+        .map(|d| d.to_timestamp())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0)
+        });
+    let date = mail_parser::DateTime::from_timestamp(timestamp);
+    const DAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    format!(
+        "From {} {} {} {:2} {:02}:{:02}:{:02} {}",
+        sender,
+        DAYS[date.day_of_week() as usize % 7],
+        MONTHS[(date.month as usize + 11) % 12],
+        date.day,
+        date.hour,
+        date.minute,
+        date.second,
+        date.year
+    )
+}
+
+/// Value of the Content-Length header in a message's header block
+fn content_length(headers: &[u8]) -> Option<u64> {
+    headers.split(|&b| b == b'\n').find_map(|line| {
+        let (name, value) = std::str::from_utf8(line).ok()?.split_once(':')?;
+        if name.eq_ignore_ascii_case("content-length") {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Content-Length or Lines header (body size as an mbox stored it); a
+/// folded continuation of either is not expected
+fn is_length_header(line: &[u8]) -> bool {
+    let name = line.split(|&b| b == b':').next().unwrap_or_default();
+    line.contains(&b':')
+        && (name.eq_ignore_ascii_case(b"content-length") || name.eq_ignore_ascii_case(b"lines"))
+}
+
+/// Status or X-Status header (flags as an mbox stores them)
+fn is_status_header(line: &[u8]) -> bool {
+    let name = line.split(|&b| b == b':').next().unwrap_or_default();
+    line.contains(&b':')
+        && (name.eq_ignore_ascii_case(b"status") || name.eq_ignore_ascii_case(b"x-status"))
+}
+
+/// Status and X-Status headers for a message file's maildir flags, the
+/// reverse of `mbox_flags`: seen is R in Status, replied, flagged and
+/// trashed are A, F and D in X-Status
+fn status_headers(file_path: &str) -> Vec<String> {
+    let name = Path::new(file_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let flags = find_info_section(&name).map_or("", |i| &name[i + INFO_SEPARATOR.len()..]);
+    let status = if flags.contains('S') { "RO" } else { "O" };
+    let x_status: String = [('R', 'A'), ('F', 'F'), ('T', 'D')]
+        .iter()
+        .filter(|(flag, _)| flags.contains(*flag))
+        .map(|(_, letter)| letter)
+        .collect();
+    let mut headers = vec![format!("Status: {}", status)];
+    if !x_status.is_empty() {
+        headers.push(format!("X-Status: {}", x_status));
+    }
+    headers
+}
+
+/// `>From `, `>>From `, ... (an escaped body line)
+fn is_escaped_from(line: &[u8]) -> bool {
+    let rest = line.iter().skip_while(|&&b| b == b'>');
+    line.first() == Some(&b'>') && rest.copied().take(5).eq(b"From ".iter().copied())
+}
+
+/// Write one message via tmp/ into cur/, named per the maildir spec
+fn write_message(dest: &Path, host: &str, seq: usize, message: &[u8]) -> Result<()> {
+    // The blank line before the next "From " belongs to the mbox, not the message
+    let message = if message.ends_with(b"\n\n") {
+        &message[..message.len() - 1]
+    } else {
+        message
+    };
+
+    let unique = unique_name(host, seq);
+    let filename = format!("{}{}{}", unique, INFO_SEPARATOR, mbox_flags(message));
+
+    let tmp = dest.join("tmp").join(&unique);
+    std::fs::write(&tmp, message)?;
+    std::fs::rename(&tmp, dest.join("cur").join(filename))?;
+    Ok(())
+}
+
+/// Maildir flags from the Status, X-Status and X-Mozilla-Status headers,
+/// in the sorted order maildir expects
+fn mbox_flags(message: &[u8]) -> String {
+    let mut flags = Vec::new();
+    for line in message.split(|&b| b == b'\n') {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end();
+        if line.is_empty() {
+            break; // end of headers
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.to_ascii_lowercase().as_str() {
+            "status" if value.contains('R') => flags.push('S'),
+            "x-status" => {
+                for c in value.chars() {
+                    match c {
+                        'A' => flags.push('R'),
+                        'F' => flags.push('F'),
+                        'D' => flags.push('T'),
+                        _ => {}
+                    }
+                }
+            }
+            // Thunderbird: 0x1 read, 0x2 replied, 0x4 flagged, 0x8 deleted
+            "x-mozilla-status" => {
+                let bits = u32::from_str_radix(value, 16).unwrap_or(0);
+                for (bit, flag) in [(0x1, 'S'), (0x2, 'R'), (0x4, 'F'), (0x8, 'T')] {
+                    if bits & bit != 0 {
+                        flags.push(flag);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    flags.sort_unstable();
+    flags.dedup();
+    flags.into_iter().collect()
+}
+
+/// Unique part of a maildir filename: time, microseconds, pid and a
+/// per-process sequence number, then the host
+pub(super) fn unique_name(host: &str, seq: usize) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format!(
+        "{}.M{}P{}Q{}.{}",
+        now.as_secs(),
+        now.subsec_micros(),
+        std::process::id(),
+        seq,
+        host
+    )
+}
+
+/// Host part of maildir filenames; '/' and ':' are not allowed in it
+pub(super) fn hostname() -> String {
+    let host = std::fs::read_to_string("/etc/hostname")
+        .ok()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .unwrap_or_else(|| "localhost".to_string());
+    host.replace('/', "\\057").replace(':', "\\072")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Import `mbox` into a fresh maildir; the messages in mbox order with
+    /// their maildir flags
+    fn import(mbox: &[u8]) -> Vec<(String, String)> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("in.mbox");
+        std::fs::write(&path, mbox).unwrap();
+        let mail_dir = dir.path().to_str().unwrap();
+        let count = import_mbox(path.to_str().unwrap(), mail_dir, "Imported").unwrap();
+        let mut messages: Vec<(usize, String, String)> =
+            std::fs::read_dir(dir.path().join("Imported/cur"))
+                .unwrap()
+                .map(|entry| {
+                    let entry = entry.unwrap();
+                    let name = entry.file_name().into_string().unwrap();
+                    let (unique, flags) = name.split_once(INFO_SEPARATOR).unwrap();
+                    let seq = unique.split('Q').nth(1).unwrap().split('.').next().unwrap();
+                    let content = std::fs::read_to_string(entry.path()).unwrap();
+                    let flags = flags.trim_start_matches("2,").to_string();
+                    (seq.parse().unwrap(), content, flags)
+                })
+                .collect();
+        assert_eq!(messages.len(), count);
+        messages.sort();
+        messages.into_iter().map(|(_, m, f)| (m, f)).collect()
+    }
+
+    /// Export messages given as (maildir flags, content) to mbox bytes
+    fn export(messages: &[(&str, &str)]) -> Vec<u8> {
+        let dir = tempfile::tempdir().unwrap();
+        let envelopes: Vec<Envelope> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, (flags, content))| {
+                let path = dir
+                    .path()
+                    .join(format!("{}.M1P1.host{}{}", i, INFO_SEPARATOR, flags));
+                std::fs::write(&path, content).unwrap();
+                Envelope {
+                    file_path: Some(path.to_string_lossy().to_string()),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let out = dir.path().join("out.mbox");
+        let refs: Vec<&Envelope> = envelopes.iter().collect();
+        assert_eq!(export_mbox(&refs, &out).unwrap(), messages.len());
+        std::fs::read(out).unwrap()
+    }
+
+    /// A message without the headers an mbox round trip rewrites
+    fn without_mbox_headers(message: &str) -> String {
+        message
+            .split_inclusive('\n')
+            .filter(|line| {
+                let line = line.as_bytes();
+                !is_status_header(line) && !is_length_header(line)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_splits_on_from_lines() {
+        let messages = import(
+            b"From a@example.com Mon Jan  1 00:00:00 2024\n\
+              Subject: one\n\
+              \n\
+              Hello\n\
+              From here on it's not a separator\n\
+              \n\
+              From b@example.com Tue Jan  2 00:00:00 2024\n\
+              Subject: two\n\
+              \n\
+              Bye\n",
+        );
+        assert_eq!(
+            messages,
+            [
+                (
+                    "Subject: one\n\nHello\nFrom here on it's not a separator\n".to_string(),
+                    String::new()
+                ),
+                ("Subject: two\n\nBye\n".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_unescapes_from_lines() {
+        let messages = import(
+            b"From a@example.com Mon Jan  1 00:00:00 2024\n\
+              Subject: one\n\
+              \n\
+              >From the start\n\
+              >>From quoted\n\
+              > From not escaped\n",
+        );
+        assert_eq!(
+            messages[0].0,
+            "Subject: one\n\nFrom the start\n>From quoted\n> From not escaped\n"
+        );
+    }
+
+    #[test]
+    fn test_import_content_length_body() {
+        let body = "From inside the body\n\n";
+        let mbox = format!(
+            "From a@example.com Mon Jan  1 00:00:00 2024\n\
+             Subject: one\n\
+             Content-Length: {}\n\
+             \n\
+             {}\n\
+             From b@example.com Tue Jan  2 00:00:00 2024\n\
+             Subject: two\n\
+             \n\
+             Bye\n",
+            body.len(),
+            body
+        );
+        let messages = import(mbox.as_bytes());
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].0,
+            format!("Subject: one\nContent-Length: {}\n\n{}", body.len(), body)
+        );
+    }
+
+    #[test]
+    fn test_import_flags() {
+        let messages = import(
+            b"From a@example.com Mon Jan  1 00:00:00 2024\n\
+              Status: RO\n\
+              X-Status: AF\n\
+              \n\
+              Body\n\
+              \n\
+              From b@example.com Tue Jan  2 00:00:00 2024\n\
+              X-Mozilla-Status: 0009\n\
+              \n\
+              Body\n",
+        );
+        assert_eq!(messages[0].1, "FRS");
+        assert_eq!(messages[1].1, "ST");
+    }
+
+    #[test]
+    fn test_round_trip_mboxrd() {
+        let first = "From: a@example.com\nSubject: one\n\n\
+                     From the start\n>From quoted\n>>From twice\nend\n";
+        let second = "Subject: two\r\nStatus: O\r\n\r\n\
+                      From here\r\n>From there\r\n";
+        let mbox = export(&[("FS", first), ("RT", second)]);
+        let text = String::from_utf8(mbox.clone()).unwrap();
+        assert!(text.contains("\n>From the start\n>>From quoted\n>>>From twice\n"));
+        assert!(text.contains("Subject: one\nStatus: RO\nX-Status: F\n\n"));
+        // CRLF kept, stale Status replaced
+        assert!(text.contains("Subject: two\r\nStatus: O\r\nX-Status: AD\r\n\r\n"));
+        assert!(text.contains("\r\n>From here\r\n>>From there\r\n"));
+
+        let messages = import(&mbox);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(without_mbox_headers(&messages[0].0), first);
+        assert_eq!(messages[0].1, "FS");
+        assert_eq!(
+            without_mbox_headers(&messages[1].0),
+            without_mbox_headers(second)
+        );
+        assert_eq!(messages[1].1, "RT");
+    }
+
+    #[test]
+    fn test_round_trip_mboxcl2() {
+        let body = "From the start\r\n>From quoted\r\n\r\nFrom after a blank\r\n";
+        let cl2 = format!(
+            "From a@example.com Mon Jan  1 00:00:00 2024\r\n\
+             Subject: one\r\n\
+             Content-Length: {}\r\n\
+             \r\n\
+             {}\n\
+             From b@example.com Tue Jan  2 00:00:00 2024\n\
+             Subject: two\n\
+             Content-Length: 3\n\
+             \n\
+             >From a body longer than its Content-Length\n",
+            body.len(),
+            body
+        );
+        let messages = import(cl2.as_bytes());
+        assert_eq!(messages.len(), 2);
+        let first = format!(
+            "Subject: one\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        assert_eq!(messages[0].0, first);
+        // A wrong length falls back to lines, still without unescaping
+        assert_eq!(
+            messages[1].0,
+            "Subject: two\nContent-Length: 3\n\n>From a body longer than its Content-Length\n"
+        );
+
+        // Exported as mboxrd and read back, the messages are unchanged
+        let mbox = export(&[("", &messages[0].0), ("", &messages[1].0)]);
+        let again = import(&mbox);
+        assert_eq!(again.len(), 2);
+        for (before, after) in messages.iter().zip(&again) {
+            assert_eq!(
+                without_mbox_headers(&after.0),
+                without_mbox_headers(&before.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_escaped_from() {
+        assert!(is_escaped_from(b">From x"));
+        assert!(is_escaped_from(b">>>From x"));
+        assert!(!is_escaped_from(b"From x"));
+        assert!(!is_escaped_from(b"> From x"));
+        assert!(!is_escaped_from(b">Fromage"));
+    }
+
+    #[test]
+    fn test_length_headers() {
+        assert!(is_length_header(b"Content-Length: 12"));
+        assert!(is_length_header(b"lines: 3"));
+        assert!(!is_length_header(b"Content-Type: text/plain"));
+        assert_eq!(
+            content_length(b"Subject: x\ncontent-length:  42 \n\n"),
+            Some(42)
+        );
+        assert_eq!(content_length(b"Subject: x\n\n"), None);
+    }
+
+    #[test]
+    fn test_from_line() {
+        let raw = b"From: Ann <ann@example.com>\nDate: Tue, 2 Jan 2024 03:04:05 +0000\n\n";
+        assert_eq!(
+            from_line(raw),
+            "From ann@example.com Tue Jan  2 03:04:05 2024"
+        );
+        let raw = b"Return-Path: <bounce@example.com>\nFrom: ann@example.com\n\
+                    Date: Tue, 2 Jan 2024 03:04:05 +0000\n\n";
+        assert!(from_line(raw).starts_with("From bounce@example.com "));
+        assert!(from_line(b"Subject: x\n\n").starts_with("From MAILER-DAEMON "));
+    }
+
+    #[test]
+    fn test_unique_name() {
+        let a = unique_name("host", 0);
+        let b = unique_name("host", 1);
+        assert_ne!(a, b);
+        assert!(a.ends_with("Q0.host"));
+        assert!(!hostname().contains(['/', ':']));
+    }
+}
@@ -1,8 +1,26 @@
 mod cache;
+mod calendar;
+mod cleanup;
 mod client;
+mod deliver;
+mod dsn;
+mod duplicates;
+mod folders;
+mod mbox;
+mod notmuch;
+mod sizes;
 mod threading;
 mod types;
 
+pub use cache::{lock_instance, prune_rendered_html};
+pub use calendar::*;
+pub use cleanup::*;
 pub use client::*;
+pub use deliver::*;
+pub use duplicates::*;
+pub use folders::*;
+pub use mbox::*;
+pub use notmuch::*;
+pub use sizes::*;
 pub use threading::*;
 pub use types::*;
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::process::{Command, Stdio};
+
+use super::types::Envelope;
+
+/// Quote a Message-ID for use in a notmuch query
+fn id_query(message_id: &str) -> String {
+    format!("id:\"{}\"", message_id.replace('"', "\"\""))
+}
+
+/// Apply tag changes (e.g. ["-unread"], ["+flagged"]) to a message in notmuch
+pub fn notmuch_tag(message_id: &str, changes: &[&str]) -> Result<()> {
+    let status = Command::new("notmuch")
+        .arg("tag")
+        .args(changes)
+        .arg("--")
+        .arg(id_query(message_id))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if !status.success() {
+        anyhow::bail!("notmuch tag failed");
+    }
+    Ok(())
+}
+
+/// Message-IDs notmuch currently tags as unread
+pub fn notmuch_unread_ids() -> Result<HashSet<String>> {
+    let output = Command::new("notmuch")
+        .args(["search", "--output=messages", "tag:unread"])
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("notmuch search failed");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.strip_prefix("id:"))
+        .map(|s| s.to_string())
+        .collect())
+}
+
+/// Treat messages notmuch tags as unread as unread, regardless of maildir flags
+pub fn apply_notmuch_unread(envelopes: &mut [Envelope]) -> Result<()> {
+    let unread = notmuch_unread_ids()?;
+    for env in envelopes.iter_mut() {
+        if env
+            .message_id
+            .as_ref()
+            .map(|mid| unread.contains(mid))
+            .unwrap_or(false)
+        {
+            env.flags.retain(|f| f != "Seen");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::folders::list_folders;
+
+/// Disk use of one maildir folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FolderSize {
+    pub folder: String,
+    pub bytes: u64,
+    pub messages: usize,
+}
+
+/// Local file holding the last count of every folder: one
+/// `<maildir>\t<folder>\t<cur mtime>\t<new mtime>\t<bytes>\t<messages>` per line.
+/// A folder whose cur/ and new/ were not touched since is not re-read
+fn sizes_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|p| p.join("mailtui/folder_sizes"))
+}
+
+/// Modification time of a directory in nanoseconds (0 if missing)
+fn dir_mtime(dir: &Path) -> u128 {
+    std::fs::metadata(dir)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos())
+}
+
+/// Total bytes and file count of a folder's cur/ and new/
+fn count_folder(dir: &Path) -> (u64, usize) {
+    let mut bytes = 0;
+    let mut messages = 0;
+    for sub in ["cur", "new"] {
+        let Ok(entries) = std::fs::read_dir(dir.join(sub)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if let Ok(meta) = entry.metadata()
+                && meta.is_file()
+            {
+                bytes += meta.len();
+                messages += 1;
+            }
+        }
+    }
+    (bytes, messages)
+}
+
+/// Size of every folder under `mail_dir`, in folder order. Only folders that
+/// changed since the last call are re-read; the counts are cached on disk
+pub fn folder_sizes(mail_dir: &str) -> Vec<FolderSize> {
+    type Entry = (u128, u128, u64, usize);
+    let path = sizes_path();
+    let content = path
+        .as_ref()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .unwrap_or_default();
+
+    // Lines of other maildirs are kept as they are
+    let mut other_lines = Vec::new();
+    let mut cached: HashMap<String, Entry> = HashMap::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.first() != Some(&mail_dir) {
+            other_lines.push(line.to_string());
+            continue;
+        }
+        let [_, folder, cur, new, bytes, messages] = fields[..] else {
+            continue;
+        };
+        let (Ok(cur), Ok(new), Ok(bytes), Ok(messages)) =
+            (cur.parse(), new.parse(), bytes.parse(), messages.parse())
+        else {
+            continue;
+        };
+        cached.insert(folder.to_string(), (cur, new, bytes, messages));
+    }
+
+    let mut lines = other_lines;
+    let sizes: Vec<FolderSize> = list_folders(mail_dir)
+        .into_iter()
+        .map(|folder| {
+            let dir = Path::new(mail_dir).join(&folder);
+            let cur = dir_mtime(&dir.join("cur"));
+            let new = dir_mtime(&dir.join("new"));
+            let (bytes, messages) = match cached.get(&folder) {
+                Some(&(c, n, bytes, messages)) if c == cur && n == new => (bytes, messages),
+                _ => count_folder(&dir),
+            };
+            lines.push(format!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                mail_dir, folder, cur, new, bytes, messages
+            ));
+            FolderSize {
+                folder,
+                bytes,
+                messages,
+            }
+        })
+        .collect();
+
+    if let Some(path) = path {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let _ = std::fs::write(path, lines.join("\n"));
+    }
+    sizes
+}
+
+/// Space an account's mail takes. Gmail keeps each label as a folder holding
+/// copies of All Mail messages, so there only All Mail, trash and spam count
+/// (what Gmail's storage quota counts); elsewhere every folder does
+pub fn account_size(sizes: &[FolderSize], trash_folder: &str, spam_folder: &str) -> u64 {
+    let gmail = sizes.iter().any(|s| s.folder == "[Gmail]/All Mail");
+    sizes
+        .iter()
+        .filter(|s| {
+            !gmail || ["[Gmail]/All Mail", trash_folder, spam_folder].contains(&s.folder.as_str())
+        })
+        .map(|s| s.bytes)
+        .sum()
+}
@@ -1,18 +1,48 @@
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::types::Envelope;
+use crate::config::ThreadingConfig;
 
 /// Build a flat, display-ready list with threading metadata.
 /// Messages are grouped into threads, sorted by most recent message (descending),
 /// and within each thread, sorted chronologically (ascending).
-/// Linear chains are collapsed (depth 1), branching creates new levels (max depth 3).
+/// Linear chains are collapsed (depth 1), branching creates new levels (max depth 3),
+/// unless `config` says otherwise; with `config.flat` every message stands alone.
 /// Uses parallel processing for performance.
-pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
+pub fn build_threaded_list(
+    mut envelopes: Vec<Envelope>,
+    config: &ThreadingConfig,
+) -> Vec<Envelope> {
     if envelopes.is_empty() {
         return envelopes;
     }
 
+    // 0. Flag sent messages that a bounce in the list reports on
+    let bounced: HashSet<String> = envelopes
+        .iter()
+        .filter_map(|env| env.bounce_of.clone())
+        .collect();
+    for env in envelopes.iter_mut() {
+        env.bounced = env.is_sent
+            && env
+                .message_id
+                .as_ref()
+                .is_some_and(|mid| bounced.contains(mid));
+    }
+
+    if config.flat {
+        envelopes.par_sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        for env in envelopes.iter_mut() {
+            env.thread_depth = 0;
+            env.display_depth = 0;
+            env.is_last_in_thread = true;
+            env.tree_prefix.clear();
+            env.unread_replies = 0;
+        }
+        return envelopes;
+    }
+
     let len = envelopes.len();
 
     // 1. Build message_id -> index map (parallel)
@@ -26,25 +56,7 @@ pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
     let parent: Vec<Option<usize>> = envelopes
         .par_iter()
         .enumerate()
-        .map(|(i, env)| {
-            // First try in_reply_to
-            if let Some(ref reply_to) = env.in_reply_to {
-                if let Some(&parent_idx) = id_to_idx.get(reply_to) {
-                    if parent_idx != i {
-                        return Some(parent_idx);
-                    }
-                }
-            }
-            // Fall back to last entry in references
-            for ref_id in env.references.iter().rev() {
-                if let Some(&parent_idx) = id_to_idx.get(ref_id) {
-                    if parent_idx != i {
-                        return Some(parent_idx);
-                    }
-                }
-            }
-            None
-        })
+        .map(|(i, env)| find_parent(i, env, &id_to_idx).map(|(p, _)| p))
         .collect();
 
     // 3. Build children map using parallel fold + reduce
@@ -70,11 +82,7 @@ pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
     let children: HashMap<usize, Vec<usize>> = children
         .into_par_iter()
         .map(|(parent_idx, mut kids)| {
-            kids.sort_by(|&a, &b| {
-                let date_a = envelopes[a].date.as_deref().unwrap_or("");
-                let date_b = envelopes[b].date.as_deref().unwrap_or("");
-                date_a.cmp(date_b)
-            });
+            kids.sort_by_key(|&i| envelopes[i].timestamp);
             (parent_idx, kids)
         })
         .collect();
@@ -124,15 +132,10 @@ pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
         });
 
     // 6. For each thread, find the most recent message date (parallel)
-    let thread_last_date: HashMap<usize, String> = threads
+    let thread_last_date: HashMap<usize, Option<i64>> = threads
         .par_iter()
         .map(|(&root, indices)| {
-            let max_date = indices
-                .iter()
-                .filter_map(|&i| envelopes[i].date.as_ref())
-                .max()
-                .cloned()
-                .unwrap_or_default();
+            let max_date = indices.iter().filter_map(|&i| envelopes[i].timestamp).max();
             (root, max_date)
         })
         .collect();
@@ -140,9 +143,9 @@ pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
     // 7. Get sorted roots
     let mut roots: Vec<usize> = threads.keys().copied().collect();
     roots.par_sort_by(|&a, &b| {
-        let date_a = thread_last_date.get(&a).map(|s| s.as_str()).unwrap_or("");
-        let date_b = thread_last_date.get(&b).map(|s| s.as_str()).unwrap_or("");
-        date_b.cmp(date_a) // Descending
+        let date_a = thread_last_date.get(&a).copied().flatten();
+        let date_b = thread_last_date.get(&b).copied().flatten();
+        date_b.cmp(&date_a) // Descending; undated threads last
     });
 
     // 8. Process each thread in parallel and collect full Envelope results
@@ -164,7 +167,12 @@ pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
             );
 
             // Compute display depths
-            let display_depths = compute_display_depths(&thread_messages, children_ref);
+            let display_depths = compute_display_depths(&thread_messages, children_ref, config);
+            let unread_replies = thread_messages
+                .iter()
+                .skip(1)
+                .filter(|(msg_idx, _, _)| envelopes_ref[*msg_idx].is_unread())
+                .count();
 
             // Build result envelopes directly
             let thread_len = thread_messages.len();
@@ -181,6 +189,7 @@ pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
                     env.display_depth = display_depth;
                     env.is_last_in_thread = is_last;
                     env.tree_prefix = prefix;
+                    env.unread_replies = if i == 0 { unread_replies } else { 0 };
                     env
                 })
                 .collect()
@@ -191,6 +200,154 @@ pub fn build_threaded_list(envelopes: Vec<Envelope>) -> Vec<Envelope> {
     thread_results.into_iter().flatten().collect()
 }
 
+/// How a message was attached to the one above it in its thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThreadLink {
+    /// No In-Reply-To or References: the message starts a thread
+    Root,
+    /// Names a parent, but no listed message has that Message-ID
+    Orphan { wanted: String },
+    /// Its In-Reply-To is listed
+    InReplyTo(String),
+    /// In-Reply-To missing or not listed; the latest listed References entry
+    Reference(String),
+}
+
+/// Parent of envelope `i` and the header that named it: In-Reply-To first,
+/// then the last listed entry in References (never the message itself)
+fn find_parent(
+    i: usize,
+    env: &Envelope,
+    id_to_idx: &HashMap<String, usize>,
+) -> Option<(usize, ThreadLink)> {
+    if let Some(ref reply_to) = env.in_reply_to
+        && let Some(&parent_idx) = id_to_idx.get(reply_to)
+        && parent_idx != i
+    {
+        return Some((parent_idx, ThreadLink::InReplyTo(reply_to.clone())));
+    }
+    for ref_id in env.references.iter().rev() {
+        if let Some(&parent_idx) = id_to_idx.get(ref_id)
+            && parent_idx != i
+        {
+            return Some((parent_idx, ThreadLink::Reference(ref_id.clone())));
+        }
+    }
+    None
+}
+
+/// Why one message of a thread sits where it does
+#[derive(Debug, Clone)]
+pub struct ThreadDebugRow {
+    /// Envelope id
+    pub id: String,
+    pub subject: Option<String>,
+    pub message_id: Option<String>,
+    pub in_reply_to: Option<String>,
+    pub references: usize,
+    /// Steps up the parent links to the thread root
+    pub raw_depth: usize,
+    /// Indent drawn in the list (linear chains collapsed, capped at 3)
+    pub display_depth: usize,
+    /// Replies the parent has: more than one makes a branch
+    pub siblings: usize,
+    /// Indent the parent is drawn at
+    pub parent_display_depth: Option<usize>,
+    pub link: ThreadLink,
+    /// Another listed message has the same Message-ID; only one of them
+    /// can be a parent
+    pub duplicate_id: bool,
+}
+
+/// Explain the thread holding `envelopes[index]` the way `build_threaded_list`
+/// resolves it against the whole list: each member's parent link, raw and
+/// display depth, in list order
+pub fn explain_thread(envelopes: &[Envelope], index: usize) -> Vec<ThreadDebugRow> {
+    let mut id_to_idx: HashMap<String, usize> = HashMap::new();
+    let mut id_count: HashMap<&str, usize> = HashMap::new();
+    for (i, env) in envelopes.iter().enumerate() {
+        if let Some(mid) = &env.message_id {
+            id_to_idx.insert(mid.clone(), i);
+            *id_count.entry(mid).or_default() += 1;
+        }
+    }
+    let parent: Vec<Option<(usize, ThreadLink)>> = envelopes
+        .iter()
+        .enumerate()
+        .map(|(i, env)| find_parent(i, env, &id_to_idx))
+        .collect();
+
+    // Root and raw depth, with the same cycle guard as threading
+    let root_of = |i: usize| {
+        let mut current = i;
+        let mut depth = 0;
+        while let Some((p, _)) = &parent[current] {
+            current = *p;
+            depth += 1;
+            if depth > 1000 {
+                break;
+            }
+        }
+        (current, depth)
+    };
+    let Some(root) = envelopes.get(index).map(|_| root_of(index).0) else {
+        return Vec::new();
+    };
+
+    let mut replies: HashMap<usize, usize> = HashMap::new();
+    for (p, _) in parent.iter().flatten() {
+        *replies.entry(*p).or_default() += 1;
+    }
+
+    envelopes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, env)| {
+            let (r, raw_depth) = root_of(i);
+            if r != root {
+                return None;
+            }
+            let (siblings, parent_display_depth, link) = match &parent[i] {
+                Some((p, link)) => (
+                    replies.get(p).copied().unwrap_or(0),
+                    Some(envelopes[*p].display_depth),
+                    link.clone(),
+                ),
+                None => {
+                    let wanted = env
+                        .in_reply_to
+                        .clone()
+                        .or_else(|| env.references.last().cloned());
+                    (
+                        0,
+                        None,
+                        match wanted {
+                            Some(wanted) => ThreadLink::Orphan { wanted },
+                            None => ThreadLink::Root,
+                        },
+                    )
+                }
+            };
+            Some(ThreadDebugRow {
+                id: env.id.clone(),
+                subject: env.subject.clone(),
+                message_id: env.message_id.clone(),
+                in_reply_to: env.in_reply_to.clone(),
+                references: env.references.len(),
+                raw_depth,
+                display_depth: env.display_depth,
+                siblings,
+                parent_display_depth,
+                link,
+                duplicate_id: env
+                    .message_id
+                    .as_deref()
+                    .is_some_and(|mid| id_count.get(mid).copied().unwrap_or(0) > 1),
+            })
+        })
+        .collect()
+}
+
 /// DFS traversal to collect messages in a thread
 fn collect_thread_dfs(
     idx: usize,
@@ -219,13 +376,16 @@ fn collect_thread_dfs(
 }
 
 /// Compute display depths with linear chain collapsing.
-/// A linear chain (single child at each level) stays at depth 1.
+/// A linear chain (single child at each level) stays at depth 1
+/// (unless `collapse_linear` is off, then every reply indents).
 /// Branching (multiple children) increases depth.
-/// Depth is capped at 3.
+/// Depth is capped at `max_depth`.
 fn compute_display_depths(
     messages: &[(usize, usize, bool)],
     children: &HashMap<usize, Vec<usize>>,
+    config: &ThreadingConfig,
 ) -> Vec<usize> {
+    let max_depth = config.max_depth.max(1);
     if messages.is_empty() {
         return vec![];
     }
@@ -255,9 +415,9 @@ fn compute_display_depths(
         // Check if parent has multiple children (branching)
         let num_children = children.get(&parent_idx).map(|c| c.len()).unwrap_or(0);
 
-        if num_children > 1 {
+        if num_children > 1 || !config.collapse_linear {
             // Branching - increment depth
-            display_depths[pos] = (parent_display_depth + 1).min(3);
+            display_depths[pos] = (parent_display_depth + 1).min(max_depth);
         } else {
             // Linear chain - stay at same depth (but at least 1 if we have a parent)
             display_depths[pos] = if parent_display_depth == 0 {
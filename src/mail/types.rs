@@ -14,7 +14,12 @@ pub struct Envelope {
     pub subject: Option<String>,
     pub from: Option<Address>,
     pub to: Option<Address>,
+    /// "YYYY-MM-DD HH:MM" as written by the sender, for display
     pub date: Option<String>,
+    /// Seconds since the epoch (UTC), for sorting and grouping; None if the
+    /// Date header couldn't be read
+    #[serde(default)]
+    pub timestamp: Option<i64>,
     #[serde(default)]
     pub has_attachment: bool,
     #[serde(default)]
@@ -29,8 +34,14 @@ pub struct Envelope {
     pub references: Vec<String>,
     #[serde(default)]
     pub is_sent: bool,
+    /// Auto-reply (vacation/out-of-office), per Auto-Submitted or X-Autoreply
+    #[serde(default)]
+    pub is_auto: bool,
     #[serde(default)]
     pub file_path: Option<String>,
+    /// For a delivery failure report: Message-ID of the message that bounced
+    #[serde(default)]
+    pub bounce_of: Option<String>,
 
     // Display fields (computed by threading algorithm, not cached)
     #[serde(skip)]
@@ -41,6 +52,12 @@ pub struct Envelope {
     pub is_last_in_thread: bool,
     #[serde(skip)]
     pub tree_prefix: String,
+    /// On thread roots: unread messages further down the thread
+    #[serde(skip)]
+    pub unread_replies: usize,
+    /// Sent message that a delivery failure report in the list points back to
+    #[serde(skip)]
+    pub bounced: bool,
 }
 
 /// Cached envelope with file modification time for invalidation
@@ -57,4 +74,14 @@ impl Envelope {
             None => "(unknown)".to_string(),
         }
     }
+
+    /// Not yet seen; auto-replies never count as unread
+    pub fn is_unread(&self) -> bool {
+        !self.is_auto && !self.flags.iter().any(|f| f == "Seen")
+    }
+
+    /// Answered (maildir R flag), by mailtui or another client
+    pub fn is_replied(&self) -> bool {
+        self.flags.iter().any(|f| f == "Replied")
+    }
 }
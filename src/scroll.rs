@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Local file holding preview scroll offsets: one `<message id>\t<offset>` per line
+fn scroll_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|p| p.join("mailtui/scroll"))
+}
+
+/// Saved preview scroll offsets by Message-ID
+pub fn load() -> HashMap<String, u16> {
+    let Some(content) = scroll_path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let (id, offset) = line.split_once('\t')?;
+            Some((id.to_string(), offset.parse().ok()?))
+        })
+        .collect()
+}
+
+pub fn save(positions: &HashMap<String, u16>) -> std::io::Result<()> {
+    let Some(path) = scroll_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut lines: Vec<String> = positions
+        .iter()
+        .filter(|(_, offset)| **offset > 0)
+        .map(|(id, offset)| format!("{}\t{}", id, offset))
+        .collect();
+    lines.sort();
+    std::fs::write(path, lines.join("\n"))
+}
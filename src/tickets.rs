@@ -0,0 +1,52 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+/// Ticket ID patterns (e.g. `PROJ-\d+`) and the URL each match opens
+#[derive(Default)]
+pub struct TicketLinks {
+    patterns: Vec<(Regex, String)>,
+}
+
+impl TicketLinks {
+    /// Compile the [links.tickets] table: pattern -> URL template with {id}.
+    /// Returns the links and any config errors (invalid patterns)
+    pub fn from_config(tickets: &HashMap<String, String>) -> (Self, Vec<String>) {
+        let mut patterns = Vec::new();
+        let mut errors = Vec::new();
+        for (pattern, url) in tickets {
+            // Whole words only, so PROJ-1 doesn't match inside XPROJ-12
+            match Regex::new(&format!(r"\b(?:{})\b", pattern)) {
+                Ok(re) => patterns.push((re, url.clone())),
+                Err(_) => errors.push(format!("Invalid ticket pattern '{}'", pattern)),
+            }
+        }
+        (Self { patterns }, errors)
+    }
+
+    /// Byte ranges of the ticket IDs in `text` with the URL each opens,
+    /// in order and without overlaps
+    pub fn find(&self, text: &str) -> Vec<(usize, usize, String)> {
+        let mut found: Vec<(usize, usize, String)> = Vec::new();
+        for (re, url) in &self.patterns {
+            for m in re.find_iter(text) {
+                if !found.iter().any(|(s, e, _)| m.start() < *e && *s < m.end()) {
+                    found.push((m.start(), m.end(), url.replace("{id}", m.as_str())));
+                }
+            }
+        }
+        found.sort_by_key(|(start, _, _)| *start);
+        found
+    }
+
+    /// Distinct ticket IDs in `text`, in order of appearance
+    pub fn ids(&self, text: &str) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for (start, end, _) in self.find(text) {
+            let id = &text[start..end];
+            if !ids.iter().any(|i| i == id) {
+                ids.push(id.to_string());
+            }
+        }
+        ids
+    }
+}
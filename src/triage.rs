@@ -0,0 +1,36 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Local file holding triage marks: one `<thread id>\t<mark>` per line
+fn triage_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|p| p.join("mailtui/triage"))
+}
+
+/// Triage marks by thread root Message-ID
+pub fn load() -> HashMap<String, char> {
+    let Some(content) = triage_path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let (id, mark) = line.split_once('\t')?;
+            Some((id.to_string(), mark.chars().next()?))
+        })
+        .collect()
+}
+
+pub fn save(marks: &HashMap<String, char>) -> std::io::Result<()> {
+    let Some(path) = triage_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut lines: Vec<String> = marks
+        .iter()
+        .map(|(id, mark)| format!("{}\t{}", id, mark))
+        .collect();
+    lines.sort();
+    std::fs::write(path, lines.join("\n"))
+}
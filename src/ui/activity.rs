@@ -0,0 +1,72 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::activity::Entry;
+use crate::config::ThemeConfig;
+use crate::i18n::tr;
+
+/// Most entries shown at once in the activity log
+const MAX_ROWS: usize = 15;
+
+/// Render the activity log, newest first, with the highlighted entry's
+/// Message-ID and detail (where the message went) below
+pub fn render_activity(
+    f: &mut Frame,
+    area: Rect,
+    entries: &[Entry],
+    selection: usize,
+    theme: &ThemeConfig,
+) {
+    let rows = entries.len().min(MAX_ROWS);
+    let modal = Modal::new(tr("modal.activity"), theme);
+    let modal_area = modal.centered_rect(90, rows as u16 + 5, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let muted = Style::default().fg(theme.fg_muted());
+    let mut lines = Vec::new();
+
+    // Keep the selection in view
+    let start = selection.saturating_sub(MAX_ROWS - 1);
+    for (i, entry) in entries.iter().enumerate().skip(start).take(MAX_ROWS) {
+        let style = if i == selection {
+            Style::default()
+                .fg(theme.fg())
+                .bg(theme.selected_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_subtle())
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}  ", entry.time), muted),
+            Span::styled(
+                format!("{:<18}  ", entry.action),
+                Style::default().fg(theme.primary()),
+            ),
+            Span::styled(entry.subject.clone(), style),
+        ]));
+    }
+
+    if let Some(entry) = entries.get(selection) {
+        lines.push(Line::from(""));
+        if !entry.message_id.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("<{}>", entry.message_id),
+                muted,
+            )));
+        }
+        if !entry.detail.is_empty() {
+            lines.push(Line::from(Span::styled(entry.detail.clone(), muted)));
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
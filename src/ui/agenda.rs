@@ -0,0 +1,60 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::config::ThemeConfig;
+use crate::i18n::tr;
+use crate::mail::CalendarEvent;
+
+/// Most events shown at once in the agenda
+const MAX_ROWS: usize = 15;
+
+/// Render the agenda: upcoming events from calendar invites, soonest first,
+/// with the highlighted event's location below
+pub fn render_agenda(
+    f: &mut Frame,
+    area: Rect,
+    events: &[CalendarEvent],
+    selection: usize,
+    theme: &ThemeConfig,
+) {
+    let rows = events.len().min(MAX_ROWS);
+    let modal = Modal::new(tr("modal.agenda"), theme);
+    let modal_area = modal.centered_rect(80, rows as u16 + 4, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let muted = Style::default().fg(theme.fg_muted());
+    let mut lines = Vec::new();
+
+    // Keep the selection in view
+    let start = selection.saturating_sub(MAX_ROWS - 1);
+    for (i, event) in events.iter().enumerate().skip(start).take(MAX_ROWS) {
+        let style = if i == selection {
+            Style::default()
+                .fg(theme.fg())
+                .bg(theme.selected_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_subtle())
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<20}  ", event.start), muted),
+            Span::styled(event.summary.clone(), style),
+        ]));
+    }
+
+    if let Some(location) = events.get(selection).and_then(|e| e.location.as_ref()) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(location.clone(), muted)));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
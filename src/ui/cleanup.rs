@@ -0,0 +1,84 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::app::CleanupView;
+use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+use crate::mail::format_size;
+
+/// Most messages shown at once in the cleanup view
+const MAX_ROWS: usize = 20;
+/// Columns given to the sender
+const FROM_WIDTH: usize = 20;
+
+/// Render the cleanup view: size, date, sender and subject per message,
+/// with marked rows flagged in the first column
+pub fn render_cleanup(f: &mut Frame, area: Rect, view: &CleanupView, theme: &ThemeConfig) {
+    let rows = view.candidates.len().min(MAX_ROWS);
+    let modal = Modal::new(tr("modal.cleanup"), theme);
+    let modal_area = modal.centered_rect(100, rows as u16 + 4, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let muted = Style::default().fg(theme.fg_muted());
+    let key_style = Style::default().fg(theme.primary());
+    let total: u64 = view.candidates.iter().map(|c| c.size).sum();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            trf(
+                "cleanup.summary",
+                &[
+                    ("count", &view.candidates.len()),
+                    ("size", &format_size(total)),
+                    ("marked", &view.marked.len()),
+                ],
+            ),
+            key_style,
+        )),
+        Line::from(""),
+    ];
+
+    // Keep the selection in view
+    let start = view.selection.saturating_sub(MAX_ROWS - 1);
+    for (i, c) in view
+        .candidates
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(MAX_ROWS)
+    {
+        let style = if i == view.selection {
+            Style::default()
+                .fg(theme.fg())
+                .bg(theme.selected_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_subtle())
+        };
+        let mark = if view.marked.contains(&c.id) {
+            "* "
+        } else {
+            "  "
+        };
+        let clip = if c.has_attachment { "+ " } else { "  " };
+        let day = c.date.split(' ').next().unwrap_or(&c.date);
+        let from: String = c.from.chars().take(FROM_WIDTH).collect();
+        lines.push(Line::from(vec![
+            Span::styled(mark, key_style),
+            Span::styled(format!("{:>9}  {}  ", format_size(c.size), day), muted),
+            Span::styled(format!("{:<width$}  ", from, width = FROM_WIDTH), muted),
+            Span::styled(clip, key_style),
+            Span::styled(c.subject.clone(), style),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
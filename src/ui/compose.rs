@@ -7,42 +7,55 @@ use ratatui::{
 };
 
 use super::Pane;
-use crate::app::ComposeState;
+use crate::app::{ComposeState, SendFailure};
 use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+use crate::mail::{format_size, guess_content_type};
 
 pub fn render_compose(
     f: &mut Frame,
     area: Rect,
     compose: &ComposeState,
     confirm_send: bool,
+    attachment_columns: &[String],
     theme: &ThemeConfig,
 ) {
+    let header_height = if compose.language.is_some() { 4 } else { 3 };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // To/Subject
-            Constraint::Min(5),    // Body preview
-            Constraint::Length(6), // Attachments
+            Constraint::Length(header_height), // To/Subject (+ language)
+            Constraint::Min(5),                // Body preview
+            Constraint::Length(6),             // Attachments
         ])
         .split(area);
 
     // Header (To/Subject)
-    let header_text = vec![
+    let mut header_text = vec![
         Line::from(vec![
-            Span::styled("To: ", Style::default().fg(theme.primary())),
+            Span::styled(tr("compose.to"), Style::default().fg(theme.primary())),
             Span::styled(&compose.to, Style::default().fg(theme.fg())),
         ]),
         Line::from(vec![
-            Span::styled("Subject: ", Style::default().fg(theme.primary())),
+            Span::styled(tr("compose.subject"), Style::default().fg(theme.primary())),
             Span::styled(&compose.subject, Style::default().fg(theme.fg())),
         ]),
     ];
-    let header_pane = Pane::new("Compose", true, theme);
+    if let Some(ref language) = compose.language {
+        header_text.push(Line::from(vec![
+            Span::styled(tr("compose.language"), Style::default().fg(theme.primary())),
+            Span::styled(
+                format!("{} ({})", language.name, language.tag),
+                Style::default().fg(theme.fg_muted()),
+            ),
+        ]));
+    }
+    let header_pane = Pane::new(tr("title.compose"), true, theme);
     let header = Paragraph::new(header_text).block(header_pane.block());
     f.render_widget(header, chunks[0]);
 
     // Body preview
-    let body_pane = Pane::new("Body", false, theme);
+    let body_pane = Pane::new(tr("title.body"), false, theme);
     let body = Paragraph::new(compose.body.as_str())
         .style(Style::default().fg(theme.fg()))
         .block(body_pane.block())
@@ -52,7 +65,7 @@ pub fn render_compose(
     // Attachments
     let attachment_items: Vec<ListItem> = if compose.attachments.is_empty() {
         vec![ListItem::new(Line::from(Span::styled(
-            "(no attachments)",
+            tr("compose.no_attachments"),
             Style::default().fg(theme.fg_muted()),
         )))]
     } else {
@@ -61,10 +74,11 @@ pub fn render_compose(
             .iter()
             .enumerate()
             .map(|(i, path)| {
-                let filename = std::path::Path::new(path)
+                let path = std::path::Path::new(path);
+                let filename = path
                     .file_name()
                     .and_then(|n| n.to_str())
-                    .unwrap_or(path);
+                    .unwrap_or_default();
                 let style = if i == compose.attachment_selection {
                     Style::default()
                         .fg(theme.attachment())
@@ -72,19 +86,44 @@ pub fn render_compose(
                 } else {
                     Style::default().fg(theme.fg())
                 };
-                ListItem::new(Line::from(Span::styled(filename.to_string(), style)))
+                let mut spans = vec![Span::styled(filename.to_string(), style)];
+                // Moved or deleted since it was attached: sending would fail
+                let Some(size) = std::fs::metadata(path)
+                    .ok()
+                    .filter(|m| m.is_file())
+                    .map(|m| m.len())
+                else {
+                    spans.push(Span::styled(
+                        format!("  {}", tr("compose.attachment_missing")),
+                        Style::default().fg(theme.error()),
+                    ));
+                    return ListItem::new(Line::from(spans));
+                };
+                let muted = Style::default().fg(theme.fg_muted());
+                for column in attachment_columns {
+                    let value = match column.as_str() {
+                        "size" => format_size(size),
+                        "type" => guess_content_type(path).to_string(),
+                        _ => continue,
+                    };
+                    spans.push(Span::styled(format!("  {}", value), muted));
+                }
+                ListItem::new(Line::from(spans))
             })
             .collect()
     };
 
-    let attach_title = format!("Attachments ({})", compose.attachments.len());
+    let attach_title = trf(
+        "title.attachments",
+        &[("count", &compose.attachments.len())],
+    );
     let attach_pane = Pane::new(&attach_title, false, theme);
     let attachments = List::new(attachment_items).block(attach_pane.block());
     f.render_widget(attachments, chunks[2]);
 
     // Render confirmation modal if needed
     if confirm_send {
-        let modal = super::Modal::new(" Confirm ", theme);
+        let modal = super::Modal::new(tr("modal.confirm"), theme);
         let modal_area = modal.centered_rect(40, 5, area);
 
         // Clear the area behind the modal
@@ -93,13 +132,13 @@ pub fn render_compose(
         let modal_text = vec![
             Line::from(""),
             Line::from(Span::styled(
-                "Send this email?",
+                tr("modal.send_question"),
                 Style::default()
                     .fg(theme.warning())
                     .add_modifier(Modifier::BOLD),
             )),
             Line::from(Span::styled(
-                "Press 's' to confirm, any key to cancel",
+                tr("modal.send_hint"),
                 Style::default().fg(theme.fg_muted()),
             )),
         ];
@@ -112,6 +151,56 @@ pub fn render_compose(
     }
 }
 
+/// Render what the send command reported when it failed: the command, its
+/// exit code and everything it printed (msmtp's errors say what went wrong)
+pub fn render_send_failure(f: &mut Frame, area: Rect, failure: &SendFailure, theme: &ThemeConfig) {
+    let muted = Style::default().fg(theme.fg_muted());
+    let code = match failure.code {
+        Some(code) => trf("send.exit_code", &[("code", &code)]),
+        None => tr("send.no_exit_code").to_string(),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("modal.send_failed_question"),
+            Style::default()
+                .fg(theme.error())
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("{}  ({})", failure.command, code),
+            muted,
+        )),
+        Line::from(""),
+    ];
+    let output = [failure.stderr.as_str(), failure.stdout.as_str()];
+    if output.iter().all(|s| s.is_empty()) {
+        lines.push(Line::from(Span::styled(tr("send.no_output"), muted)));
+    }
+    for line in output
+        .iter()
+        .filter(|s| !s.is_empty())
+        .flat_map(|s| s.lines())
+    {
+        lines.push(Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(theme.fg()),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        tr("modal.send_failed_hint"),
+        muted,
+    )));
+
+    let modal = super::Modal::new(tr("modal.send_failed"), theme);
+    let modal_area = modal.centered_rect(90, lines.len() as u16 + 2, area);
+    f.render_widget(Clear, modal_area);
+    let content = Paragraph::new(lines)
+        .block(modal.block())
+        .wrap(Wrap { trim: false });
+    f.render_widget(content, modal_area);
+}
+
 pub fn render_compose_help(f: &mut Frame, area: Rect, theme: &ThemeConfig) {
     let key_style = Style::default().fg(theme.primary());
     let text_style = Style::default().fg(theme.fg_muted());
@@ -119,17 +208,19 @@ pub fn render_compose_help(f: &mut Frame, area: Rect, theme: &ThemeConfig) {
 
     let help = Line::from(vec![
         Span::styled("e", key_style),
-        Span::styled(" edit  ", text_style),
+        Span::styled(format!(" {}  ", tr("help.edit")), text_style),
         Span::styled("a", key_style),
-        Span::styled(" attach  ", text_style),
+        Span::styled(format!(" {}  ", tr("help.attach")), text_style),
         Span::styled("d", key_style),
-        Span::styled(" remove  ", text_style),
+        Span::styled(format!(" {}  ", tr("help.remove")), text_style),
         Span::styled("j/k", key_style),
-        Span::styled(" select  ", text_style),
+        Span::styled(format!(" {}  ", tr("help.select")), text_style),
+        Span::styled("J/K", key_style),
+        Span::styled(format!(" {}  ", tr("help.reorder")), text_style),
         Span::styled("s", key_style),
-        Span::styled(" send  ", text_style),
+        Span::styled(format!(" {}  ", tr("help.send")), text_style),
         Span::styled("q", key_style),
-        Span::styled(" cancel", text_style),
+        Span::styled(format!(" {}", tr("help.cancel")), text_style),
     ]);
 
     let paragraph = Paragraph::new(help).style(bg_style);
@@ -0,0 +1,111 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+use crate::config::ThemeConfig;
+use crate::contacts::ContactEntry;
+use crate::i18n::{tr, trf};
+
+/// Width of the contact panel in columns
+pub const CONTACT_PANEL_WIDTH: u16 = 36;
+
+/// Everything known about the selected message's sender
+pub struct ContactSummary {
+    pub name: String,
+    pub addr: String,
+    pub entry: Option<ContactEntry>,
+    pub message_count: usize,
+    pub first_seen: Option<String>,
+    /// Most recent messages from this sender: (date, subject)
+    pub recent: Vec<(String, String)>,
+}
+
+pub fn render_contact_panel(
+    f: &mut Frame,
+    area: Rect,
+    summary: Option<&ContactSummary>,
+    theme: &ThemeConfig,
+) {
+    let block = super::Pane::new(tr("title.contact"), false, theme).block();
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(summary) = summary else {
+        return;
+    };
+
+    let label_style = Style::default().fg(theme.fg_muted());
+    let value_style = Style::default().fg(theme.fg());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            summary.name.clone(),
+            Style::default().fg(theme.fg()).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            summary.addr.clone(),
+            Style::default().fg(theme.secondary()),
+        )),
+        Line::from(""),
+    ];
+
+    match &summary.entry {
+        Some(entry) => {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{} ", tr("contact.address_book")), label_style),
+                Span::styled(entry.name.clone(), value_style),
+            ]));
+            if !entry.info.is_empty() {
+                lines.push(Line::from(Span::styled(entry.info.clone(), label_style)));
+            }
+        }
+        None => lines.push(Line::from(Span::styled(
+            tr("contact.not_in_address_book"),
+            label_style,
+        ))),
+    }
+
+    lines.push(Line::from(vec![
+        Span::styled(format!("{} ", tr("contact.first_seen")), label_style),
+        Span::styled(
+            summary
+                .first_seen
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+            value_style,
+        ),
+    ]));
+    lines.push(Line::from(Span::styled(
+        trf(
+            "contact.message_count",
+            &[("count", &summary.message_count)],
+        ),
+        label_style,
+    )));
+    lines.push(Line::from(""));
+
+    lines.push(Line::from(Span::styled(
+        tr("contact.recent"),
+        Style::default().fg(theme.primary()),
+    )));
+    for (date, subject) in &summary.recent {
+        // Date only; the time adds little in a narrow panel
+        let day = date.split(' ').next().unwrap_or(date);
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", day), label_style),
+            Span::styled(subject.clone(), value_style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("F", Style::default().fg(theme.primary())),
+        Span::styled(format!(" {}", tr("contact.filter_hint")), label_style),
+    ]));
+
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }), inner);
+}
@@ -0,0 +1,64 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::config::ThemeConfig;
+use crate::diagnostics::{ToolState, ToolStatus};
+use crate::i18n::tr;
+
+/// Render the external tool check: each tool's state, the feature it backs
+/// and its version, with a note on what is degraded below
+pub fn render_diagnostics(f: &mut Frame, area: Rect, tools: &[ToolStatus], theme: &ThemeConfig) {
+    let modal = Modal::new(tr("modal.diagnostics"), theme);
+    let modal_area = modal.centered_rect(90, tools.len() as u16 + 4, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let muted = Style::default().fg(theme.fg_muted());
+    let mut lines = Vec::new();
+
+    for tool in tools {
+        let (label, color) = match tool.state {
+            ToolState::Active => (tr("diag.active"), theme.success()),
+            ToolState::Unused => (tr("diag.unused"), theme.fg_muted()),
+            ToolState::Missing => (tr("diag.missing"), theme.fg_muted()),
+            ToolState::Broken => (tr("diag.broken"), theme.error()),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<9}", tool.name),
+                Style::default().fg(theme.fg()).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(format!("{:<15}", label), Style::default().fg(color)),
+            Span::styled(
+                format!("{:<28}", tr(tool.feature)),
+                Style::default().fg(theme.fg_subtle()),
+            ),
+            Span::styled(tool.version.clone().unwrap_or_default(), muted),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    let broken: Vec<&str> = tools
+        .iter()
+        .filter(|t| t.state == ToolState::Broken)
+        .map(|t| tr(t.feature))
+        .collect();
+    lines.push(if broken.is_empty() {
+        Line::from(Span::styled(tr("diag.all_ok"), muted))
+    } else {
+        Line::from(vec![
+            Span::styled(tr("diag.degraded"), Style::default().fg(theme.error())),
+            Span::styled(broken.join(", "), Style::default().fg(theme.fg())),
+        ])
+    });
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
@@ -0,0 +1,82 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+use crate::mail::{format_size, DuplicateGroup};
+
+/// Most groups shown at once in the duplicate finder
+const MAX_ROWS: usize = 15;
+
+/// Render the duplicate finder: one row per message with extra copies,
+/// and the folders holding the highlighted one
+pub fn render_duplicates(
+    f: &mut Frame,
+    area: Rect,
+    groups: &[DuplicateGroup],
+    selection: usize,
+    theme: &ThemeConfig,
+) {
+    let rows = groups.len().min(MAX_ROWS);
+    let modal = Modal::new(tr("modal.duplicates"), theme);
+    let modal_area = modal.centered_rect(90, rows as u16 + 6, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let muted = Style::default().fg(theme.fg_muted());
+    let total: u64 = groups.iter().map(|g| g.reclaimable()).sum();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            trf(
+                "duplicates.summary",
+                &[("count", &groups.len()), ("size", &format_size(total))],
+            ),
+            Style::default().fg(theme.primary()),
+        )),
+        Line::from(""),
+    ];
+
+    // Keep the selection in view
+    let start = selection.saturating_sub(MAX_ROWS - 1);
+    for (i, group) in groups.iter().enumerate().skip(start).take(MAX_ROWS) {
+        let style = if i == selection {
+            Style::default()
+                .fg(theme.fg())
+                .bg(theme.selected_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_subtle())
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{}x {:>9}  ", group.copies.len(), format_size(group.size)),
+                muted,
+            ),
+            Span::styled(group.subject.clone(), style),
+        ]));
+    }
+
+    if let Some(group) = groups.get(selection) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            trf(
+                "duplicates.folders",
+                &[
+                    ("keep", &group.folders[0]),
+                    ("others", &group.folders[1..].join(", ")),
+                ],
+            ),
+            muted,
+        )));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
@@ -1,3 +1,4 @@
+use chrono::{Datelike, Local, NaiveDate, TimeZone};
 use ratatui::{
     layout::Rect,
     style::{Modifier, Style},
@@ -8,19 +9,24 @@ use ratatui::{
 
 use super::Pane;
 use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
 use crate::mail::Envelope;
 
 pub fn render_envelopes(
     f: &mut Frame,
     area: Rect,
     envelopes: &[&Envelope],
+    triage_marks: &[Option<char>],
+    bulk_selected: &[bool],
     state: &mut ListState,
     title: &str,
     focused: bool,
     theme: &ThemeConfig,
     date_width: usize,
     from_width: usize,
-) {
+    glyph_markers: bool,
+    day_separators: bool,
+) -> Vec<Option<usize>> {
     // Available width: area minus borders (2) minus highlight symbol (2)
     let avail_width = area.width.saturating_sub(4) as usize;
     // Account for tree prefix (max ~9 chars for "│  └─ ") and sent indicator (~7 chars for " ┤sent├")
@@ -28,16 +34,53 @@ pub fn render_envelopes(
     let sent_indicator_reserve = 8;
     let content_width = avail_width.saturating_sub(tree_prefix_reserve + sent_indicator_reserve);
     let from_w = from_width.min(content_width.saturating_sub(date_width + 4) / 3);
-    let subject_width = content_width.saturating_sub(date_width + from_w + 4);
+    // One more column for the replied marker
+    let subject_width = content_width.saturating_sub(date_width + from_w + 5);
 
-    let items: Vec<ListItem> = envelopes
-        .iter()
-        .map(|e| {
-            let is_unread = !e.flags.contains(&"Seen".to_string());
+    // Triage column only appears once something has been marked
+    let show_triage = triage_marks.iter().any(|m| m.is_some());
+
+    let today = Local::now().date_naive();
+    let separator_style = Style::default().fg(theme.fg_subtle());
+
+    // Display rows: Some(envelope row) or None for a day divider
+    let mut rows: Vec<Option<usize>> = Vec::with_capacity(envelopes.len());
+    let mut items: Vec<ListItem> = Vec::with_capacity(envelopes.len());
+    let mut current_group: Option<String> = None;
+
+    for (i, e) in envelopes.iter().enumerate() {
+        // Replies stay with their thread; only roots can start a new day
+        if day_separators && e.thread_depth == 0 {
+            let group = e.timestamp.and_then(|t| day_group(t, today));
+            if group.is_some() && group != current_group {
+                let label = format!("── {} ", group.as_deref().unwrap_or(""));
+                let fill = avail_width.saturating_sub(label.chars().count());
+                rows.push(None);
+                items.push(ListItem::new(Line::from(Span::styled(
+                    format!("{}{}", label, "─".repeat(fill)),
+                    separator_style,
+                ))));
+                current_group = group;
+            }
+        }
+        rows.push(Some(i));
+        items.push({
+            // Auto-replies never show as unread
+            let is_unread = !e.flags.contains(&"Seen".to_string()) && !e.is_auto;
             let has_attach = e.has_attachment;
             let has_images = e.has_inline_images;
 
-            let unread_marker = if is_unread { "*" } else { " " };
+            // A read thread root with unread replies gets a lighter marker, so
+            // unread messages deep in a long thread aren't missed
+            let has_unread_replies = !is_unread && e.unread_replies > 0;
+            // Glyph mode uses a filled dot that reads clearly without color
+            let unread_marker = match (is_unread, has_unread_replies, glyph_markers) {
+                (true, _, true) => "●",
+                (true, _, false) => "*",
+                (false, true, true) => "○",
+                (false, true, false) => "+",
+                (false, false, _) => " ",
+            };
             let attach_marker = if has_attach {
                 "@"
             } else if has_images {
@@ -46,14 +89,26 @@ pub fn render_envelopes(
                 " "
             };
             let from = e.from_display();
-            let subject = e.subject.as_deref().unwrap_or("(no subject)");
+            let subject = e.subject.as_deref().unwrap_or(tr("list.no_subject"));
             let date = format_date(e.date.as_deref().unwrap_or(""));
 
             // Build styled spans
             let mut spans = vec![];
 
+            if show_triage {
+                match triage_marks.get(i).copied().flatten() {
+                    Some(mark) => spans.push(Span::styled(
+                        mark.to_string(),
+                        Style::default()
+                            .fg(triage_color(mark, theme))
+                            .add_modifier(Modifier::BOLD),
+                    )),
+                    None => spans.push(Span::raw(" ")),
+                }
+            }
+
             // Unread marker with color
-            if is_unread {
+            if is_unread || has_unread_replies {
                 spans.push(Span::styled(
                     unread_marker,
                     Style::default().fg(theme.unread()),
@@ -77,6 +132,13 @@ pub fn render_envelopes(
                 spans.push(Span::raw(attach_marker));
             }
 
+            // Replied marker
+            if e.is_replied() {
+                spans.push(Span::styled("↩", Style::default().fg(theme.secondary())));
+            } else {
+                spans.push(Span::raw(" "));
+            }
+
             // Tree prefix for threading (indentation)
             if !e.tree_prefix.is_empty() {
                 spans.push(Span::styled(
@@ -85,6 +147,12 @@ pub fn render_envelopes(
                 ));
             }
 
+            // Unread-replies count after the subject, which shrinks to fit it
+            let unread_replies = (e.unread_replies > 0)
+                .then(|| trf("list.unread_replies", &[("count", &e.unread_replies)]));
+            let subject_width = subject_width
+                .saturating_sub(unread_replies.as_ref().map_or(0, |s| s.chars().count()));
+
             // Main content: date, from, subject
             let main_content = format!(
                 " {:dw$} {:fw$} {}",
@@ -98,7 +166,9 @@ pub fn render_envelopes(
             // Thread replies (depth > 0) get more muted colors
             let is_thread_reply = e.thread_depth > 0;
 
-            let text_color = if is_unread {
+            let text_color = if e.is_auto {
+                theme.fg_subtle() // Auto-reply: always dim
+            } else if is_unread {
                 if is_thread_reply {
                     theme.fg_muted() // Unread reply: muted but not as dim
                 } else {
@@ -110,7 +180,8 @@ pub fn render_envelopes(
                 theme.fg_muted() // Read root: normal muted
             };
 
-            let style = if is_unread && !is_thread_reply {
+            // In glyph mode unread replies are bold too, not just brighter
+            let style = if is_unread && (!is_thread_reply || glyph_markers) {
                 Style::default().fg(text_color).add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(text_color)
@@ -118,14 +189,44 @@ pub fn render_envelopes(
 
             spans.push(Span::styled(main_content, style));
 
+            if let Some(unread_replies) = unread_replies {
+                spans.push(Span::styled(
+                    unread_replies,
+                    Style::default().fg(theme.unread()),
+                ));
+            }
+
             // Sent indicator with box-breaking style
             if e.is_sent {
                 spans.push(Span::styled(" ┤sent├", Style::default().fg(theme.sent())));
             }
+            if e.bounced {
+                spans.push(Span::styled(
+                    format!(" ┤{}├", tr("list.bounced")),
+                    Style::default().fg(theme.error()),
+                ));
+            }
 
-            ListItem::new(Line::from(spans))
-        })
-        .collect();
+            // Bulk-selected rows get a tinted background
+            if bulk_selected.get(i).copied().unwrap_or(false) {
+                ListItem::new(Line::from(spans)).style(Style::default().bg(theme.bg_element()))
+            } else {
+                ListItem::new(Line::from(spans))
+            }
+        });
+    }
+
+    // Map the envelope-based state onto display rows and back, so callers
+    // keep indexing envelopes while dividers stay unselectable
+    let display_row = |row: usize| rows.iter().position(|r| *r == Some(row));
+    let mut display_offset = display_row(state.offset()).unwrap_or(0);
+    if display_offset > 0 && rows[display_offset - 1].is_none() {
+        // Keep the divider above the top row in view
+        display_offset -= 1;
+    }
+    let mut display_state = ListState::default()
+        .with_offset(display_offset)
+        .with_selected(state.selected().and_then(display_row));
 
     let pane = Pane::new(title, focused, theme);
 
@@ -139,7 +240,60 @@ pub fn render_envelopes(
         .highlight_symbol("> ")
         .scroll_padding(0);
 
-    f.render_stateful_widget(list, area, state);
+    f.render_stateful_widget(list, area, &mut display_state);
+
+    let top = display_state.offset().min(rows.len());
+    *state.offset_mut() = rows[top..]
+        .iter()
+        .find_map(|r| *r)
+        .unwrap_or(state.offset());
+    let height = area.height.saturating_sub(2) as usize;
+    rows[top..].iter().take(height).copied().collect()
+}
+
+/// Divider label for a message's timestamp relative to `today`, both as
+/// local calendar days (a message at 23:30 local time belongs to that day
+/// whatever the UTC date)
+fn day_group(timestamp: i64, today: NaiveDate) -> Option<String> {
+    let date = Local.timestamp_opt(timestamp, 0).earliest()?.date_naive();
+    let age = (today - date).num_days();
+    let label = match age {
+        ..=0 => tr("list.today").to_string(),
+        1 => tr("list.yesterday").to_string(),
+        2..=6 => tr("list.this_week").to_string(),
+        7..=13 => tr("list.last_week").to_string(),
+        _ => format!("{} {}", month_name(date.month() as u8)?, date.year()),
+    };
+    Some(label)
+}
+
+/// Short month name for a month number (1 -> "Jan")
+fn month_name(month: u8) -> Option<&'static str> {
+    Some(match month {
+        1 => "Jan",
+        2 => "Feb",
+        3 => "Mar",
+        4 => "Apr",
+        5 => "May",
+        6 => "Jun",
+        7 => "Jul",
+        8 => "Aug",
+        9 => "Sep",
+        10 => "Oct",
+        11 => "Nov",
+        12 => "Dec",
+        _ => return None,
+    })
+}
+
+/// Color for a triage mark: 1 is most urgent
+fn triage_color(mark: char, theme: &ThemeConfig) -> ratatui::style::Color {
+    match mark {
+        '1' => theme.error(),
+        '2' => theme.warning(),
+        '3' => theme.info(),
+        _ => theme.secondary(),
+    }
 }
 
 fn truncate(s: &str, max: usize) -> String {
@@ -177,20 +331,8 @@ fn format_date(date: &str) -> String {
         return date.to_string();
     }
 
-    let month = match date_parts[1] {
-        "01" => "Jan",
-        "02" => "Feb",
-        "03" => "Mar",
-        "04" => "Apr",
-        "05" => "May",
-        "06" => "Jun",
-        "07" => "Jul",
-        "08" => "Aug",
-        "09" => "Sep",
-        "10" => "Oct",
-        "11" => "Nov",
-        "12" => "Dec",
-        _ => return date.to_string(),
+    let Some(month) = date_parts[1].parse().ok().and_then(month_name) else {
+        return date.to_string();
     };
     let day = date_parts[2];
 
@@ -206,3 +348,31 @@ fn format_date(date: &str) -> String {
 
     format!("{} {} {}", month, day, time_short)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_group_uses_local_days() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let at = |day: u32, hour: u32, minute: u32| {
+            let time = NaiveDate::from_ymd_opt(2024, 3, day)
+                .unwrap()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap();
+            Local
+                .from_local_datetime(&time)
+                .earliest()
+                .unwrap()
+                .timestamp()
+        };
+        // Either side of local midnight, whatever the offset from UTC
+        assert_eq!(day_group(at(15, 0, 5), today).unwrap(), tr("list.today"));
+        assert_eq!(
+            day_group(at(14, 23, 55), today).unwrap(),
+            tr("list.yesterday")
+        );
+        assert_eq!(day_group(at(1, 12, 0), today).unwrap(), "Mar 2024");
+    }
+}
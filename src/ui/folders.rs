@@ -0,0 +1,88 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::app::MovePrompt;
+use crate::config::ThemeConfig;
+use crate::i18n::tr;
+use crate::mail::{format_size, FolderSize};
+
+/// Most folder rows shown in the move prompt
+const MAX_ROWS: usize = 12;
+
+/// Render the move-to-folder picker over the list, with each folder's size
+/// once it has been counted
+pub fn render_move_prompt(
+    f: &mut Frame,
+    area: Rect,
+    prompt: &MovePrompt,
+    sizes: &[FolderSize],
+    theme: &ThemeConfig,
+) {
+    let matches = prompt.matches();
+    let rows = matches.len().clamp(1, MAX_ROWS);
+
+    let modal = Modal::new(tr("modal.move"), theme);
+    let modal_area = modal.centered_rect(50, rows as u16 + 4, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let key_style = Style::default().fg(theme.primary());
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("> ", key_style),
+            Span::styled(prompt.query.clone(), Style::default().fg(theme.fg())),
+            Span::styled("_", key_style),
+        ]),
+        Line::from(""),
+    ];
+
+    // Keep the selection in view
+    let start = prompt.selection.saturating_sub(MAX_ROWS - 1);
+    let recent_count = if prompt.query.is_empty() {
+        prompt.recent.len()
+    } else {
+        0
+    };
+    for (i, folder) in matches.iter().enumerate().skip(start).take(MAX_ROWS) {
+        let number = if i < recent_count {
+            format!("{} ", i + 1)
+        } else {
+            "  ".to_string()
+        };
+        let style = if i == prompt.selection {
+            Style::default()
+                .fg(theme.fg())
+                .bg(theme.selected_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_subtle())
+        };
+        let mut spans = vec![
+            Span::styled(number, key_style),
+            Span::styled(folder.to_string(), style),
+        ];
+        if let Some(size) = sizes.iter().find(|s| s.folder == *folder) {
+            spans.push(Span::styled(
+                format!("  {}", format_size(size.bytes)),
+                Style::default().fg(theme.fg_muted()),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    if matches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            tr("move.new_folder"),
+            Style::default().fg(theme.fg_muted()),
+        )));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
@@ -8,6 +8,69 @@ use ratatui::{
 
 use crate::app::View;
 use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+use crate::mail::{format_size, MaildirProblem};
+
+/// Quota use from which the mailbox size turns to the warning color
+const QUOTA_WARN_PERCENT: u64 = 90;
+
+/// Frames of the background scan spinner, advanced every 100ms
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Help bar for `mailtui view`
+pub fn render_viewer_help(f: &mut Frame, area: Rect, theme: &ThemeConfig) {
+    let key_style = Style::default().fg(theme.primary());
+    let text_style = Style::default().fg(theme.fg_subtle());
+    let line = Line::from(vec![
+        Span::styled("j/k", key_style),
+        Span::styled(format!(" {}  ", tr("help.scroll")), text_style),
+        Span::styled("Space", key_style),
+        Span::styled(format!(" {}  ", tr("help.page")), text_style),
+        Span::styled("q", key_style),
+        Span::styled(format!(" {}", tr("help.quit")), text_style),
+    ]);
+    let paragraph = Paragraph::new(line).style(Style::default().bg(theme.bg_panel()));
+    f.render_widget(paragraph, area);
+}
+
+/// Warning line shown while the account's maildir is missing or has no
+/// folders, naming the path as it was resolved
+pub fn render_maildir_warning(
+    f: &mut Frame,
+    area: Rect,
+    problem: MaildirProblem,
+    path: &str,
+    theme: &ThemeConfig,
+) {
+    let key = match problem {
+        MaildirProblem::Missing => "warn.maildir_missing",
+        MaildirProblem::NoFolders => "warn.maildir_empty",
+    };
+    let line = Line::from(Span::styled(
+        trf(key, &[("path", &path)]),
+        Style::default().fg(theme.bg()).bg(theme.warning()),
+    ));
+    let paragraph = Paragraph::new(line).style(Style::default().bg(theme.warning()));
+    f.render_widget(paragraph, area);
+}
+
+/// List view state shown in the help bar
+pub struct ListIndicators<'a> {
+    /// Junk review mode is on (the list shows the spam folder)
+    pub reviewing_junk: bool,
+    /// Messages waiting in the spam folder
+    pub junk_count: usize,
+    /// Register a keyboard macro is being recorded into
+    pub recording: Option<char>,
+    /// Inbox unread count per account, from the background check
+    pub account_unread: &'a [(String, usize)],
+    pub current_account: &'a str,
+    /// Space the current account's mail takes, and its quota in bytes
+    pub mailbox_size: Option<u64>,
+    pub quota: Option<u64>,
+    /// Percentage of files read by a background rescan, while one runs
+    pub scanning: Option<usize>,
+}
 
 pub fn render_help(
     f: &mut Frame,
@@ -15,6 +78,7 @@ pub fn render_help(
     view: View,
     status: Option<&str>,
     search_query: Option<&str>,
+    indicators: ListIndicators<'_>,
     theme: &ThemeConfig,
 ) {
     let key_style = Style::default().fg(theme.primary());
@@ -25,6 +89,16 @@ pub fn render_help(
     let muted_style = Style::default().fg(theme.fg_muted());
 
     let help_text = match view {
+        View::List if indicators.reviewing_junk => vec![
+            Span::styled("N", key_style),
+            Span::styled(format!(" {}  ", tr("help.not_spam")), text_style),
+            Span::styled("x", key_style),
+            Span::styled(format!(" {}  ", tr("help.delete_forever")), text_style),
+            Span::styled("j/k", key_style),
+            Span::styled(format!(" {}  ", tr("help.nav")), text_style),
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}", tr("help.close")), text_style),
+        ],
         View::Search => vec![
             Span::styled("/", key_style),
             Span::raw(" "),
@@ -32,35 +106,35 @@ pub fn render_help(
             Span::styled("_", cursor_style),
             Span::styled("  ", text_style),
             Span::styled("Enter", key_style),
-            Span::styled(" confirm  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.confirm")), text_style),
             Span::styled("Esc", key_style),
-            Span::styled(" cancel", text_style),
+            Span::styled(format!(" {}", tr("help.cancel")), text_style),
         ],
         View::List => vec![
             Span::styled("h/l", key_style),
-            Span::styled(" pane  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.pane")), text_style),
             Span::styled("j/k", key_style),
-            Span::styled(" nav  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.nav")), text_style),
             Span::styled("Tab", key_style),
-            Span::styled(" account  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.account")), text_style),
             Span::styled("u", key_style),
             Span::styled("/", text_style),
             Span::styled("U", key_style),
-            Span::styled(" read  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.read")), text_style),
             Span::styled("/", key_style),
-            Span::styled(" search  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.search")), text_style),
             Span::styled("?", key_style),
-            Span::styled(" deep  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.deep")), text_style),
             Span::styled("r", key_style),
-            Span::styled(" reply  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.reply")), text_style),
             Span::styled("c", key_style),
-            Span::styled(" compose  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.compose")), text_style),
             Span::styled("S", key_style),
-            Span::styled(" config  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.config")), text_style),
             Span::styled("R", key_style),
-            Span::styled(" reload disk  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.reload")), text_style),
             Span::styled("q", key_style),
-            Span::styled(" quit", text_style),
+            Span::styled(format!(" {}", tr("help.quit")), text_style),
         ],
 
         View::DeepSearch => vec![
@@ -70,16 +144,179 @@ pub fn render_help(
             Span::styled("_", deep_key_style),
             Span::styled("  ", text_style),
             Span::styled("Enter", key_style),
-            Span::styled(" search  ", text_style),
+            Span::styled(format!(" {}  ", tr("help.search")), text_style),
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}  ", tr("help.cancel")), text_style),
+            Span::styled(tr("help.substring_match"), muted_style),
+        ],
+        View::Move => vec![
+            Span::styled("Enter", key_style),
+            Span::styled(format!(" {}  ", tr("help.move")), text_style),
+            Span::styled("Tab", key_style),
+            Span::styled("/", text_style),
+            Span::styled("↑↓", key_style),
+            Span::styled(format!(" {}  ", tr("help.select")), text_style),
+            Span::styled("Alt+1-5", key_style),
+            Span::styled(format!(" {}  ", tr("help.recent")), text_style),
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}", tr("help.cancel")), text_style),
+        ],
+        View::Templates => vec![
+            Span::styled("Enter", key_style),
+            Span::styled("/", text_style),
+            Span::styled("1-9", key_style),
+            Span::styled(format!(" {}  ", tr("help.reply")), text_style),
+            Span::styled("j/k", key_style),
+            Span::styled(format!(" {}  ", tr("help.select")), text_style),
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}", tr("help.cancel")), text_style),
+        ],
+        View::Cleanup => vec![
+            Span::styled("Space", key_style),
+            Span::styled(format!(" {}  ", tr("help.mark")), text_style),
+            Span::styled("d", key_style),
+            Span::styled(format!(" {}  ", tr("help.delete")), text_style),
+            Span::styled("s", key_style),
+            Span::styled(format!(" {}  ", tr("help.strip")), text_style),
+            Span::styled("j/k", key_style),
+            Span::styled(format!(" {}  ", tr("help.select")), text_style),
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}", tr("help.close")), text_style),
+        ],
+        View::Agenda | View::Activity | View::ThreadDebug => vec![
+            Span::styled("Enter", key_style),
+            Span::styled(format!(" {}  ", tr("help.open_message")), text_style),
+            Span::styled("j/k", key_style),
+            Span::styled(format!(" {}  ", tr("help.select")), text_style),
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}", tr("help.close")), text_style),
+        ],
+        View::OpenWith => vec![
+            Span::styled("Enter", key_style),
+            Span::styled(format!(" {}  ", tr("help.open")), text_style),
+            Span::styled("j/k", key_style),
+            Span::styled(format!(" {}  ", tr("help.select")), text_style),
             Span::styled("Esc", key_style),
-            Span::styled(" cancel  ", text_style),
-            Span::styled("(substring match)", muted_style),
+            Span::styled(format!(" {}", tr("help.back")), text_style),
+        ],
+        View::Diagnostics => vec![
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}", tr("help.close")), text_style),
+        ],
+        View::Duplicates => vec![
+            Span::styled("d", key_style),
+            Span::styled(format!(" {}  ", tr("help.delete_copies")), text_style),
+            Span::styled("D", key_style),
+            Span::styled(format!(" {}  ", tr("help.delete_all_copies")), text_style),
+            Span::styled("j/k", key_style),
+            Span::styled(format!(" {}  ", tr("help.select")), text_style),
+            Span::styled("Esc", key_style),
+            Span::styled(format!(" {}", tr("help.close")), text_style),
+        ],
+        View::LinkHints => vec![
+            Span::styled(tr("help.type_hint"), text_style),
+            Span::styled("  Esc", key_style),
+            Span::styled(format!(" {}", tr("help.cancel")), text_style),
         ],
         View::Compose => vec![], // Compose has its own help bar
     };
 
     let mut line = Line::from(help_text);
 
+    // Leading, so a narrow terminal does not cut it off
+    if let Some(percent) = indicators.scanning {
+        let tick = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() / 100);
+        let frame = SPINNER[tick as usize % SPINNER.len()];
+        line.spans.splice(
+            0..0,
+            [
+                Span::styled(
+                    trf(
+                        "help.scanning",
+                        &[("spinner", &frame), ("percent", &percent)],
+                    ),
+                    Style::default().fg(theme.primary()),
+                ),
+                Span::styled("  │  ", Style::default().fg(theme.border())),
+            ],
+        );
+    }
+
+    if view == View::List && indicators.junk_count > 0 {
+        line.spans
+            .push(Span::styled("  │  ", Style::default().fg(theme.border())));
+        line.spans.push(Span::styled(
+            trf("help.junk_count", &[("count", &indicators.junk_count)]),
+            Style::default().fg(theme.warning()),
+        ));
+    }
+
+    if let Some(register) = indicators.recording {
+        line.spans
+            .push(Span::styled("  │  ", Style::default().fg(theme.border())));
+        line.spans.push(Span::styled(
+            trf("help.recording", &[("register", &register)]),
+            Style::default().fg(theme.warning()),
+        ));
+    }
+
+    // Unread counters for every account: [work 3 | personal 12]
+    if !indicators.account_unread.is_empty() {
+        line.spans
+            .push(Span::styled("  │  ", Style::default().fg(theme.border())));
+        line.spans.push(Span::styled("[", muted_style));
+        for (i, (name, count)) in indicators.account_unread.iter().enumerate() {
+            if i > 0 {
+                line.spans.push(Span::styled(" | ", muted_style));
+            }
+            let name_style = if name == indicators.current_account {
+                Style::default().fg(theme.primary())
+            } else {
+                muted_style
+            };
+            let count_style = if *count > 0 {
+                Style::default().fg(theme.unread())
+            } else {
+                muted_style
+            };
+            line.spans.push(Span::styled(name.clone(), name_style));
+            line.spans
+                .push(Span::styled(format!(" {}", count), count_style));
+        }
+        line.spans.push(Span::styled("]", muted_style));
+    }
+
+    // Mailbox size, against the quota when one is set: 3.2 GB of 15.0 GB (21%)
+    if let Some(bytes) = indicators.mailbox_size {
+        line.spans
+            .push(Span::styled("  │  ", Style::default().fg(theme.border())));
+        let size = format_size(bytes);
+        match indicators.quota.filter(|&q| q > 0) {
+            Some(quota) => {
+                let percent = bytes.saturating_mul(100) / quota;
+                let style = if percent >= QUOTA_WARN_PERCENT {
+                    Style::default().fg(theme.warning())
+                } else {
+                    muted_style
+                };
+                line.spans.push(Span::styled(
+                    trf(
+                        "list.quota",
+                        &[
+                            ("size", &size),
+                            ("quota", &format_size(quota)),
+                            ("percent", &percent),
+                        ],
+                    ),
+                    style,
+                ));
+            }
+            None => line.spans.push(Span::styled(size, muted_style)),
+        }
+    }
+
     // Add status message if present
     if let Some(msg) = status {
         line.spans
@@ -0,0 +1,87 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::reader::{render_thread_summary, ThreadEntry};
+use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+
+/// Width of the info column in the three-column layout
+pub const INFO_PANEL_WIDTH: u16 = 44;
+
+/// Most thread messages listed in the info column
+pub const INFO_THREAD_MAX_ROWS: usize = 15;
+
+/// Rows (inside the border) where the info column's thread messages and
+/// attachments start; shared with mouse handling
+pub fn info_rows(thread_len: usize) -> (usize, usize) {
+    if thread_len == 0 {
+        (0, 1)
+    } else {
+        // Heading, messages, blank line, heading
+        (1, thread_len + 3)
+    }
+}
+
+/// Render the third column of the wide layout: the previewed message's thread
+/// and its attachments, which otherwise sit above and inside the preview text
+pub fn render_info_panel(
+    f: &mut Frame,
+    area: Rect,
+    thread: &[ThreadEntry],
+    attachments: &[String],
+    theme: &ThemeConfig,
+) {
+    let block = super::Pane::new(tr("title.info"), false, theme).block();
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let heading = Style::default()
+        .fg(theme.fg_muted())
+        .add_modifier(Modifier::BOLD);
+    let (thread_top, attachments_top) = info_rows(thread.len());
+
+    if !thread.is_empty() {
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                trf("info.thread", &[("count", &thread.len())]),
+                heading,
+            ))),
+            inner,
+        );
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(thread_top as u16),
+                Constraint::Length(thread.len() as u16),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+        render_thread_summary(f, rows[1], thread, theme);
+    }
+
+    let mut lines = vec![Line::from(Span::styled(tr("info.attachments"), heading))];
+    if attachments.is_empty() {
+        lines.push(Line::from(Span::styled(
+            tr("info.no_attachments"),
+            Style::default().fg(theme.fg_muted()),
+        )));
+    }
+    for attachment in attachments {
+        lines.push(Line::from(Span::styled(
+            attachment.trim().to_string(),
+            Style::default().fg(theme.secondary()),
+        )));
+    }
+    let top = (attachments_top as u16 - 1).min(inner.height);
+    let area = Rect {
+        y: inner.y + top,
+        height: inner.height - top,
+        ..inner
+    };
+    f.render_widget(Paragraph::new(lines), area);
+}
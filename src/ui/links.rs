@@ -0,0 +1,57 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::config::ThemeConfig;
+
+/// Keys used for hint labels, home row first
+const HINT_CHARS: &str = "asdfghjkl";
+
+/// A URL on screen with the label that opens it
+pub struct LinkHint {
+    pub label: String,
+    /// Screen position of the URL's first character
+    pub x: u16,
+    pub y: u16,
+    pub url: String,
+}
+
+/// Labels for `count` links: single keys when they suffice, otherwise pairs
+pub fn hint_labels(count: usize) -> Vec<String> {
+    let chars: Vec<char> = HINT_CHARS.chars().collect();
+    if count <= chars.len() {
+        return chars.iter().take(count).map(|c| c.to_string()).collect();
+    }
+    chars
+        .iter()
+        .flat_map(|a| chars.iter().map(move |b| format!("{}{}", a, b)))
+        .take(count)
+        .collect()
+}
+
+/// Draw each hint's label over the start of its URL; labels that no longer
+/// match what has been typed are hidden
+pub fn render_link_hints(f: &mut Frame, hints: &[LinkHint], typed: &str, theme: &ThemeConfig) {
+    let area = f.area();
+    let typed_style = Style::default().fg(theme.fg_muted()).bg(theme.warning());
+    let label_style = Style::default()
+        .fg(theme.bg())
+        .bg(theme.warning())
+        .add_modifier(Modifier::BOLD);
+    for hint in hints.iter().filter(|h| h.label.starts_with(typed)) {
+        let width = hint.label.chars().count() as u16;
+        let rect = Rect::new(hint.x, hint.y, width, 1).intersection(area);
+        if rect.is_empty() {
+            continue;
+        }
+        let line = Line::from(vec![
+            Span::styled(typed.to_string(), typed_style),
+            Span::styled(hint.label[typed.len()..].to_string(), label_style),
+        ]);
+        f.render_widget(Paragraph::new(line), rect);
+    }
+}
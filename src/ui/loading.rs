@@ -8,12 +8,21 @@ use ratatui::{
 
 use super::Modal;
 use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+
+/// What a progress modal shows
+pub struct Progress<'a> {
+    pub title: &'a str,
+    pub message: &'a str,
+    pub current: usize,
+    /// 0 while the total isn't known yet
+    pub total: usize,
+}
 
 /// Render a loading screen with progress bar
 pub fn render_loading(
     f: &mut Frame,
     area: Rect,
-    progress: f32,
     current: usize,
     total: usize,
     message: &str,
@@ -23,8 +32,31 @@ pub fn render_loading(
     let bg_block = Block::default().style(Style::default().bg(theme.bg()));
     f.render_widget(bg_block, area);
 
+    let progress = Progress {
+        title: tr("modal.loading"),
+        message,
+        current,
+        total,
+    };
+    render_progress(f, area, &progress, theme);
+}
+
+/// Render a progress modal over whatever is already drawn
+pub fn render_progress(f: &mut Frame, area: Rect, progress: &Progress, theme: &ThemeConfig) {
+    let Progress {
+        title,
+        message,
+        current,
+        total,
+    } = *progress;
+    let ratio = if total > 0 {
+        current as f64 / total as f64
+    } else {
+        0.0
+    };
+
     // Centered modal
-    let modal = Modal::new(" Loading ", theme);
+    let modal = Modal::new(title, theme);
     let modal_area = modal.centered_rect(50, 7, area);
 
     // Clear the modal area
@@ -56,16 +88,16 @@ pub fn render_loading(
 
     // Progress bar using Gauge widget
     let gauge = Gauge::default()
-        .ratio(progress.clamp(0.0, 1.0) as f64)
+        .ratio(ratio.clamp(0.0, 1.0))
         .gauge_style(Style::default().fg(theme.primary()).bg(theme.bg_element()))
         .use_unicode(true);
     f.render_widget(gauge, chunks[2]);
 
     // Count
     let count_text = if total > 0 {
-        format!("{} / {} messages", current, total)
+        trf("loading.count", &[("current", &current), ("total", &total)])
     } else {
-        "Scanning...".to_string()
+        tr("loading.scanning").to_string()
     };
     let count = Paragraph::new(Line::from(Span::styled(
         count_text,
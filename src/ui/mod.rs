@@ -1,13 +1,37 @@
+mod activity;
+mod agenda;
+mod cleanup;
 mod compose;
+mod contact;
+mod diagnostics;
+mod duplicates;
 mod envelopes;
+mod folders;
 mod help;
+mod info;
+mod links;
 mod loading;
+mod open_with;
 mod pane;
 mod reader;
+mod templates;
+mod threads;
 
+pub use activity::*;
+pub use agenda::*;
+pub use cleanup::*;
 pub use compose::*;
+pub use contact::*;
+pub use diagnostics::*;
+pub use duplicates::*;
 pub use envelopes::*;
+pub use folders::*;
 pub use help::*;
+pub use info::*;
+pub use links::*;
 pub use loading::*;
+pub use open_with::*;
 pub use pane::*;
 pub use reader::*;
+pub use templates::*;
+pub use threads::*;
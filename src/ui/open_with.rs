@@ -0,0 +1,98 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::app::OpenWithMenu;
+use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+
+/// Render the open-with menu: the message's attachments to pick from, then
+/// the chosen one's handlers, the default application and a typed command
+pub fn render_open_with(f: &mut Frame, area: Rect, menu: &OpenWithMenu, theme: &ThemeConfig) {
+    let chosen = menu.chosen.and_then(|i| menu.parts.get(i));
+    // The chosen attachment and a blank line head the handler list
+    let extra = match (chosen, &menu.command) {
+        (Some(_), Some(_)) => 5,
+        (Some(_), None) => 2,
+        (None, _) => 0,
+    };
+    let modal = Modal::new(tr("modal.open_with"), theme);
+    let modal_area = modal.centered_rect(60, (menu.rows() + extra) as u16 + 2, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let key_style = Style::default().fg(theme.primary());
+    let muted = Style::default().fg(theme.fg_muted());
+    let row_style = |i: usize| {
+        if i == menu.selection && menu.command.is_none() {
+            Style::default()
+                .fg(theme.fg())
+                .bg(theme.selected_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_subtle())
+        }
+    };
+    let mut lines = Vec::new();
+
+    let Some(part) = chosen else {
+        for (i, part) in menu.parts.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(part.filename.clone(), row_style(i)),
+                Span::styled(format!("  {}", part.content_type), muted),
+            ]));
+        }
+        f.render_widget(Paragraph::new(lines), inner);
+        return;
+    };
+
+    lines.push(Line::from(vec![
+        Span::styled(
+            part.filename.clone(),
+            Style::default().fg(theme.fg()).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("  {}", part.content_type), muted),
+    ]));
+    lines.push(Line::from(""));
+    for (i, handler) in menu.handlers.iter().enumerate() {
+        lines.push(Line::from(Span::styled(handler.clone(), row_style(i))));
+    }
+    let default_row = menu.handlers.len();
+    lines.push(Line::from(vec![
+        Span::styled(tr("open_with.default"), row_style(default_row)),
+        Span::styled(
+            if menu.handlers.is_empty() {
+                format!(
+                    "  {}",
+                    trf("open_with.none_configured", &[("type", &part.content_type)])
+                )
+            } else {
+                String::new()
+            },
+            muted,
+        ),
+    ]));
+    lines.push(Line::from(Span::styled(
+        tr("open_with.other"),
+        row_style(default_row + 1),
+    )));
+
+    if let Some(ref command) = menu.command {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(tr("open_with.command"), muted)));
+        lines.push(Line::from(vec![
+            Span::styled("> ", key_style),
+            Span::styled(command.clone(), Style::default().fg(theme.fg())),
+            Span::styled("_", key_style),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
@@ -1,102 +1,186 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Paragraph, Wrap},
+    widgets::Paragraph,
     Frame,
 };
 use ratatui_image::{picker::Picker, protocol::StatefulProtocol, StatefulImage};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use super::Pane;
 use crate::config::ThemeConfig;
+use crate::tickets::TicketLinks;
 
 /// Holds the stateful protocol for an image
 pub type ImageState = StatefulProtocol;
 
-/// Extract URLs from content - returns (row, col_start, col_end, url)
-pub fn extract_urls(content: &str) -> Vec<(u16, u16, u16, String)> {
+/// Height of the sender header drawn above the message text
+pub const SENDER_HEADER_HEIGHT: u16 = 3;
+
+/// A clickable URL on screen: (row, col_start, col_end, url)
+pub type UrlSpan = (u16, u16, u16, String);
+
+/// Where the preview text landed after wrapping, for mouse hit-testing
+#[derive(Default)]
+pub struct PreviewLayout {
+    pub urls: Vec<UrlSpan>,
+    /// Source line of each wrapped row
+    pub source_rows: Vec<usize>,
+}
+
+/// Most messages listed in the thread summary above the preview
+pub const THREAD_SUMMARY_MAX_ROWS: usize = 5;
+
+/// One message in the thread summary
+pub struct ThreadEntry {
+    pub from: String,
+    pub date: String,
+    pub unread: bool,
+    /// The message being previewed
+    pub current: bool,
+}
+
+/// Sender avatar and details shown at the top of the preview
+pub struct SenderHeader<'a> {
+    pub from: &'a str,
+    pub date: &'a str,
+    pub initials: &'a str,
+    pub color: Color,
+    /// Fetched avatar image; the initials block is drawn when absent
+    pub image: Option<&'a mut ImageState>,
+}
+
+/// Byte ranges of the URLs in one line of text
+fn find_urls(line: &str) -> Vec<(usize, usize)> {
     let mut urls = Vec::new();
+    let mut search_start = 0;
+    while let Some(start) = line[search_start..]
+        .find("http://")
+        .or_else(|| line[search_start..].find("https://"))
+    {
+        let abs_start = search_start + start;
 
-    for (row, line_str) in content.lines().enumerate() {
-        let mut search_start = 0;
-        while let Some(start) = line_str[search_start..]
-            .find("http://")
-            .or_else(|| line_str[search_start..].find("https://"))
-        {
-            let abs_start = search_start + start;
-
-            // Find end of URL (whitespace or common delimiters)
-            let url_end = line_str[abs_start..]
-                .find(|c: char| c.is_whitespace() || c == '>' || c == ')' || c == ']' || c == '"')
-                .map(|i| abs_start + i)
-                .unwrap_or(line_str.len());
-
-            let url = &line_str[abs_start..url_end];
-            urls.push((
-                row as u16,
-                abs_start as u16,
-                url_end as u16,
-                url.to_string(),
-            ));
-
-            search_start = url_end;
-        }
-    }
+        // Find end of URL (whitespace or common delimiters)
+        let url_end = line[abs_start..]
+            .find(|c: char| c.is_whitespace() || c == '>' || c == ')' || c == ']' || c == '"')
+            .map(|i| abs_start + i)
+            .unwrap_or(line.len());
 
+        urls.push((abs_start, url_end));
+        search_start = url_end;
+    }
     urls
 }
 
-/// Style content with underlined URLs
-fn style_content(content: &str, theme: &ThemeConfig) -> Vec<Line<'static>> {
+/// Split a line into byte ranges at most `width` columns wide,
+/// breaking after a space where possible
+fn wrap_line(line: &str, width: usize) -> Vec<(usize, usize)> {
+    if width == 0 {
+        return vec![(0, line.len())];
+    }
+    let mut segments = Vec::new();
+    let mut start = 0;
+    loop {
+        let rest = &line[start..];
+        // First char that no longer fits; a row always takes at least one
+        let mut used = 0;
+        let overflow = rest.char_indices().find(|&(i, c)| {
+            used += c.width().unwrap_or(0);
+            used > width && i > 0
+        });
+        let Some((limit, _)) = overflow else {
+            segments.push((start, line.len()));
+            return segments;
+        };
+        let limit = start + limit;
+        let end = line[start..limit]
+            .rfind(' ')
+            .map(|i| start + i + 1)
+            .unwrap_or(limit);
+        segments.push((start, end));
+        start = end;
+    }
+}
+
+/// URLs and ticket IDs in one line: byte range and the URL each opens
+fn find_links(line: &str, tickets: &TicketLinks) -> Vec<(usize, usize, String)> {
+    let mut links: Vec<(usize, usize, String)> = find_urls(line)
+        .into_iter()
+        .map(|(start, end)| (start, end, line[start..end].to_string()))
+        .collect();
+    // A ticket ID inside a URL is already part of that link
+    for ticket in tickets.find(line) {
+        if !links.iter().any(|(s, e, _)| ticket.0 < *e && *s < ticket.1) {
+            links.push(ticket);
+        }
+    }
+    links.sort_by_key(|(start, _, _)| *start);
+    links
+}
+
+/// Wrap content to `width` columns and style its URLs, ticket IDs and
+/// `clickable_lines` (source line numbers, e.g. attachment entries) as links.
+/// A URL broken across rows gets one span per row
+pub fn layout_content(
+    content: &str,
+    width: u16,
+    clickable_lines: &[usize],
+    tickets: &TicketLinks,
+    theme: &ThemeConfig,
+) -> (Vec<Line<'static>>, PreviewLayout) {
     let url_style = Style::default()
         .fg(theme.url())
         .add_modifier(Modifier::UNDERLINED);
     let text_style = Style::default().fg(theme.fg());
 
-    content
-        .lines()
-        .map(|line_str| {
+    let mut lines = Vec::new();
+    let mut layout = PreviewLayout::default();
+    for (source_row, line_str) in content.lines().enumerate() {
+        let urls = find_links(line_str, tickets);
+        if clickable_lines.contains(&source_row) {
+            for (seg_start, seg_end) in wrap_line(line_str, width as usize) {
+                layout.source_rows.push(source_row);
+                lines.push(Line::from(Span::styled(
+                    line_str[seg_start..seg_end].to_string(),
+                    url_style,
+                )));
+            }
+            continue;
+        }
+        for (seg_start, seg_end) in wrap_line(line_str, width as usize) {
+            let row = lines.len() as u16;
+            let col = |byte: usize| line_str[seg_start..byte].width() as u16;
             let mut spans = Vec::new();
-            let mut last_end = 0;
-            let mut search_start = 0;
-
-            while let Some(start) = line_str[search_start..]
-                .find("http://")
-                .or_else(|| line_str[search_start..].find("https://"))
-            {
-                let abs_start = search_start + start;
-                let url_end = line_str[abs_start..]
-                    .find(|c: char| {
-                        c.is_whitespace() || c == '>' || c == ')' || c == ']' || c == '"'
-                    })
-                    .map(|i| abs_start + i)
-                    .unwrap_or(line_str.len());
-
-                if abs_start > last_end {
+            let mut last_end = seg_start;
+            for (url_start, url_end, url) in &urls {
+                // Part of this URL that falls on this row
+                let start = (*url_start).max(seg_start);
+                let end = (*url_end).min(seg_end);
+                if start >= end {
+                    continue;
+                }
+                if start > last_end {
                     spans.push(Span::styled(
-                        line_str[last_end..abs_start].to_string(),
+                        line_str[last_end..start].to_string(),
                         text_style,
                     ));
                 }
+                spans.push(Span::styled(line_str[start..end].to_string(), url_style));
+                layout.urls.push((row, col(start), col(end), url.clone()));
+                last_end = end;
+            }
+            if last_end < seg_end || spans.is_empty() {
                 spans.push(Span::styled(
-                    line_str[abs_start..url_end].to_string(),
-                    url_style,
+                    line_str[last_end..seg_end].to_string(),
+                    text_style,
                 ));
-
-                last_end = url_end;
-                search_start = url_end;
-            }
-
-            if last_end < line_str.len() {
-                spans.push(Span::styled(line_str[last_end..].to_string(), text_style));
-            }
-            if spans.is_empty() {
-                spans.push(Span::styled(line_str.to_string(), text_style));
             }
-
-            Line::from(spans)
-        })
-        .collect()
+            layout.source_rows.push(source_row);
+            lines.push(Line::from(spans));
+        }
+    }
+    (lines, layout)
 }
 
 pub fn render_reader(
@@ -108,7 +192,21 @@ pub fn render_reader(
     title: &str,
     theme: &ThemeConfig,
 ) {
-    render_reader_with_images(f, area, content, &mut [], scroll, focused, title, theme);
+    render_reader_with_images(
+        f,
+        area,
+        content,
+        &[],
+        &mut [],
+        &[],
+        None,
+        &TicketLinks::default(),
+        scroll,
+        focused,
+        title,
+        0,
+        theme,
+    );
 }
 
 /// Render reader with optional inline images
@@ -116,23 +214,52 @@ pub fn render_reader_with_images(
     f: &mut Frame,
     area: Rect,
     content: &str,
+    clickable_lines: &[usize],
     image_states: &mut [ImageState],
+    thread: &[ThreadEntry],
+    header: Option<SenderHeader>,
+    tickets: &TicketLinks,
     scroll: u16,
     focused: bool,
     title: &str,
+    max_width: u16,
     theme: &ThemeConfig,
-) {
+) -> PreviewLayout {
     let pane = Pane::new(title, focused, theme);
     let block = pane.block();
-    let inner = block.inner(area);
+    let mut inner = block.inner(area);
     f.render_widget(block, area);
 
+    if !thread.is_empty() {
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(thread.len() as u16), Constraint::Min(0)])
+            .split(inner);
+        render_thread_summary(f, parts[0], thread, theme);
+        inner = parts[1];
+    }
+
+    if let Some(header) = header {
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(SENDER_HEADER_HEIGHT), Constraint::Min(0)])
+            .split(inner);
+        render_sender_header(f, parts[0], header, theme);
+        inner = parts[1];
+    }
+
+    // Long lines are hard to read across a wide pane; the text stays
+    // left-aligned so hit boxes keep their origin
+    if max_width > 0 {
+        inner.width = inner.width.min(max_width);
+    }
+
+    // Wrapped here rather than by the Paragraph so URL hit boxes match the screen
+    let (lines, layout) = layout_content(content, inner.width, clickable_lines, tickets, theme);
+    let paragraph = Paragraph::new(lines).scroll((scroll, 0));
+
     if image_states.is_empty() {
         // Text only - simple case
-        let lines = style_content(content, theme);
-        let paragraph = Paragraph::new(lines)
-            .wrap(Wrap { trim: false })
-            .scroll((scroll, 0));
         f.render_widget(paragraph, inner);
     } else {
         // Mixed content: text then images
@@ -154,10 +281,6 @@ pub fn render_reader_with_images(
             .split(inner);
 
         // Render text
-        let lines = style_content(content, theme);
-        let paragraph = Paragraph::new(lines)
-            .wrap(Wrap { trim: false })
-            .scroll((scroll, 0));
         f.render_widget(paragraph, chunks[0]);
 
         // Render images
@@ -166,6 +289,86 @@ pub fn render_reader_with_images(
             f.render_stateful_widget(image_widget, chunks[i + 1], state);
         }
     }
+    layout
+}
+
+/// Render one line per thread message, highlighting the one being previewed
+pub(super) fn render_thread_summary(
+    f: &mut Frame,
+    area: Rect,
+    thread: &[ThreadEntry],
+    theme: &ThemeConfig,
+) {
+    let lines: Vec<Line> = thread
+        .iter()
+        .map(|entry| {
+            let marker = if entry.current { "▸ " } else { "  " };
+            let (dot, dot_style) = if entry.unread {
+                ("● ", Style::default().fg(theme.unread()))
+            } else {
+                ("  ", Style::default())
+            };
+            let mut from_style = Style::default().fg(theme.fg());
+            let mut line_style = Style::default();
+            if entry.current {
+                from_style = from_style.add_modifier(Modifier::BOLD);
+                line_style = line_style.bg(theme.selected_bg());
+            }
+            Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.primary())),
+                Span::styled(dot, dot_style),
+                Span::styled(entry.from.clone(), from_style),
+                Span::styled(
+                    format!("  {}", entry.date),
+                    Style::default().fg(theme.fg_muted()),
+                ),
+            ])
+            .style(line_style)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// Render the avatar block with sender name and date beside it
+fn render_sender_header(f: &mut Frame, area: Rect, header: SenderHeader, theme: &ThemeConfig) {
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Length(6),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    match header.image {
+        Some(state) => f.render_stateful_widget(StatefulImage::default(), cols[0], state),
+        None => {
+            let block_style = Style::default()
+                .bg(header.color)
+                .fg(theme.bg())
+                .add_modifier(Modifier::BOLD);
+            let avatar = Paragraph::new(vec![
+                Line::from(""),
+                Line::from(header.initials.to_string()),
+                Line::from(""),
+            ])
+            .alignment(Alignment::Center)
+            .style(block_style);
+            f.render_widget(avatar, cols[0]);
+        }
+    }
+
+    let details = Paragraph::new(vec![
+        Line::from(Span::styled(
+            header.from.to_string(),
+            Style::default().fg(theme.fg()).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            header.date.to_string(),
+            Style::default().fg(theme.fg_muted()),
+        )),
+    ]);
+    f.render_widget(details, cols[2]);
 }
 
 /// Create image protocol states from images using the picker
@@ -175,3 +378,17 @@ pub fn create_image_states(images: &[image::DynamicImage], picker: &Picker) -> V
         .map(|img| picker.new_resize_protocol(img.clone()))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_line_counts_columns() {
+        assert_eq!(wrap_line("abc def", 4), [(0, 4), (4, 7)]);
+        // Each of these is two columns wide and three bytes long
+        assert_eq!(wrap_line("日本語", 4), [(0, 6), (6, 9)]);
+        // A char wider than the row still gets a row of its own
+        assert_eq!(wrap_line("日本", 1), [(0, 3), (3, 6)]);
+    }
+}
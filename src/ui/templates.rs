@@ -0,0 +1,58 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+use std::collections::BTreeMap;
+
+use super::Modal;
+use crate::config::ThemeConfig;
+use crate::i18n::tr;
+
+/// Render the reply template picker: numbered names with the first body line
+pub fn render_template_picker(
+    f: &mut Frame,
+    area: Rect,
+    templates: &BTreeMap<String, String>,
+    selection: usize,
+    theme: &ThemeConfig,
+) {
+    let modal = Modal::new(tr("modal.templates"), theme);
+    let modal_area = modal.centered_rect(60, templates.len() as u16 + 2, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let key_style = Style::default().fg(theme.primary());
+    let preview_style = Style::default().fg(theme.fg_muted());
+    let lines: Vec<Line> = templates
+        .iter()
+        .enumerate()
+        .map(|(i, (name, body))| {
+            let name_style = if i == selection {
+                Style::default()
+                    .fg(theme.fg())
+                    .bg(theme.selected_bg())
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.fg_subtle())
+            };
+            let number = if i < 9 {
+                format!("{} ", i + 1)
+            } else {
+                "  ".to_string()
+            };
+            let first_line = body.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+            Line::from(vec![
+                Span::styled(number, key_style),
+                Span::styled(name.clone(), name_style),
+                Span::styled(format!("  {}", first_line), preview_style),
+            ])
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
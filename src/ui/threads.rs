@@ -0,0 +1,132 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph},
+    Frame,
+};
+
+use super::Modal;
+use crate::config::ThemeConfig;
+use crate::i18n::{tr, trf};
+use crate::mail::{ThreadDebugRow, ThreadLink};
+
+/// Most messages shown at once in the threading view
+const MAX_ROWS: usize = 15;
+
+/// Render how the selected thread was built: each message at its raw depth
+/// with the depth it is drawn at, and for the highlighted one its headers,
+/// the link that attached it and why it is indented the way it is
+pub fn render_thread_debug(
+    f: &mut Frame,
+    area: Rect,
+    rows: &[ThreadDebugRow],
+    selection: usize,
+    theme: &ThemeConfig,
+) {
+    let shown = rows.len().min(MAX_ROWS);
+    let modal = Modal::new(tr("modal.thread_debug"), theme);
+    let modal_area = modal.centered_rect(90, shown as u16 + 8, area);
+    f.render_widget(Clear, modal_area);
+    let block = modal.block();
+    let inner = block.inner(modal_area);
+    f.render_widget(block, modal_area);
+
+    let muted = Style::default().fg(theme.fg_muted());
+    let mut lines = vec![Line::from(Span::styled(tr("thread.columns"), muted))];
+
+    // Keep the selection in view
+    let start = selection.saturating_sub(MAX_ROWS - 1);
+    for (i, row) in rows.iter().enumerate().skip(start).take(MAX_ROWS) {
+        let style = if i == selection {
+            Style::default()
+                .fg(theme.fg())
+                .bg(theme.selected_bg())
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.fg_subtle())
+        };
+        let mut spans = vec![
+            Span::styled(
+                format!("{:>3} {:>5}  ", row.raw_depth, row.display_depth),
+                Style::default().fg(theme.primary()),
+            ),
+            Span::styled(
+                format!(
+                    "{}{}",
+                    "  ".repeat(row.raw_depth.min(20)),
+                    row.subject.as_deref().unwrap_or(tr("list.no_subject"))
+                ),
+                style,
+            ),
+        ];
+        if matches!(row.link, ThreadLink::Orphan { .. }) {
+            spans.push(Span::styled(
+                format!("  {}", tr("thread.orphan_tag")),
+                Style::default().fg(theme.error()),
+            ));
+        }
+        if row.duplicate_id {
+            spans.push(Span::styled(
+                format!("  {}", tr("thread.duplicate_tag")),
+                Style::default().fg(theme.warning()),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if let Some(row) = rows.get(selection) {
+        lines.push(Line::from(""));
+        let none = tr("thread.none").to_string();
+        let message_id = row.message_id.as_ref().map(|id| format!("<{}>", id));
+        let in_reply_to = row.in_reply_to.as_ref().map(|id| format!("<{}>", id));
+        lines.push(Line::from(Span::styled(
+            trf(
+                "thread.headers",
+                &[
+                    ("message_id", message_id.as_ref().unwrap_or(&none)),
+                    ("in_reply_to", in_reply_to.as_ref().unwrap_or(&none)),
+                    ("count", &row.references),
+                ],
+            ),
+            muted,
+        )));
+        let link = match &row.link {
+            ThreadLink::Root => tr("thread.root").to_string(),
+            ThreadLink::Orphan { wanted } => trf("thread.orphan", &[("id", wanted)]),
+            ThreadLink::InReplyTo(id) => trf("thread.in_reply_to", &[("id", id)]),
+            ThreadLink::Reference(id) => trf("thread.reference", &[("id", id)]),
+        };
+        lines.push(Line::from(Span::styled(
+            link,
+            Style::default().fg(theme.fg()),
+        )));
+        let indented = row
+            .parent_display_depth
+            .is_some_and(|parent| row.display_depth > parent);
+        let depth = if row.raw_depth == 0 {
+            None
+        } else if row.display_depth == 0 {
+            Some(tr("thread.flat").to_string())
+        } else if indented && row.siblings > 1 {
+            Some(trf("thread.branch", &[("count", &row.siblings)]))
+        } else if indented {
+            Some(tr("thread.indented").to_string())
+        } else if row.siblings > 1 {
+            Some(tr("thread.capped").to_string())
+        } else {
+            Some(tr("thread.chain").to_string())
+        };
+        if let Some(depth) = depth {
+            lines.push(Line::from(Span::styled(depth, muted)));
+        }
+        if row.duplicate_id {
+            lines.push(Line::from(Span::styled(
+                tr("thread.duplicate"),
+                Style::default().fg(theme.warning()),
+            )));
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// List view settings remembered for one account's folder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViewPrefs {
+    pub unread_only: bool,
+    pub sort_by_triage: bool,
+}
+
+/// Local file holding view settings: one `<account>/<folder>\t<unread only>\t<triage sort>`
+/// per line, flags as 0 or 1
+fn prefs_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|p| p.join("mailtui/view_prefs"))
+}
+
+/// View settings by `<account>/<folder>`
+pub fn load() -> HashMap<String, ViewPrefs> {
+    prefs_path().map(|p| load_from(&p)).unwrap_or_default()
+}
+
+fn load_from(path: &Path) -> HashMap<String, ViewPrefs> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let key = fields.next()?;
+            let unread_only = fields.next()? == "1";
+            let sort_by_triage = fields.next()? == "1";
+            Some((
+                key.to_string(),
+                ViewPrefs {
+                    unread_only,
+                    sort_by_triage,
+                },
+            ))
+        })
+        .collect()
+}
+
+pub fn save(prefs: &HashMap<String, ViewPrefs>) -> std::io::Result<()> {
+    match prefs_path() {
+        Some(path) => save_to(&path, prefs),
+        None => Ok(()),
+    }
+}
+
+fn save_to(path: &Path, prefs: &HashMap<String, ViewPrefs>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Folders left at the defaults need no line
+    let mut lines: Vec<String> = prefs
+        .iter()
+        .filter(|(_, p)| **p != ViewPrefs::default())
+        .map(|(key, p)| {
+            format!(
+                "{}\t{}\t{}",
+                key, p.unread_only as u8, p.sort_by_triage as u8
+            )
+        })
+        .collect();
+    lines.sort();
+    std::fs::write(path, lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mailtui/view_prefs");
+        let unread = ViewPrefs {
+            unread_only: true,
+            sort_by_triage: false,
+        };
+        let both = ViewPrefs {
+            unread_only: true,
+            sort_by_triage: true,
+        };
+        let prefs = HashMap::from([
+            ("work/INBOX".to_string(), unread),
+            ("work/[Gmail]/Spam".to_string(), both),
+            ("home/INBOX".to_string(), ViewPrefs::default()),
+        ]);
+        save_to(&path, &prefs).unwrap();
+        // Defaults are left out of the file
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        let loaded = load_from(&path);
+        assert_eq!(loaded["work/INBOX"], unread);
+        assert_eq!(loaded["work/[Gmail]/Spam"], both);
+        // A folder never saved restores to the defaults
+        let restored = loaded.get("home/INBOX").copied().unwrap_or_default();
+        assert_eq!(restored, ViewPrefs::default());
+    }
+}
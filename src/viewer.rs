@@ -0,0 +1,148 @@
+use anyhow::Result;
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::prelude::*;
+use ratatui_image::picker::Picker;
+
+use crate::config::Config;
+use crate::i18n::{self, tr};
+use crate::mail::{preview_text, read_envelope, read_message_content};
+use crate::tickets::TicketLinks;
+use crate::{avatar, platform, ui};
+
+/// `mailtui view <file>`: show one RFC 822 file in the reader, without an
+/// account or maildir
+pub fn run(path: &str) -> Result<()> {
+    let config = Config::load();
+    i18n::init(config.locale.as_deref());
+
+    // Read before touching the terminal so errors print normally
+    let envelope = read_envelope(path)?;
+    let view = config.preview.view_options(
+        envelope
+            .from
+            .as_ref()
+            .map(|from| from.addr.as_str())
+            .unwrap_or(""),
+    );
+    let content = read_message_content(path, view.prefer_html)?;
+    let images: Vec<Vec<u8>> = if view.load_images {
+        content.images.iter().map(|img| img.data.clone()).collect()
+    } else {
+        Vec::new()
+    };
+    let mut preview = preview_text(content);
+    if view.collapse_quotes {
+        preview.collapse_quotes();
+    }
+    // Pattern errors are reported by the main UI; here bad patterns just don't link
+    let (tickets, _) = TicketLinks::from_config(&config.links.tickets);
+
+    enable_raw_mode()?;
+    execute!(std::io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+
+    let picker = Picker::from_query_stdio().unwrap_or_else(|_| Picker::halfblocks());
+    let mut image_states: Vec<ui::ImageState> = images
+        .iter()
+        .filter_map(|data| image::load_from_memory(data).ok())
+        .map(|img| picker.new_resize_protocol(img))
+        .collect();
+
+    let title = envelope
+        .subject
+        .clone()
+        .unwrap_or_else(|| tr("title.message").to_string());
+    let from = envelope.from_display();
+    let date = envelope.date.clone().unwrap_or_default();
+    let addr = envelope
+        .from
+        .as_ref()
+        .map(|a| a.addr.clone())
+        .unwrap_or_default();
+    let name = envelope.from.as_ref().and_then(|a| a.name.clone());
+    let initials = avatar::initials(name.as_deref(), &addr);
+
+    let mut scroll: u16 = 0;
+    let mut area = Rect::default();
+    let mut layout = ui::PreviewLayout::default();
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(1), Constraint::Length(1)])
+                .split(f.area());
+            area = chunks[0];
+            let header = ui::SenderHeader {
+                from: &from,
+                date: &date,
+                initials: &initials,
+                color: avatar::color_for(&addr),
+                image: None,
+            };
+            layout = ui::render_reader_with_images(
+                f,
+                chunks[0],
+                &preview.text,
+                &[],
+                &mut image_states,
+                &[],
+                Some(header),
+                &tickets,
+                scroll,
+                true,
+                &title,
+                config.layout.max_content_width,
+                &config.theme,
+            );
+            ui::render_viewer_help(f, chunks[1], &config.theme);
+        })?;
+
+        match event::read()? {
+            Event::Key(key) => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('j') | KeyCode::Down => scroll = scroll.saturating_add(1),
+                KeyCode::Char('k') | KeyCode::Up => scroll = scroll.saturating_sub(1),
+                KeyCode::Char(' ') | KeyCode::PageDown => {
+                    scroll = scroll.saturating_add(area.height.saturating_sub(3))
+                }
+                KeyCode::PageUp => scroll = scroll.saturating_sub(area.height.saturating_sub(3)),
+                KeyCode::Char('g') | KeyCode::Home => scroll = 0,
+                _ => {}
+            },
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseEventKind::ScrollDown => scroll = scroll.saturating_add(3),
+                MouseEventKind::ScrollUp => scroll = scroll.saturating_sub(3),
+                MouseEventKind::Down(_) => {
+                    // Same hit-testing as the main preview: border + sender header
+                    let text_top = area.y + 1 + ui::SENDER_HEADER_HEIGHT;
+                    let col = mouse.column.saturating_sub(area.x + 1);
+                    if mouse.row >= text_top {
+                        let row = mouse.row - text_top + scroll;
+                        if let Some((_, _, _, url)) = layout
+                            .urls
+                            .iter()
+                            .find(|(r, start, end, _)| *r == row && col >= *start && col < *end)
+                        {
+                            platform::open_url(url);
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+    Ok(())
+}